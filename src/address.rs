@@ -0,0 +1,237 @@
+use std::{
+    error::Error,
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+};
+
+/// The protocol used to talk to the printer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Scheme {
+    #[default]
+    Http,
+    Https,
+}
+
+impl Scheme {
+    /// The port used when the address doesn't specify one
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Scheme::Http => 80,
+            Scheme::Https => 443,
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        })
+    }
+}
+
+/// Why an address couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    Empty,
+
+    /// Only `http` and `https` are supported
+    UnsupportedScheme(String),
+
+    InvalidHost(String),
+
+    /// The port is not a number between 1 and 65535
+    InvalidPort(String),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressError::Empty => write!(f, "The address is empty"),
+            AddressError::UnsupportedScheme(scheme) => {
+                write!(f, "Unsupported scheme `{}`, use http or https", scheme)
+            }
+            AddressError::InvalidHost(host) => write!(f, "Invalid host `{}`", host),
+            AddressError::InvalidPort(port) => write!(f, "Invalid port `{}`", port),
+        }
+    }
+}
+
+impl Error for AddressError {}
+
+/// The address of a printer, parsed from a url like `http://192.168.1.50:8080`,
+/// a host name like `prusa-mk4.local`, a `host:port` string or an ip address.
+///
+/// A url can contain a path, for printers behind a reverse proxy like `https://farm.local/printers/mk4a`.
+///
+/// # Example
+///
+/// ```rust
+/// # use prusa_link_rs::address::{PrinterAddress, Scheme};
+/// let address: PrinterAddress = "https://prusa-mk4.local:8443".parse().unwrap();
+///
+/// assert_eq!(address.scheme, Scheme::Https);
+/// assert_eq!(address.host, "prusa-mk4.local");
+/// assert_eq!(address.port, Some(8443));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrinterAddress {
+    pub scheme: Scheme,
+
+    /// The host name or ip address, ipv6 addresses are wrapped in brackets
+    pub host: String,
+
+    /// The port, if the address specified one
+    pub port: Option<u16>,
+
+    /// The path prefix of the api, without a trailing slash, for example `/printers/mk4a`.
+    /// Empty if the printer is served at the root.
+    pub path: String,
+}
+
+impl PrinterAddress {
+    /// Returns the port of the address, or the default port of the scheme
+    pub fn port_or_default(&self) -> u16 {
+        self.port.unwrap_or(self.scheme.default_port())
+    }
+}
+
+/// Normalizes a path prefix to start with a slash and end without one, `/` becomes empty
+pub(crate) fn normalize_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+impl FromStr for PrinterAddress {
+    type Err = AddressError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        let address = address.trim();
+
+        let (scheme, rest) = match address.split_once("://") {
+            Some((scheme, rest)) => match scheme.to_ascii_lowercase().as_str() {
+                "http" => (Scheme::Http, rest),
+                "https" => (Scheme::Https, rest),
+                _ => return Err(AddressError::UnsupportedScheme(scheme.to_string())),
+            },
+            None => (Scheme::Http, address),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let path = normalize_path(path);
+
+        if authority.is_empty() {
+            return Err(AddressError::Empty);
+        }
+
+        // A bare ipv6 address, which has colons but no port
+        if let Ok(ip) = authority.parse::<Ipv6Addr>() {
+            return Ok(Self {
+                scheme,
+                host: format!("[{}]", ip),
+                port: None,
+                path,
+            });
+        }
+
+        let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+            let (ip, rest) = bracketed
+                .split_once(']')
+                .ok_or_else(|| AddressError::InvalidHost(authority.to_string()))?;
+
+            ip.parse::<Ipv6Addr>()
+                .map_err(|_| AddressError::InvalidHost(ip.to_string()))?;
+
+            (format!("[{}]", ip), rest.strip_prefix(':'))
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host.to_string(), Some(port)),
+                None => (authority.to_string(), None),
+            }
+        };
+
+        let valid_host = !host.is_empty()
+            && (host.starts_with('[')
+                || host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_')));
+        if !valid_host {
+            return Err(AddressError::InvalidHost(host));
+        }
+
+        let port = port
+            .map(|port| {
+                port.parse::<u16>()
+                    .ok()
+                    .filter(|port| *port != 0)
+                    .ok_or_else(|| AddressError::InvalidPort(port.to_string()))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            scheme,
+            host,
+            port,
+            path,
+        })
+    }
+}
+
+impl From<IpAddr> for PrinterAddress {
+    fn from(ip: IpAddr) -> Self {
+        let host = match ip {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{}]", ip),
+        };
+
+        Self {
+            scheme: Scheme::Http,
+            host,
+            port: None,
+            path: String::new(),
+        }
+    }
+}
+
+impl From<Ipv4Addr> for PrinterAddress {
+    fn from(ip: Ipv4Addr) -> Self {
+        IpAddr::V4(ip).into()
+    }
+}
+
+impl From<Ipv6Addr> for PrinterAddress {
+    fn from(ip: Ipv6Addr) -> Self {
+        IpAddr::V6(ip).into()
+    }
+}
+
+impl From<SocketAddr> for PrinterAddress {
+    fn from(socket: SocketAddr) -> Self {
+        Self {
+            port: Some(socket.port()),
+            ..socket.ip().into()
+        }
+    }
+}
+
+impl fmt::Display for PrinterAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+
+        f.write_str(&self.path)
+    }
+}
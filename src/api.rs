@@ -0,0 +1,307 @@
+//! The `PrinterApi` trait, so code using a printer can be tested against a fake one.
+
+use std::{error::Error, future::Future, time::Duration};
+
+use crate::{
+    error::ConnectionError,
+    raw_info::RawInfo,
+    raw_job::{FileRefs, JobId, RawJob, ThumbnailSize},
+    raw_printer::RawPrinter,
+    raw_status::RawStatus,
+    raw_storage::StorageInfo,
+    storage::Storage,
+    units::Celsius,
+    Printer,
+};
+
+/// The requests a printer can answer, implemented by `Printer`.
+///
+/// Accept `impl PrinterApi` instead of `Printer` to test your code with a hand-written fake,
+/// without a printer or a mock server. Only the endpoints have to be implemented,
+/// the convenience getters like `is_printing()` are built on top of them.
+/// `Printer` overrides the getters to use its cache.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::api::PrinterApi;
+///
+/// async fn report(printer: &mut impl PrinterApi) -> String {
+///     match printer.progress_percent().await {
+///         Ok(Some(progress)) => format!("{:.0}% done", progress),
+///         Ok(None) => "Not printing".to_string(),
+///         Err(err) => format!("Printer unavailable: {}", err),
+///     }
+/// }
+/// ```
+pub trait PrinterApi {
+    /// See `Printer::get_version()`
+    fn get_version(&self) -> impl Future<Output = Result<String, Box<dyn Error>>>;
+
+    /// See `Printer::check_connection()`
+    fn check_connection(&self) -> impl Future<Output = Result<(), ConnectionError>>;
+
+    /// See `Printer::get_printer_info()`
+    fn get_printer_info(&mut self) -> impl Future<Output = Result<RawPrinter, Box<dyn Error>>>;
+
+    /// See `Printer::get_job_info()`
+    fn get_job_info(&self) -> impl Future<Output = Result<Option<RawJob>, Box<dyn Error>>>;
+
+    /// See `Printer::get_job()`
+    fn get_job(&self, id: JobId) -> impl Future<Output = Result<Option<RawJob>, Box<dyn Error>>>;
+
+    /// See `Printer::pause_job()`
+    fn pause_job(&self, id: JobId) -> impl Future<Output = Result<(), Box<dyn Error>>>;
+
+    /// See `Printer::resume_job()`
+    fn resume_job(&self, id: JobId) -> impl Future<Output = Result<(), Box<dyn Error>>>;
+
+    /// See `Printer::continue_job()`
+    fn continue_job(&self, id: JobId) -> impl Future<Output = Result<(), Box<dyn Error>>>;
+
+    /// See `Printer::stop_job()`
+    fn stop_job(&self, id: JobId) -> impl Future<Output = Result<(), Box<dyn Error>>>;
+
+    /// See `Printer::get_status()`
+    fn get_status(&self) -> impl Future<Output = Result<RawStatus, Box<dyn Error>>>;
+
+    /// See `Printer::get_info()`
+    fn get_info(&self) -> impl Future<Output = Result<RawInfo, Box<dyn Error>>>;
+
+    /// See `Printer::get_storage_info()`
+    fn get_storage_info(&self) -> impl Future<Output = Result<Vec<StorageInfo>, Box<dyn Error>>>;
+
+    /// See `Printer::get_thumbnail()`
+    fn get_thumbnail(
+        &self,
+        refs: &FileRefs,
+        size: ThumbnailSize,
+    ) -> impl Future<Output = Result<Option<Vec<u8>>, Box<dyn Error>>>;
+
+    /// See `Printer::upload_file()`
+    fn upload_file(
+        &self,
+        storage: &Storage,
+        path: &str,
+        data: Vec<u8>,
+    ) -> impl Future<Output = Result<(), Box<dyn Error>>>;
+
+    /// See `Printer::get_nozzle_temp()`
+    fn get_nozzle_temp(&mut self) -> impl Future<Output = Result<Celsius, Box<dyn Error>>> {
+        async move { Ok(self.get_printer_info().await?.get_nozzle_temp()) }
+    }
+
+    /// See `Printer::get_bed_temp()`
+    fn get_bed_temp(&mut self) -> impl Future<Output = Result<Celsius, Box<dyn Error>>> {
+        async move { Ok(self.get_printer_info().await?.get_bed_temp()) }
+    }
+
+    /// See `Printer::get_tool_temp()`
+    fn get_tool_temp(
+        &mut self,
+        index: usize,
+    ) -> impl Future<Output = Result<Option<Celsius>, Box<dyn Error>>> {
+        async move { Ok(self.get_printer_info().await?.get_tool_temp(index)) }
+    }
+
+    /// See `Printer::fan_speeds()`
+    fn fan_speeds(
+        &mut self,
+    ) -> impl Future<Output = Result<(Option<u32>, Option<u32>), Box<dyn Error>>> {
+        async move {
+            let status = self.get_status().await?;
+
+            Ok((status.get_fan_hotend(), status.get_fan_print()))
+        }
+    }
+
+    /// See `Printer::hotend_fan_stalled()`
+    fn hotend_fan_stalled(&mut self) -> impl Future<Output = Result<bool, Box<dyn Error>>> {
+        async move {
+            Ok(self
+                .get_status()
+                .await?
+                .hotend_fan_stalled()
+                .unwrap_or(false))
+        }
+    }
+
+    /// See `Printer::speed_and_flow()`
+    fn speed_and_flow(
+        &mut self,
+    ) -> impl Future<Output = Result<(Option<u32>, Option<u32>), Box<dyn Error>>> {
+        async move {
+            let status = self.get_status().await?;
+
+            Ok((status.get_speed_factor(), status.get_flow_factor()))
+        }
+    }
+
+    /// See `Printer::has_mmu()`
+    fn has_mmu(&mut self) -> impl Future<Output = Result<bool, Box<dyn Error>>> {
+        async move { Ok(self.get_info().await?.mmu.unwrap_or(false)) }
+    }
+
+    /// See `Printer::active_slot()`
+    fn active_slot(&mut self) -> impl Future<Output = Result<Option<u32>, Box<dyn Error>>> {
+        async move { Ok(self.get_status().await?.get_active_slot()) }
+    }
+
+    /// See `Printer::filament_detected()`
+    fn filament_detected(&mut self) -> impl Future<Output = Result<Option<bool>, Box<dyn Error>>> {
+        async move { Ok(self.get_status().await?.get_filament_detected()) }
+    }
+
+    /// See `Printer::is_printing()`
+    fn is_printing(&mut self) -> impl Future<Output = Result<bool, Box<dyn Error>>> {
+        async move { Ok(self.get_printer_info().await?.get_printing()) }
+    }
+
+    /// See `Printer::is_idle()`
+    fn is_idle(&mut self) -> impl Future<Output = Result<bool, Box<dyn Error>>> {
+        async move { Ok(self.get_printer_info().await?.is_idle()) }
+    }
+
+    /// See `Printer::has_error()`
+    fn has_error(&mut self) -> impl Future<Output = Result<bool, Box<dyn Error>>> {
+        async move { Ok(self.get_printer_info().await?.has_error()) }
+    }
+
+    /// See `Printer::progress_percent()`
+    fn progress_percent(&mut self) -> impl Future<Output = Result<Option<f32>, Box<dyn Error>>> {
+        async move { Ok(self.get_job_info().await?.map(|job| job.get_progress())) }
+    }
+
+    /// See `Printer::time_remaining()`
+    fn time_remaining(&mut self) -> impl Future<Output = Result<Option<Duration>, Box<dyn Error>>> {
+        async move {
+            Ok(self
+                .get_job_info()
+                .await?
+                .and_then(|job| job.get_time_remaining()))
+        }
+    }
+}
+
+impl PrinterApi for Printer {
+    async fn get_version(&self) -> Result<String, Box<dyn Error>> {
+        Printer::get_version(self).await
+    }
+
+    async fn check_connection(&self) -> Result<(), ConnectionError> {
+        Printer::check_connection(self).await
+    }
+
+    async fn get_printer_info(&mut self) -> Result<RawPrinter, Box<dyn Error>> {
+        Printer::get_printer_info(self).await
+    }
+
+    async fn get_job_info(&self) -> Result<Option<RawJob>, Box<dyn Error>> {
+        Printer::get_job_info(self).await
+    }
+
+    async fn get_job(&self, id: JobId) -> Result<Option<RawJob>, Box<dyn Error>> {
+        Printer::get_job(self, id).await
+    }
+
+    async fn pause_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        Printer::pause_job(self, id).await
+    }
+
+    async fn resume_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        Printer::resume_job(self, id).await
+    }
+
+    async fn continue_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        Printer::continue_job(self, id).await
+    }
+
+    async fn stop_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        Printer::stop_job(self, id).await
+    }
+
+    async fn get_status(&self) -> Result<RawStatus, Box<dyn Error>> {
+        Printer::get_status(self).await
+    }
+
+    async fn get_info(&self) -> Result<RawInfo, Box<dyn Error>> {
+        Printer::get_info(self).await
+    }
+
+    async fn get_storage_info(&self) -> Result<Vec<StorageInfo>, Box<dyn Error>> {
+        Printer::get_storage_info(self).await
+    }
+
+    async fn get_thumbnail(
+        &self,
+        refs: &FileRefs,
+        size: ThumbnailSize,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        Printer::get_thumbnail(self, refs, size).await
+    }
+
+    async fn upload_file(
+        &self,
+        storage: &Storage,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        Printer::upload_file(self, storage, path, data).await
+    }
+
+    async fn get_nozzle_temp(&mut self) -> Result<Celsius, Box<dyn Error>> {
+        Printer::get_nozzle_temp(self).await
+    }
+
+    async fn get_bed_temp(&mut self) -> Result<Celsius, Box<dyn Error>> {
+        Printer::get_bed_temp(self).await
+    }
+
+    async fn get_tool_temp(&mut self, index: usize) -> Result<Option<Celsius>, Box<dyn Error>> {
+        Printer::get_tool_temp(self, index).await
+    }
+
+    async fn fan_speeds(&mut self) -> Result<(Option<u32>, Option<u32>), Box<dyn Error>> {
+        Printer::fan_speeds(self).await
+    }
+
+    async fn hotend_fan_stalled(&mut self) -> Result<bool, Box<dyn Error>> {
+        Printer::hotend_fan_stalled(self).await
+    }
+
+    async fn speed_and_flow(&mut self) -> Result<(Option<u32>, Option<u32>), Box<dyn Error>> {
+        Printer::speed_and_flow(self).await
+    }
+
+    async fn has_mmu(&mut self) -> Result<bool, Box<dyn Error>> {
+        Printer::has_mmu(self).await
+    }
+
+    async fn active_slot(&mut self) -> Result<Option<u32>, Box<dyn Error>> {
+        Printer::active_slot(self).await
+    }
+
+    async fn filament_detected(&mut self) -> Result<Option<bool>, Box<dyn Error>> {
+        Printer::filament_detected(self).await
+    }
+
+    async fn is_printing(&mut self) -> Result<bool, Box<dyn Error>> {
+        Printer::is_printing(self).await
+    }
+
+    async fn is_idle(&mut self) -> Result<bool, Box<dyn Error>> {
+        Printer::is_idle(self).await
+    }
+
+    async fn has_error(&mut self) -> Result<bool, Box<dyn Error>> {
+        Printer::has_error(self).await
+    }
+
+    async fn progress_percent(&mut self) -> Result<Option<f32>, Box<dyn Error>> {
+        Printer::progress_percent(self).await
+    }
+
+    async fn time_remaining(&mut self) -> Result<Option<Duration>, Box<dyn Error>> {
+        Printer::time_remaining(self).await
+    }
+}
@@ -0,0 +1,276 @@
+//! `prusalink-top`, a terminal dashboard for PrusaLink printers, built with the `tui` feature.
+//!
+//! Every printer is given as an address or as `name=address`, the api key is read from `--api-key`
+//! or from `PRUSALINK_API_KEY`, which can also be set in a `.env` file.
+//!
+//! ```text
+//! prusalink-top 192.168.1.50
+//! prusalink-top mk4=192.168.1.50 mini=prusa-mini.local --interval 5
+//! ```
+//!
+//! Press `q` or `Esc` to quit.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    error::Error,
+    io,
+    process::ExitCode,
+    time::Duration,
+};
+
+use clap::Parser;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use prusa_link_rs::{
+    events::{diff_events, Heater, PrinterEvent},
+    farm::{Farm, PrinterSnapshot},
+    PrinterBuilder,
+};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Gauge, List, Paragraph},
+};
+
+/// How many events are kept for the event log
+const EVENT_LOG_LEN: usize = 50;
+
+#[derive(Parser)]
+#[command(
+    name = "prusalink-top",
+    version,
+    about = "Watch PrusaLink printers in the terminal"
+)]
+struct Cli {
+    /// The printers to watch, as an address or as name=address
+    #[arg(required = true)]
+    printers: Vec<String>,
+
+    /// The api key shown in the network settings of the printers
+    #[arg(short = 'k', long, env = "PRUSALINK_API_KEY", hide_env_values = true)]
+    api_key: String,
+
+    /// Seconds between two refreshes of the printers
+    #[arg(short, long, default_value_t = 2)]
+    interval: u64,
+}
+
+/// What the dashboard shows, updated after every refresh
+#[derive(Default)]
+struct Dashboard {
+    snapshots: BTreeMap<String, PrinterSnapshot>,
+    errors: BTreeMap<String, String>,
+    events: VecDeque<String>,
+}
+
+impl Dashboard {
+    /// Takes the state of the farm after a refresh, logging the events since the last one
+    fn update(&mut self, farm: &Farm, errors: BTreeMap<String, Box<dyn Error>>) {
+        for (name, snapshot) in farm.snapshots() {
+            if let Some(previous) = self.snapshots.get(name) {
+                for event in diff_events(&previous.printer, &snapshot.printer) {
+                    self.log(name, &event);
+                }
+            }
+
+            self.snapshots.insert(name.to_string(), snapshot);
+        }
+
+        for (name, err) in &errors {
+            if !self.errors.contains_key(name) {
+                self.log(name, &PrinterEvent::RefreshFailed(err.to_string()));
+            }
+        }
+
+        self.errors = errors
+            .into_iter()
+            .map(|(name, err)| (name, err.to_string()))
+            .collect();
+    }
+
+    fn log(&mut self, name: &str, event: &PrinterEvent) {
+        let text = match event {
+            PrinterEvent::StateChanged { from, to } => format!("{} -> {}", from, to),
+            PrinterEvent::PrintStarted => "print started".to_string(),
+            PrinterEvent::PrintFinished => "print finished".to_string(),
+            PrinterEvent::PrintFailed => "print failed".to_string(),
+            PrinterEvent::TemperatureReached {
+                heater,
+                temperature,
+            } => {
+                let heater = match heater {
+                    Heater::Nozzle => "nozzle",
+                    Heater::Bed => "bed",
+                };
+                format!("{} reached {:.0}", heater, temperature)
+            }
+            PrinterEvent::RefreshFailed(err) => format!("unavailable: {}", err),
+        };
+
+        self.events.push_front(format!("{}: {}", name, text));
+        self.events.truncate(EVENT_LOG_LEN);
+    }
+
+    fn render(&self, names: &[String], frame: &mut Frame) {
+        let [printers, events] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(8)]).areas(frame.size());
+
+        let rows = Layout::vertical(names.iter().map(|_| Constraint::Length(5))).split(printers);
+
+        for (name, area) in names.iter().zip(rows.iter()) {
+            self.render_printer(name, *area, frame);
+        }
+
+        let log = List::new(self.events.iter().map(String::as_str))
+            .block(Block::default().borders(Borders::ALL).title("Events"));
+        frame.render_widget(log, events);
+    }
+
+    fn render_printer(&self, name: &str, area: Rect, frame: &mut Frame) {
+        let block = Block::default().borders(Borders::ALL);
+
+        let Some(snapshot) = self.snapshots.get(name) else {
+            let text = self
+                .errors
+                .get(name)
+                .map_or("connecting...", String::as_str);
+
+            frame.render_widget(
+                Paragraph::new(text).block(block.title(name.to_string())),
+                area,
+            );
+            return;
+        };
+
+        let printer = &snapshot.printer;
+        let mut title = format!("{} - {}", name, printer.link_state());
+        if self.errors.contains_key(name) {
+            title.push_str(" (unavailable)");
+        }
+
+        let inner = block.inner(area);
+        frame.render_widget(block.title(title), area);
+
+        let [temps, progress] =
+            Layout::vertical([Constraint::Length(2), Constraint::Length(1)]).areas(inner);
+
+        let temps_text = vec![
+            Line::from(format!(
+                "Nozzle {:>5.1}°C / {:>5.1}°C",
+                printer.get_nozzle_temp().value(),
+                printer.get_target_nozzle_temp().value()
+            )),
+            Line::from(format!(
+                "Bed    {:>5.1}°C / {:>5.1}°C",
+                printer.get_bed_temp().value(),
+                printer.get_target_bed_temp().value()
+            )),
+        ];
+        frame.render_widget(Paragraph::new(temps_text), temps);
+
+        if let Some(job) = &snapshot.job {
+            let percent = job.get_progress().clamp(0.0, 100.0);
+            let label = match job.get_time_remaining().map(|time| time.as_secs()) {
+                Some(remaining) => format!(
+                    "{:.0}% - {}h {:02}m left",
+                    percent,
+                    remaining / 3600,
+                    remaining % 3600 / 60
+                ),
+                None => format!("{:.0}%", percent),
+            };
+
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(Color::Green))
+                .ratio(f64::from(percent) / 100.0)
+                .label(label);
+            frame.render_widget(gauge, progress);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenv::dotenv().ok();
+
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let mut farm = Farm::new();
+    let mut names = Vec::new();
+
+    for printer in &cli.printers {
+        let (name, address) = printer.split_once('=').unwrap_or((printer, printer));
+
+        farm.add(
+            name,
+            PrinterBuilder::new(address, cli.api_key.as_str()).try_build()?,
+        );
+        names.push(name.to_string());
+    }
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    enable_raw_mode()?;
+    io::stdout().execute(EnterAlternateScreen)?;
+
+    let result = watch(
+        &mut terminal,
+        &mut farm,
+        &names,
+        Duration::from_secs(cli.interval),
+    )
+    .await;
+
+    disable_raw_mode()?;
+    io::stdout().execute(LeaveAlternateScreen)?;
+
+    result
+}
+
+/// Refreshes the farm and draws the dashboard until a quit key is pressed
+async fn watch(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    farm: &mut Farm,
+    names: &[String],
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let (quit_tx, mut quit) = tokio::sync::mpsc::channel(1);
+
+    // Reading the keyboard blocks, so it gets a thread of its own
+    std::thread::spawn(move || loop {
+        if let Ok(Event::Key(key)) = event::read() {
+            if key.kind == KeyEventKind::Press
+                && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+            {
+                let _ = quit_tx.blocking_send(());
+                return;
+            }
+        }
+    });
+
+    let mut dashboard = Dashboard::default();
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        terminal.draw(|frame| dashboard.render(names, frame))?;
+
+        tokio::select! {
+            _ = ticker.tick() => {
+                let errors = farm.refresh_errors().await;
+                dashboard.update(farm, errors);
+            }
+            _ = quit.recv() => return Ok(()),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! `prusalink`, a command line tool for PrusaLink printers, built with the `cli` feature.
+//!
+//! The address and api key are read from `--address` and `--api-key`,
+//! or from `PRUSALINK_ADDRESS` and `PRUSALINK_API_KEY`, which can also be set in a `.env` file.
+//!
+//! ```text
+//! prusalink status
+//! prusalink files ls --storage usb projects
+//! prusalink print plates/plate_1.bgcode
+//! ```
+
+use std::{error::Error, path::PathBuf, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand};
+use prusa_link_rs::{
+    error::PrinterError, raw_job::RawJob, storage::Storage, Printer, PrinterBuilder,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "prusalink",
+    version,
+    about = "Control PrusaLink printers from the command line"
+)]
+struct Cli {
+    /// The address of the printer, for example 192.168.1.50 or http://prusa-mk4.local:8080
+    #[arg(short, long, env = "PRUSALINK_ADDRESS", global = true)]
+    address: Option<String>,
+
+    /// The api key shown in the network settings of the printer
+    #[arg(
+        short = 'k',
+        long,
+        env = "PRUSALINK_API_KEY",
+        hide_env_values = true,
+        global = true
+    )]
+    api_key: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Shows the state of the printer and its job
+    Status,
+
+    /// Shows the nozzle and bed temperatures
+    Temps,
+
+    /// Works with the files on the printer
+    #[command(subcommand)]
+    Files(FilesCommand),
+
+    /// Uploads a local file to the printer
+    Upload(UploadArgs),
+
+    /// Uploads a local file to the printer and starts printing it
+    Print(UploadArgs),
+
+    /// Pauses the current job
+    Pause,
+
+    /// Resumes the paused job
+    Resume,
+
+    /// Stops the current job
+    Stop,
+}
+
+#[derive(Subcommand)]
+enum FilesCommand {
+    /// Lists the files in a folder of a storage
+    Ls {
+        /// The storage to list, for example usb or local
+        #[arg(short, long, default_value = "usb")]
+        storage: Storage,
+
+        /// The folder to list, the root of the storage by default
+        #[arg(default_value = "")]
+        path: String,
+    },
+}
+
+#[derive(Args)]
+struct UploadArgs {
+    /// The file on this computer
+    file: PathBuf,
+
+    /// The storage to upload to, for example usb or local
+    #[arg(short, long, default_value = "usb")]
+    storage: Storage,
+
+    /// The path on the storage, the name of the local file by default
+    #[arg(short, long)]
+    path: Option<String>,
+}
+
+impl UploadArgs {
+    fn remote_path(&self) -> String {
+        self.path.clone().unwrap_or_else(|| {
+            self.file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenv::dotenv().ok();
+
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let address = cli
+        .address
+        .ok_or("No address given, use --address or PRUSALINK_ADDRESS")?;
+    let api_key = cli
+        .api_key
+        .ok_or("No api key given, use --api-key or PRUSALINK_API_KEY")?;
+
+    let mut printer = PrinterBuilder::new(address, api_key).try_build()?;
+
+    match cli.command {
+        Command::Status => status(&mut printer).await,
+        Command::Temps => temps(&mut printer).await,
+        Command::Files(FilesCommand::Ls { storage, path }) => {
+            for entry in printer.list_files(&storage, &path).await? {
+                match (entry.is_folder(), entry.size) {
+                    (true, _) => println!("{}/", entry.display_name()),
+                    (false, Some(size)) => println!("{:>12}  {}", size, entry.display_name()),
+                    (false, None) => println!("{:>12}  {}", "", entry.display_name()),
+                }
+            }
+
+            Ok(())
+        }
+        Command::Upload(args) => {
+            let path = args.remote_path();
+            printer
+                .upload_local_file(&args.storage, &path, &args.file)
+                .await?;
+
+            println!(
+                "Uploaded {} to /{}/{}",
+                args.file.display(),
+                args.storage,
+                path
+            );
+            Ok(())
+        }
+        Command::Print(args) => {
+            let path = args.remote_path();
+            let job = printer
+                .upload_and_print(&args.storage, &path, args.file.as_path())
+                .await?;
+
+            println!(
+                "Printing /{}/{} as job {}",
+                args.storage,
+                path,
+                job.get_id()
+            );
+            Ok(())
+        }
+        Command::Pause => {
+            let job = current_job(&printer).await?;
+            printer.pause_job(job.get_id()).await
+        }
+        Command::Resume => {
+            let job = current_job(&printer).await?;
+            printer.resume_job(job.get_id()).await
+        }
+        Command::Stop => {
+            let job = current_job(&printer).await?;
+            printer.stop_job(job.get_id()).await
+        }
+    }
+}
+
+async fn status(printer: &mut Printer) -> Result<(), Box<dyn Error>> {
+    let info = printer.get_printer_info().await?;
+    println!("State:     {}", info.get_state_text());
+
+    let job = match printer.get_job_info().await {
+        Err(err) if matches!(err.downcast_ref(), Some(PrinterError::Unsupported(_))) => None,
+        result => result?,
+    };
+
+    if let Some(job) = job {
+        let file = job
+            .get_file()
+            .map(|file| file.display_name.as_deref().unwrap_or(&file.name))
+            .unwrap_or("unknown file");
+
+        println!("Job:       {} ({})", job.get_id(), file);
+        println!("Progress:  {:.0}%", job.get_progress());
+
+        if let Some(remaining) = job.get_time_remaining().map(|time| time.as_secs()) {
+            println!(
+                "Remaining: {}h {:02}m",
+                remaining / 3600,
+                remaining % 3600 / 60
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn temps(printer: &mut Printer) -> Result<(), Box<dyn Error>> {
+    let info = printer.get_printer_info().await?;
+
+    println!(
+        "Nozzle: {} / {}",
+        info.get_nozzle_temp(),
+        info.get_target_nozzle_temp()
+    );
+    println!(
+        "Bed:    {} / {}",
+        info.get_bed_temp(),
+        info.get_target_bed_temp()
+    );
+
+    Ok(())
+}
+
+/// Returns the job the printer is running, or an error if there is none
+async fn current_job(printer: &Printer) -> Result<RawJob, Box<dyn Error>> {
+    printer
+        .get_job_info()
+        .await?
+        .ok_or_else(|| "The printer has no job".into())
+}
@@ -0,0 +1,675 @@
+//! A blocking version of `Printer`, for scripts and command line tools that don't use async.
+//!
+//! Enabled with the `blocking` feature. Every function mirrors the async one of the same name,
+//! the requests run on a single threaded runtime owned by the printer,
+//! the same way `reqwest::blocking` works.
+//!
+//! ```no_run
+//! use prusa_link_rs::PrinterBuilder;
+//!
+//! let mut printer = PrinterBuilder::new("192.168.1.50", "api_key")
+//!     .build_blocking()
+//!     .unwrap();
+//!
+//! println!("Nozzle is at {}", printer.get_nozzle_temp().unwrap());
+//! ```
+//!
+//! The functions panic if they are called from within an async runtime, use the async `Printer` there.
+
+#[cfg(target_arch = "wasm32")]
+compile_error!("the blocking feature is not available on wasm, use the async Printer");
+
+use std::{error::Error, path::Path, time::Duration};
+
+use crate::{
+    capabilities::Capabilities,
+    cleanup::{CleanupPolicy, CleanupReport},
+    connect::{ConnectServer, ConnectStatus, RegistrationCode},
+    error::{BuildError, ConnectionError},
+    files::FileTree,
+    firmware::FirmwareUpdateStatus,
+    health::Health,
+    history::TelemetryHistory,
+    job_check::JobExpectations,
+    logs::LogFile,
+    material::Material,
+    model::{PrinterIdentity, PrinterModel},
+    raw_info::RawInfo,
+    raw_job::{FileRefs, JobId, RawJob, ThumbnailSize},
+    raw_network::NetworkInfo,
+    raw_printer::{LinkState, PrinterFlags, RawPrinter, TemperatureView},
+    raw_status::{PrinterErrorInfo, RawStatus},
+    raw_storage::{FileDetail, StorageInfo},
+    ready::ReadyCheck,
+    stats::RequestStats,
+    storage::Storage,
+    units::Celsius,
+    upload::UploadSource,
+};
+
+/// Contains all the information about the printer, like `crate::Printer` but with blocking functions
+#[derive(Debug)]
+pub struct Printer {
+    printer: crate::Printer,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Printer {
+    /// Wraps an async printer, creating the runtime its requests run on
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the runtime can't be created.
+    pub fn new(printer: crate::Printer) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self { printer, runtime })
+    }
+
+    /// Returns the async printer, for example to move it to an async runtime
+    pub fn into_async(self) -> crate::Printer {
+        self.printer
+    }
+
+    /// Returns a reference to the address string
+    pub fn address(&self) -> &str {
+        self.printer.address()
+    }
+
+    /// Returns the api key, see `crate::Printer::expose_api_key()`
+    pub fn expose_api_key(&self) -> &str {
+        self.printer.expose_api_key()
+    }
+
+    /// Returns the recorded telemetry history,
+    /// or None if it wasn't enabled with `PrinterBuilder::telemetry_history()`
+    pub fn telemetry_history(&self) -> Option<&TelemetryHistory> {
+        self.printer.telemetry_history()
+    }
+
+    /// Returns the cached printer information without refreshing it,
+    /// or None if the printer hasn't been refreshed yet
+    pub fn cached(&self) -> Option<&RawPrinter> {
+        self.printer.cached()
+    }
+
+    /// Returns how the requests to the printer went recently
+    pub fn health(&self) -> Health {
+        self.printer.health()
+    }
+
+    /// Returns the number of requests sent to every endpoint of the printer,
+    /// how many of them failed and how long they took
+    pub fn stats(&self) -> RequestStats {
+        self.printer.stats()
+    }
+
+    /// Starts counting the requests of `stats()` from zero again
+    pub fn reset_stats(&self) {
+        self.printer.reset_stats()
+    }
+
+    /// Returns true if the cached getters are returning information from before an outage,
+    /// see `PrinterBuilder::serve_stale()`
+    pub fn is_stale(&self) -> bool {
+        self.printer.is_stale()
+    }
+
+    /// Blocking version of `crate::Printer::change_address()`
+    pub fn change_address(&mut self, address: &str) -> Result<(), BuildError> {
+        self.printer.change_address(address)
+    }
+
+    /// changes the APIs api key
+    pub fn change_api_key(&mut self, api_key: String) {
+        self.printer.change_api_key(api_key);
+    }
+
+    /// Blocking version of `crate::Printer::rotate_api_key()`
+    pub fn rotate_api_key(
+        &mut self,
+        api_key: impl Into<String>,
+        verify: bool,
+    ) -> Result<(), ConnectionError> {
+        self.runtime
+            .block_on(self.printer.rotate_api_key(api_key, verify))
+    }
+
+    /// Blocking version of `crate::Printer::get_version()`
+    pub fn get_version(&self) -> Result<String, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_version())
+    }
+
+    /// Blocking version of `crate::Printer::check_connection()`
+    pub fn check_connection(&self) -> Result<(), ConnectionError> {
+        self.runtime.block_on(self.printer.check_connection())
+    }
+
+    /// Blocking version of `crate::Printer::capabilities()`
+    pub fn capabilities(&self) -> Result<&Capabilities, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.capabilities())
+    }
+
+    /// Blocking version of `crate::Printer::identity()`
+    pub fn identity(&self) -> Result<&PrinterIdentity, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.identity())
+    }
+
+    /// Blocking version of `crate::Printer::require_min_version()`
+    pub fn require_min_version(&self, min_version: &str) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.require_min_version(min_version))
+    }
+
+    /// Blocking version of `crate::Printer::model()`
+    pub fn model(&self) -> Result<PrinterModel, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.model())
+    }
+
+    /// Blocking version of `crate::Printer::cached_or_refresh()`
+    pub fn cached_or_refresh(&mut self) -> Result<&RawPrinter, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.cached_or_refresh())
+    }
+
+    /// Blocking version of `crate::Printer::get_printer_info()`
+    pub fn get_printer_info(&mut self) -> Result<RawPrinter, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_printer_info())
+    }
+
+    /// Blocking version of `crate::Printer::refresh()`
+    pub fn refresh(&mut self) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.refresh())
+    }
+
+    /// Blocking version of `crate::Printer::get_job_info()`
+    pub fn get_job_info(&self) -> Result<Option<RawJob>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_job_info())
+    }
+
+    /// Blocking version of `crate::Printer::refresh_job()`
+    pub fn refresh_job(&mut self) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.refresh_job())
+    }
+
+    /// Blocking version of `crate::Printer::get_job()`
+    pub fn get_job(&self, id: JobId) -> Result<Option<RawJob>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_job(id))
+    }
+
+    /// Blocking version of `crate::Printer::pause_job()`
+    pub fn pause_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.pause_job(id))
+    }
+
+    /// Blocking version of `crate::Printer::resume_job()`
+    pub fn resume_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.resume_job(id))
+    }
+
+    /// Blocking version of `crate::Printer::continue_job()`
+    pub fn continue_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.continue_job(id))
+    }
+
+    /// Blocking version of `crate::Printer::stop_job()`
+    pub fn stop_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.stop_job(id))
+    }
+
+    /// Blocking version of `crate::Printer::try_pause()`
+    pub fn try_pause(&self) -> Result<JobId, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.try_pause())
+    }
+
+    /// Blocking version of `crate::Printer::try_resume()`
+    pub fn try_resume(&self) -> Result<JobId, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.try_resume())
+    }
+
+    /// Blocking version of `crate::Printer::try_continue()`
+    pub fn try_continue(&self) -> Result<JobId, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.try_continue())
+    }
+
+    /// Blocking version of `crate::Printer::try_stop()`
+    pub fn try_stop(&self) -> Result<JobId, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.try_stop())
+    }
+
+    /// Blocking version of `crate::Printer::pause_and_confirm()`
+    pub fn pause_and_confirm(&mut self, timeout: Duration) -> Result<LinkState, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.pause_and_confirm(timeout))
+    }
+
+    /// Blocking version of `crate::Printer::resume_and_confirm()`
+    pub fn resume_and_confirm(&mut self, timeout: Duration) -> Result<LinkState, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.resume_and_confirm(timeout))
+    }
+
+    /// Blocking version of `crate::Printer::continue_and_confirm()`
+    pub fn continue_and_confirm(&mut self, timeout: Duration) -> Result<LinkState, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.continue_and_confirm(timeout))
+    }
+
+    /// Blocking version of `crate::Printer::stop_and_confirm()`
+    pub fn stop_and_confirm(&mut self, timeout: Duration) -> Result<LinkState, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.stop_and_confirm(timeout))
+    }
+
+    /// Blocking version of `crate::Printer::ensure_ready()`
+    pub fn ensure_ready(&mut self, check: ReadyCheck) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.ensure_ready(check))
+    }
+
+    /// Blocking version of `crate::Printer::start_print()`
+    pub fn start_print(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.start_print(storage, path))
+    }
+
+    /// Blocking version of `crate::Printer::start_print_checked()`
+    pub fn start_print_checked(
+        &mut self,
+        storage: impl Into<Storage>,
+        path: &str,
+        expectations: JobExpectations,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(
+            self.printer
+                .start_print_checked(storage, path, expectations),
+        )
+    }
+
+    /// Blocking version of `crate::Printer::delete_job()`
+    pub fn delete_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.delete_job(id))
+    }
+
+    /// Blocking version of `crate::Printer::connect_status()`
+    pub fn connect_status(&self) -> Result<ConnectStatus, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.connect_status())
+    }
+
+    /// Blocking version of `crate::Printer::register_connect()`
+    pub fn register_connect(
+        &self,
+        server: &ConnectServer,
+    ) -> Result<RegistrationCode, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.register_connect(server))
+    }
+
+    /// Blocking version of `crate::Printer::unregister_connect()`
+    pub fn unregister_connect(&self) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.unregister_connect())
+    }
+
+    /// Blocking version of `crate::Printer::upload_firmware()`
+    pub fn upload_firmware(
+        &self,
+        file_name: &str,
+        source: impl Into<UploadSource>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.upload_firmware(file_name, source))
+    }
+
+    /// Blocking version of `crate::Printer::firmware_update_status()`
+    pub fn firmware_update_status(&self) -> Result<FirmwareUpdateStatus, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.firmware_update_status())
+    }
+
+    /// Blocking version of `crate::Printer::get_logs()`
+    pub fn get_logs(&self) -> Result<Vec<LogFile>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_logs())
+    }
+
+    /// Blocking version of `crate::Printer::get_log()`
+    pub fn get_log(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_log(name))
+    }
+
+    /// Blocking version of `crate::Printer::reboot()`
+    pub fn reboot(&self, confirm: bool) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.reboot(confirm))
+    }
+
+    /// Blocking version of `crate::Printer::shutdown()`
+    pub fn shutdown(&self, confirm: bool) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.shutdown(confirm))
+    }
+
+    /// Blocking version of `crate::Printer::wait_until_finished()`
+    pub fn wait_until_finished(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<LinkState, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.wait_until_finished(poll_interval, timeout))
+    }
+
+    /// Blocking version of `crate::Printer::wait_for_state()`
+    pub fn wait_for_state(
+        &mut self,
+        state: LinkState,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.wait_for_state(state, poll_interval, timeout))
+    }
+
+    /// Blocking version of `crate::Printer::get_status()`
+    pub fn get_status(&self) -> Result<RawStatus, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_status())
+    }
+
+    /// Blocking version of `crate::Printer::refresh_status()`
+    pub fn refresh_status(&mut self) -> Result<(), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.refresh_status())
+    }
+
+    /// Blocking version of `crate::Printer::get_info()`
+    pub fn get_info(&self) -> Result<RawInfo, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_info())
+    }
+
+    /// Blocking version of `crate::Printer::get_network_info()`
+    pub fn get_network_info(&self) -> Result<NetworkInfo, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_network_info())
+    }
+
+    /// Blocking version of `crate::Printer::get_storage_info()`
+    pub fn get_storage_info(&self) -> Result<Vec<StorageInfo>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_storage_info())
+    }
+
+    /// Blocking version of `crate::Printer::file_tree()`
+    pub fn file_tree(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<FileTree, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.file_tree(storage, path))
+    }
+
+    /// Blocking version of `crate::Printer::find_files()`
+    pub fn find_files(
+        &self,
+        storage: impl Into<Storage>,
+        pattern: &str,
+    ) -> Result<Vec<FileTree>, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.find_files(storage, pattern))
+    }
+
+    /// Blocking version of `crate::Printer::get_file_info()`
+    pub fn get_file_info(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<FileDetail, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.get_file_info(storage, path))
+    }
+
+    /// Blocking version of `crate::Printer::delete_file()`
+    pub fn delete_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.delete_file(storage, path))
+    }
+
+    /// Blocking version of `crate::Printer::cleanup_storage()`
+    pub fn cleanup_storage(
+        &self,
+        storage: impl Into<Storage>,
+        policy: CleanupPolicy,
+    ) -> Result<CleanupReport, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.cleanup_storage(storage, policy))
+    }
+
+    /// Blocking version of `crate::Printer::get_thumbnail()`
+    pub fn get_thumbnail(
+        &self,
+        refs: &FileRefs,
+        size: ThumbnailSize,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.get_thumbnail(refs, size))
+    }
+
+    /// Blocking version of `crate::Printer::get_nozzle_temp()`
+    pub fn get_nozzle_temp(&mut self) -> Result<Celsius, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_nozzle_temp())
+    }
+
+    /// Blocking version of `crate::Printer::get_bed_temp()`
+    pub fn get_bed_temp(&mut self) -> Result<Celsius, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_bed_temp())
+    }
+
+    /// Blocking version of `crate::Printer::get_chamber_temp()`
+    pub fn get_chamber_temp(&mut self) -> Result<Option<Celsius>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_chamber_temp())
+    }
+
+    /// Blocking version of `crate::Printer::loaded_material()`
+    pub fn loaded_material(&mut self) -> Result<Option<Material>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.loaded_material())
+    }
+
+    /// Blocking version of `crate::Printer::temperatures()`
+    pub fn temperatures(&mut self) -> Result<TemperatureView, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.temperatures())
+    }
+
+    /// Blocking version of `crate::Printer::get_tool_temp()`
+    pub fn get_tool_temp(&mut self, index: usize) -> Result<Option<Celsius>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.get_tool_temp(index))
+    }
+
+    /// Blocking version of `crate::Printer::free_space()`
+    pub fn free_space(
+        &mut self,
+        storage: impl Into<Storage>,
+    ) -> Result<Option<u64>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.free_space(storage))
+    }
+
+    /// Blocking version of `crate::Printer::total_space()`
+    pub fn total_space(
+        &mut self,
+        storage: impl Into<Storage>,
+    ) -> Result<Option<u64>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.total_space(storage))
+    }
+
+    /// Blocking version of `crate::Printer::fan_speeds()`
+    pub fn fan_speeds(&mut self) -> Result<(Option<u32>, Option<u32>), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.fan_speeds())
+    }
+
+    /// Blocking version of `crate::Printer::hotend_fan_stalled()`
+    pub fn hotend_fan_stalled(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.hotend_fan_stalled())
+    }
+
+    /// Blocking version of `crate::Printer::current_error()`
+    pub fn current_error(&mut self) -> Result<Option<PrinterErrorInfo>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.current_error())
+    }
+
+    /// Blocking version of `crate::Printer::speed_and_flow()`
+    pub fn speed_and_flow(&mut self) -> Result<(Option<u32>, Option<u32>), Box<dyn Error>> {
+        self.runtime.block_on(self.printer.speed_and_flow())
+    }
+
+    /// Blocking version of `crate::Printer::has_mmu()`
+    pub fn has_mmu(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.has_mmu())
+    }
+
+    /// Blocking version of `crate::Printer::active_slot()`
+    pub fn active_slot(&mut self) -> Result<Option<u32>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.active_slot())
+    }
+
+    /// Blocking version of `crate::Printer::filament_detected()`
+    pub fn filament_detected(&mut self) -> Result<Option<bool>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.filament_detected())
+    }
+
+    /// Blocking version of `crate::Printer::is_printing()`
+    pub fn is_printing(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_printing())
+    }
+
+    /// Blocking version of `crate::Printer::is_idle()`
+    pub fn is_idle(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_idle())
+    }
+
+    /// Blocking version of `crate::Printer::has_error()`
+    pub fn has_error(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.has_error())
+    }
+
+    /// Blocking version of `crate::Printer::state()`
+    pub fn state(&mut self) -> Result<LinkState, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.state())
+    }
+
+    /// Blocking version of `crate::Printer::state_text()`
+    pub fn state_text(&mut self) -> Result<String, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.state_text())
+    }
+
+    /// Blocking version of `crate::Printer::flags()`
+    pub fn flags(&mut self) -> Result<PrinterFlags, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.flags())
+    }
+
+    /// Blocking version of `crate::Printer::is_operational()`
+    pub fn is_operational(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_operational())
+    }
+
+    /// Blocking version of `crate::Printer::is_ready()`
+    pub fn is_ready(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_ready())
+    }
+
+    /// Blocking version of `crate::Printer::is_paused()`
+    pub fn is_paused(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_paused())
+    }
+
+    /// Blocking version of `crate::Printer::is_pausing()`
+    pub fn is_pausing(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_pausing())
+    }
+
+    /// Blocking version of `crate::Printer::is_cancelling()`
+    pub fn is_cancelling(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_cancelling())
+    }
+
+    /// Blocking version of `crate::Printer::is_finished()`
+    pub fn is_finished(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_finished())
+    }
+
+    /// Blocking version of `crate::Printer::is_prepared()`
+    pub fn is_prepared(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_prepared())
+    }
+
+    /// Blocking version of `crate::Printer::is_sd_ready()`
+    pub fn is_sd_ready(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_sd_ready())
+    }
+
+    /// Blocking version of `crate::Printer::is_closed_or_error()`
+    pub fn is_closed_or_error(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.is_closed_or_error())
+    }
+
+    /// Blocking version of `crate::Printer::progress_percent()`
+    pub fn progress_percent(&mut self) -> Result<Option<f32>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.progress_percent())
+    }
+
+    /// Blocking version of `crate::Printer::time_remaining()`
+    pub fn time_remaining(&mut self) -> Result<Option<Duration>, Box<dyn Error>> {
+        self.runtime.block_on(self.printer.time_remaining())
+    }
+
+    /// Blocking version of `crate::Printer::upload_file()`
+    pub fn upload_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.upload_file(storage, path, data))
+    }
+
+    /// Blocking version of `crate::Printer::upload_local_file()`
+    pub fn upload_local_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        local_path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.upload_local_file(storage, path, local_path))
+    }
+
+    /// Blocking version of `crate::Printer::download_file()`
+    pub fn download_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.download_file(storage, path))
+    }
+
+    /// Blocking version of `crate::Printer::download_local_file()`
+    pub fn download_local_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        local_path: impl AsRef<Path>,
+    ) -> Result<u64, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.download_local_file(storage, path, local_path))
+    }
+
+    /// Blocking version of `crate::Printer::upload_and_print()`
+    pub fn upload_and_print(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        source: impl Into<UploadSource>,
+    ) -> Result<RawJob, Box<dyn Error>> {
+        self.runtime
+            .block_on(self.printer.upload_and_print(storage, path, source))
+    }
+}
@@ -0,0 +1,23 @@
+//! How the getters of `Printer` use the cached printer information once it is outdated,
+//! see `PrinterBuilder::cache_policy()`.
+
+use std::time::Duration;
+
+/// When a getter waits for a refresh and when it answers from the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// Outdated information is refreshed before the getter returns
+    #[default]
+    Strict,
+
+    /// Outdated printer information that is at most `max_stale` old is returned right away,
+    /// while a refresh runs in the background for the next call.
+    ///
+    /// Only the information of `/api/printer` is revalidated in the background,
+    /// the job and status are refreshed like with `Strict`. Without a tokio runtime, in the browser for example,
+    /// this behaves like `Strict` as well.
+    StaleWhileRevalidate { max_stale: Duration },
+
+    /// Every getter sends a request, the cache is never used
+    NoCache,
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{PrinterKind, PrinterModel};
+
+/// Features listed in the `capabilities` block of `/api/version`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct VersionCapabilities {
+    #[serde(rename = "upload-by-put", default)]
+    pub upload_by_put: bool,
+}
+
+/// The response of `/api/version`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RawVersion {
+    /// Version of the api, for example `2.0.0`
+    pub api: String,
+
+    /// Version of PrusaLink itself
+    pub server: Option<String>,
+
+    /// Version of the printer firmware
+    pub firmware: Option<String>,
+
+    /// The printer PrusaLink runs for, only reported by the Raspberry Pi version, like `PrusaLink I3MK3S`
+    pub original: Option<String>,
+
+    pub text: Option<String>,
+
+    pub hostname: Option<String>,
+
+    pub nozzle_diameter: Option<f32>,
+
+    pub capabilities: Option<VersionCapabilities>,
+}
+
+/// What the firmware of a printer supports, detected from `/api/version`.
+///
+/// Older PrusaLink versions only expose the legacy `/api/*` endpoints,
+/// newer ones add the `/api/v1/*` endpoints used for jobs, status, storage and uploads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Version of the api, None if the printer didn't report it
+    pub api_version: Option<String>,
+
+    /// Version of PrusaLink, None if the printer didn't report it
+    pub server_version: Option<String>,
+
+    /// The `/api/v1/*` endpoints are available
+    pub v1_api: bool,
+
+    /// Files can be uploaded with `PUT /api/v1/files/{storage}/{path}`
+    pub upload_by_put: bool,
+
+    /// Whether the printer extrudes filament or cures resin, judged by the names in the version
+    pub kind: PrinterKind,
+}
+
+impl Capabilities {
+    /// Detects the capabilities from the version the printer reported
+    pub fn from_version(version: &RawVersion) -> Self {
+        let upload_by_put = version
+            .capabilities
+            .as_ref()
+            .is_some_and(|capabilities| capabilities.upload_by_put);
+
+        let api_major = version
+            .api
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u32>().ok());
+
+        Self {
+            api_version: Some(version.api.clone()),
+            server_version: version.server.clone(),
+            v1_api: upload_by_put || api_major.is_some_and(|major| major >= 2),
+            upload_by_put,
+            kind: PrinterModel::detect(Some(version), None).kind(),
+        }
+    }
+
+    /// Capabilities assumed when the printer doesn't report its version,
+    /// every feature is tried and fails with the error of the printer if it is missing
+    pub fn assumed() -> Self {
+        Self {
+            api_version: None,
+            server_version: None,
+            v1_api: true,
+            upload_by_put: true,
+            kind: PrinterKind::Fdm,
+        }
+    }
+}
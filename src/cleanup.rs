@@ -0,0 +1,178 @@
+//! Removing old files from a storage, see `Printer::cleanup_storage()`.
+//!
+//! Files are removed oldest first by their modification time, until every file left is younger
+//! than the maximum age and all files together fit in the maximum size. Folders are never removed,
+//! and neither are read only files, the file that is being printed or files the printer reports
+//! no modification time for, since their age is unknown.
+
+use std::{error::Error, time::Duration};
+
+use crate::{
+    files::FileTree,
+    raw_job::{FileRefs, JobFile, RawJob},
+    storage::Storage,
+    time::SystemTime,
+    Printer,
+};
+
+/// Which files `Printer::cleanup_storage()` removes, a policy without limits removes nothing
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::cleanup::CleanupPolicy;
+/// use std::time::Duration;
+///
+/// // Keep at most two weeks of files, and no more than 1 GB
+/// let policy = CleanupPolicy::new()
+///     .max_age(Duration::from_secs(14 * 24 * 60 * 60))
+///     .max_total_size(1_000_000_000);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupPolicy {
+    max_age: Option<Duration>,
+    max_total_size: Option<u64>,
+}
+
+impl CleanupPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the files that were last modified longer ago than `max_age`
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Removes the oldest files until the files on the storage take at most `max_total_size` bytes together
+    pub fn max_total_size(mut self, max_total_size: u64) -> Self {
+        self.max_total_size = Some(max_total_size);
+        self
+    }
+}
+
+/// What `Printer::cleanup_storage()` did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// The files that were deleted, oldest first
+    pub removed: Vec<FileTree>,
+
+    /// The files that would have been removed but were kept because they are read only,
+    /// being printed or have no modification time
+    pub kept: Vec<FileTree>,
+
+    /// The files the printer refused to delete, with the error
+    pub failed: Vec<(FileTree, String)>,
+
+    /// The bytes freed by the removed files
+    pub freed: u64,
+
+    /// The size of all files left on the storage
+    pub remaining_size: u64,
+}
+
+impl Printer {
+    /// Deletes old files from a storage, `storage` is for example `usb` or `local`.
+    /// See `CleanupPolicy` for which files are removed.
+    ///
+    /// Files that can't be deleted don't stop the cleanup, they are listed in `CleanupReport::failed`
+    /// and the next oldest file is removed instead if the storage is still too full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the storage or the current job can't be read, in which case nothing was deleted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{cleanup::CleanupPolicy, PrinterBuilder};
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let policy = CleanupPolicy::new().max_age(Duration::from_secs(30 * 24 * 60 * 60));
+    /// let report = printer.cleanup_storage("usb", policy).await.unwrap();
+    ///
+    /// println!("Removed {} files, freeing {} bytes", report.removed.len(), report.freed);
+    /// # })
+    /// ```
+    pub async fn cleanup_storage(
+        &self,
+        storage: impl Into<Storage>,
+        policy: CleanupPolicy,
+    ) -> Result<CleanupReport, Box<dyn Error>> {
+        let storage = storage.into();
+        let tree = self.file_tree(&storage, "").await?;
+        let job = self.get_job_info().await?.filter(RawJob::is_active);
+        let printing = job.as_ref().and_then(RawJob::get_file);
+
+        // Files without a modification time go last, they are never removed
+        let mut files = tree.files().cloned().collect::<Vec<_>>();
+        files.sort_by_key(|file| (file.entry.m_timestamp.is_none(), file.entry.m_timestamp));
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let oldest_allowed = policy
+            .max_age
+            .map(|max_age| now.saturating_sub(max_age).as_secs());
+
+        let mut report = CleanupReport {
+            remaining_size: files.iter().filter_map(|file| file.entry.size).sum(),
+            ..Default::default()
+        };
+
+        for file in files {
+            let too_old = oldest_allowed.is_some_and(|oldest| {
+                file.entry
+                    .m_timestamp
+                    .is_none_or(|timestamp| timestamp < oldest)
+            });
+            let too_large = policy
+                .max_total_size
+                .is_some_and(|max| report.remaining_size > max);
+
+            if !too_old && !too_large {
+                continue;
+            }
+
+            if file.entry.read_only
+                || file.entry.m_timestamp.is_none()
+                || printing.is_some_and(|job| is_job_file(job, &storage, &file))
+            {
+                report.kept.push(file);
+                continue;
+            }
+
+            match self.delete_file(&storage, &file.path).await {
+                Ok(()) => {
+                    let size = file.entry.size.unwrap_or(0);
+                    report.freed += size;
+                    report.remaining_size -= size;
+                    report.removed.push(file);
+                }
+                Err(err) => report.failed.push((file, err.to_string())),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Returns true if `file` is the file of the job, compared by download link if both have one
+/// and by path otherwise
+fn is_job_file(job: &JobFile, storage: &Storage, file: &FileTree) -> bool {
+    let download = |refs: Option<&FileRefs>| refs.and_then(|refs| refs.download.clone());
+
+    if let (Some(job), Some(file)) = (
+        download(job.refs.as_ref()),
+        download(file.entry.refs.as_ref()),
+    ) {
+        return job.eq_ignore_ascii_case(&file);
+    }
+
+    job.is_at(storage, &file.path)
+}
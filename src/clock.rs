@@ -0,0 +1,27 @@
+//! Where the printer gets the current time from when deciding if cached information is outdated.
+//!
+//! `SystemClock` is used unless `PrinterBuilder::clock()` is given another `Clock`,
+//! for example the `test_utils::MockClock` of the `test-utils` feature, which only moves when told to.
+
+use std::fmt;
+
+/// The instant type of the clocks, `web_time::Instant` in the browser where `std::time::Instant` panics
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub use web_time::Instant;
+
+/// A source of the current time
+pub trait Clock: fmt::Debug + Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The clock of the system, used by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
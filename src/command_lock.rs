@@ -0,0 +1,48 @@
+//! Keeping commands sent from different tasks from overlapping, set with `PrinterBuilder::command_lock()`.
+//!
+//! When the same `Printer` is shared between tasks, one of them could stop a job while another one
+//! starts a print. With a command lock, only one command that changes the printer runs at a time.
+//! Reading the printer information is never held back.
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::error::PrinterError;
+
+/// What a command does while another command of the same printer is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandLock {
+    /// Fails right away with a `PrinterError::Busy`
+    Reject,
+
+    /// Waits for the other command to finish, commands run in the order they were made
+    Queue,
+}
+
+/// Serializes the commands of a printer according to a `CommandLock`
+#[derive(Debug)]
+pub(crate) struct CommandLocker {
+    mode: CommandLock,
+    // The tokio mutex is fair, so queued commands run in the order they were made
+    lock: Mutex<()>,
+}
+
+impl CommandLocker {
+    pub(crate) fn new(mode: CommandLock) -> Self {
+        Self {
+            mode,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Waits for or rejects `command` until no other command is running,
+    /// the command may run while the returned guard is kept
+    pub(crate) async fn acquire(&self, command: &str) -> Result<MutexGuard<'_, ()>, PrinterError> {
+        match self.mode {
+            CommandLock::Reject => self
+                .lock
+                .try_lock()
+                .map_err(|_| PrinterError::Busy(command.to_string())),
+            CommandLock::Queue => Ok(self.lock.lock().await),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Pausing, resuming and stopping the current job without knowing its id.
+//!
+//! The `try_` functions send the command and return as soon as the printer accepted it,
+//! the printer may still take a while to get there, a pause for example first finishes the current move.
+//! The `_and_confirm` functions send the same command and then poll the printer
+//! until its state shows the command took effect.
+
+use std::{error::Error, time::Duration};
+
+use crate::{
+    config,
+    error::PrinterError,
+    raw_job::{JobId, RawJob},
+    raw_printer::{LinkState, RawPrinter},
+    Printer,
+};
+
+impl Printer {
+    /// Pauses the current job without waiting for the printer to pause, returns the id of the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::NoActiveJob` if nothing is printing,
+    /// or an Err if the printer refuses the command, see `pause_job()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let id = printer.try_pause().await.unwrap();
+    /// println!("Pausing job {}", id);
+    /// # })
+    /// ```
+    pub async fn try_pause(&self) -> Result<JobId, Box<dyn Error>> {
+        let id = self.active_job_id().await?;
+        self.pause_job(id).await?;
+        Ok(id)
+    }
+
+    /// Resumes the paused current job without waiting for the printer to print again,
+    /// returns the id of the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::NoActiveJob` if there is no job,
+    /// or an Err if the printer refuses the command, see `resume_job()`.
+    pub async fn try_resume(&self) -> Result<JobId, Box<dyn Error>> {
+        let id = self.active_job_id().await?;
+        self.resume_job(id).await?;
+        Ok(id)
+    }
+
+    /// Continues the current job after the printer asked for attention, without waiting for it,
+    /// returns the id of the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::NoActiveJob` if there is no job,
+    /// or an Err if the printer refuses the command, see `continue_job()`.
+    pub async fn try_continue(&self) -> Result<JobId, Box<dyn Error>> {
+        let id = self.active_job_id().await?;
+        self.continue_job(id).await?;
+        Ok(id)
+    }
+
+    /// Stops the current job without waiting for the printer to stop, returns the id of the job.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::NoActiveJob` if there is no job,
+    /// or an Err if the printer refuses the command, see `stop_job()`.
+    pub async fn try_stop(&self) -> Result<JobId, Box<dyn Error>> {
+        let id = self.active_job_id().await?;
+        self.stop_job(id).await?;
+        Ok(id)
+    }
+
+    /// Pauses the current job and waits until the printer reports it is paused,
+    /// returns the state of the printer.
+    ///
+    /// The printer is polled at the auto refresh interval, or every `config::DEFAULT_REFRESH`
+    /// if auto refresh is off.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Timeout` if the printer hasn't paused after `timeout`,
+    /// the command was sent nonetheless. See `try_pause()` for the other errors.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer.pause_and_confirm(Duration::from_secs(60)).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn pause_and_confirm(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<LinkState, Box<dyn Error>> {
+        self.try_pause().await?;
+        self.confirm(timeout, |printer| {
+            printer.get_paused() || printer.link_state() == LinkState::Paused
+        })
+        .await
+    }
+
+    /// Resumes the current job and waits until the printer reports it is printing,
+    /// returns the state of the printer. See `pause_and_confirm()`.
+    pub async fn resume_and_confirm(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<LinkState, Box<dyn Error>> {
+        self.try_resume().await?;
+        self.confirm(timeout, |printer| {
+            printer.get_printing() && !printer.get_pausing() && !printer.get_paused()
+        })
+        .await
+    }
+
+    /// Continues the current job and waits until the printer no longer asks for attention,
+    /// returns the state of the printer. See `pause_and_confirm()`.
+    pub async fn continue_and_confirm(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<LinkState, Box<dyn Error>> {
+        self.try_continue().await?;
+        self.confirm(timeout, |printer| {
+            printer.link_state() != LinkState::Attention
+        })
+        .await
+    }
+
+    /// Stops the current job and waits until the printer has stopped printing,
+    /// returns the state of the printer. See `pause_and_confirm()`.
+    pub async fn stop_and_confirm(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<LinkState, Box<dyn Error>> {
+        self.try_stop().await?;
+        self.confirm(timeout, |printer| {
+            !(printer.get_printing() || printer.get_pausing() || printer.get_paused())
+        })
+        .await
+    }
+
+    /// Returns the id of the current job if it hasn't ended
+    async fn active_job_id(&self) -> Result<JobId, Box<dyn Error>> {
+        match self.get_job_info().await?.filter(RawJob::is_active) {
+            Some(job) => Ok(job.get_id()),
+            None => Err(PrinterError::NoActiveJob.into()),
+        }
+    }
+
+    /// Polls the printer until `confirmed` is true for its state
+    async fn confirm<F>(
+        &mut self,
+        timeout: Duration,
+        confirmed: F,
+    ) -> Result<LinkState, Box<dyn Error>>
+    where
+        F: Fn(&RawPrinter) -> bool,
+    {
+        let poll_interval = self.auto_refresh.unwrap_or(config::DEFAULT_REFRESH);
+        self.poll_until(poll_interval, timeout, confirmed).await
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::model::PrinterKind;
+
+/// How strictly the responses of the printer are expected to match the PrusaLink api
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Compatibility {
+    /// Responses must have every state flag PrusaLink on the Buddy firmware sends
+    #[default]
+    PrusaLink,
+
+    /// Accepts the nearly OctoPrint compatible responses of the MK3 PrusaLink on a Raspberry Pi,
+    /// which lack some of the state flags
+    OctoPrint,
+}
+
+/// The state flags of `/api/printer`, missing ones are filled in as false
+const STATE_FLAGS: [&str; 11] = [
+    "operational",
+    "paused",
+    "printing",
+    "cancelling",
+    "pausing",
+    "sdReady",
+    "error",
+    "ready",
+    "closedOrError",
+    "finished",
+    "prepared",
+];
+
+/// Fills in the state flags an OctoPrint style `/api/printer` response lacks,
+/// so it can be deserialized into a `RawPrinter`.
+///
+/// Missing flags are added as false and a missing `link_state` is derived from the other flags,
+/// the missing `sd`, `telemetry` and `storage` blocks are accepted by `RawPrinter` itself.
+/// Responses that aren't json objects are returned unchanged.
+pub fn normalize_octoprint_printer(mut printer: Value) -> Value {
+    let Some(printer_object) = printer.as_object_mut() else {
+        return printer;
+    };
+
+    let state = printer_object
+        .entry("state")
+        .or_insert_with(|| json!({ "text": "Unknown" }));
+
+    if let Some(state) = state.as_object_mut() {
+        state
+            .entry("text")
+            .or_insert_with(|| Value::String("Unknown".to_string()));
+
+        let flags = state
+            .entry("flags")
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        if let Some(flags) = flags.as_object_mut() {
+            for flag in STATE_FLAGS {
+                flags.entry(flag).or_insert(Value::Bool(false));
+            }
+
+            if !flags.contains_key("link_state") {
+                let link_state = link_state_from_flags(flags);
+                flags.insert(
+                    "link_state".to_string(),
+                    Value::String(link_state.to_string()),
+                );
+            }
+        }
+    }
+
+    printer
+}
+
+/// Returns the kind of printer an `/api/printer` response comes from,
+/// SLA printers are the ones that don't report a `tool0` temperature.
+///
+/// Their state flags are as incomplete as those of OctoPrint style responses,
+/// so they are filled in with `normalize_octoprint_printer()` whatever the `Compatibility`.
+pub fn printer_kind(printer: &Value) -> PrinterKind {
+    if printer.pointer("/temperature/tool0").is_some() {
+        PrinterKind::Fdm
+    } else {
+        PrinterKind::Sla
+    }
+}
+
+/// Derives the PrusaLink `link_state` from the OctoPrint flags
+fn link_state_from_flags(flags: &Map<String, Value>) -> &'static str {
+    let flag = |name: &str| flags.get(name).and_then(Value::as_bool).unwrap_or(false);
+
+    if flag("error") || flag("closedOrError") {
+        "ERROR"
+    } else if flag("paused") || flag("pausing") {
+        "PAUSED"
+    } else if flag("cancelling") {
+        "STOPPED"
+    } else if flag("printing") {
+        "PRINTING"
+    } else if flag("finished") {
+        "FINISHED"
+    } else if flag("ready") || flag("operational") {
+        "IDLE"
+    } else {
+        "BUSY"
+    }
+}
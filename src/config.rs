@@ -0,0 +1,268 @@
+//! Printer definitions that can be stored in configuration files.
+//!
+//! `PrinterConfig` holds what is needed to build a `Printer`, and can be read from and written to
+//! any format serde supports, like TOML or JSON:
+//!
+//! ```toml
+//! address = "https://prusa-mk4.local"
+//! api_key = "api_key"
+//! refresh = 5
+//! ```
+//!
+//! Only api key authentication is supported, the username and password of PrusaLink
+//! can't be used instead, so there is no setting for the auth mode.
+//!
+//! A whole `Farm` is defined by a `FarmConfig`, with the printers in a table keyed by their names:
+//!
+//! ```toml
+//! parallelism = 4
+//!
+//! [printers.mk4-a]
+//! address = "192.168.1.50"
+//! api_key = "api_key"
+//!
+//! [printers.mini]
+//! address = "192.168.1.51"
+//! api_key = "other_key"
+//! compatibility = "octoprint"
+//! ```
+
+use std::{collections::BTreeMap, error::Error, fmt, time::Duration};
+
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    address::PrinterAddress, compat::Compatibility, error::BuildError, farm::Farm, Printer,
+    PrinterBuilder,
+};
+
+/// The auto refresh time used when a config doesn't give one
+pub const DEFAULT_REFRESH: Duration = Duration::from_secs(2);
+
+/// The definition of a printer, see `Printer::from_config()`.
+///
+/// The api key is kept out of the `Debug` output, but written in full when the config is serialized.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrinterConfig {
+    /// The address of the printer, in any format `PrinterAddress` accepts
+    pub address: String,
+
+    /// The port, taking precedence over a port given in the address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u32>,
+
+    #[serde(serialize_with = "serialize_secret")]
+    pub api_key: SecretString,
+
+    /// The auto refresh time, in seconds in the serialized config. Zero turns auto refresh off.
+    #[serde(
+        default = "default_refresh",
+        serialize_with = "serialize_secs",
+        deserialize_with = "deserialize_secs"
+    )]
+    pub refresh: Duration,
+
+    /// The path prefix of the api, taking precedence over a path given in the address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+
+    #[serde(default)]
+    pub compatibility: Compatibility,
+}
+
+impl PrinterConfig {
+    /// Creates a config with the default refresh time and compatibility
+    pub fn new(address: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            port: None,
+            api_key: SecretString::new(api_key.into()),
+            refresh: DEFAULT_REFRESH,
+            base_path: None,
+            compatibility: Compatibility::default(),
+        }
+    }
+
+    /// Returns a builder with the settings of the config, to change what the config doesn't cover
+    pub fn builder(&self) -> PrinterBuilder {
+        let mut builder = PrinterBuilder::new(&self.address, self.api_key.expose_secret())
+            .compatibility(self.compatibility);
+
+        if let Some(port) = self.port {
+            builder = builder.port(port);
+        }
+        if let Some(base_path) = &self.base_path {
+            builder = builder.base_path(base_path);
+        }
+
+        builder.auto_refresh = (!self.refresh.is_zero()).then_some(self.refresh);
+        builder
+    }
+}
+
+impl Printer {
+    /// Builds a printer from its definition
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BuildError` for the same mistakes as `PrinterBuilder::try_build()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use prusa_link_rs::{config::PrinterConfig, Printer};
+    ///
+    /// let config: PrinterConfig =
+    ///     serde_json::from_str(r#"{"address": "192.168.1.50", "api_key": "api_key"}"#).unwrap();
+    ///
+    /// let printer = Printer::from_config(&config).unwrap();
+    /// assert_eq!(printer.address(), "192.168.1.50");
+    /// ```
+    pub fn from_config(config: &PrinterConfig) -> Result<Printer, BuildError> {
+        config.builder().try_build()
+    }
+
+    /// Returns the definition of the printer, which builds the same printer with `from_config()`.
+    ///
+    /// Only the settings `PrinterConfig` covers are kept, hooks or a rate limit for example are not.
+    pub fn to_config(&self) -> PrinterConfig {
+        let address = PrinterAddress {
+            scheme: self.scheme,
+            host: self.address.clone(),
+            port: None,
+            path: String::new(),
+        };
+        let default_port = u32::from(self.scheme.default_port());
+
+        PrinterConfig {
+            address: address.to_string(),
+            port: (self.port != default_port).then_some(self.port),
+            api_key: self.api_key.clone(),
+            refresh: self.auto_refresh.unwrap_or(Duration::ZERO),
+            base_path: (!self.base_path.is_empty()).then(|| self.base_path.clone()),
+            compatibility: self.compatibility,
+        }
+    }
+}
+
+/// The definition of every printer of a farm, see `FarmConfig::build()`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FarmConfig {
+    /// How many printers are refreshed at the same time, `farm::DEFAULT_PARALLELISM` if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<usize>,
+
+    /// The printers keyed by the names they get in the farm
+    #[serde(default)]
+    pub printers: BTreeMap<String, PrinterConfig>,
+}
+
+impl FarmConfig {
+    /// Reads the config from a TOML file
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConfigError::Io` if the file can't be read and a `ConfigError::Parse` if it isn't a valid config.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use prusa_link_rs::config::FarmConfig;
+    ///
+    /// let farm = FarmConfig::load("printers.toml").unwrap().build().unwrap();
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+
+        Self::from_toml(&text)
+    }
+
+    /// Parses the config from the text of a TOML file
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConfigError::Parse` if the text isn't a valid config.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(|err| ConfigError::Parse(err.to_string()))
+    }
+
+    /// Builds a farm with every printer of the config
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConfigError::Printer` for the first printer, in alphabetical order, that can't be built.
+    pub fn build(&self) -> Result<Farm, ConfigError> {
+        let mut farm = Farm::new();
+        if let Some(parallelism) = self.parallelism {
+            farm = farm.parallelism(parallelism);
+        }
+
+        for (name, config) in &self.printers {
+            let printer = Printer::from_config(config).map_err(|err| ConfigError::Printer {
+                name: name.clone(),
+                err,
+            })?;
+
+            farm.add(name.clone(), printer);
+        }
+
+        Ok(farm)
+    }
+}
+
+/// Why a `FarmConfig` couldn't be loaded or built
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file couldn't be read
+    Io(std::io::Error),
+
+    /// The file isn't a valid config, with the message of the parser
+    Parse(String),
+
+    /// The printer with the given name can't be built
+    Printer { name: String, err: BuildError },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "Failed to read the config: {}", err),
+            ConfigError::Parse(message) => write!(f, "Invalid config: {}", message),
+            ConfigError::Printer { name, err } => write!(f, "Invalid printer {}: {}", name, err),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Printer { err, .. } => Some(err),
+            ConfigError::Parse(_) => None,
+        }
+    }
+}
+
+fn default_refresh() -> Duration {
+    DEFAULT_REFRESH
+}
+
+fn serialize_secret<S: Serializer>(
+    secret: &SecretString,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(secret.expose_secret())
+}
+
+fn serialize_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+fn deserialize_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let secs = f64::deserialize(deserializer)?;
+
+    Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+}
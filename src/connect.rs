@@ -0,0 +1,220 @@
+//! Registering the printer with Prusa Connect through `/api/connection`, so provisioning tools
+//! can bring new printers online without the touchscreen.
+//!
+//! Registration takes two steps: `Printer::register_connect()` returns a code, which is entered
+//! in Prusa Connect (or the returned url is opened) while logged in to the account the printer should
+//! belong to. Until then `Printer::connect_status()` reports the registration as in progress.
+//!
+//! Only PrusaLink on a Raspberry Pi has these endpoints, the firmware of the MK4, XL and MINI
+//! registers from the touchscreen.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::PrinterError, transport::TransportBody, Printer};
+
+/// The Prusa Connect server to register with, `ConnectServer::default()` is `connect.prusa3d.com`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConnectServer {
+    pub hostname: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+impl Default for ConnectServer {
+    fn default() -> Self {
+        Self {
+            hostname: "connect.prusa3d.com".to_string(),
+            port: 443,
+            tls: true,
+        }
+    }
+}
+
+/// How far the printer is with registering with Prusa Connect
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ConnectRegistration {
+    /// The printer isn't registered, `NO_REGISTRATION`
+    NotRegistered,
+
+    /// A code was handed out and is waiting to be entered in Prusa Connect, `IN_PROGRESS`
+    InProgress,
+
+    /// The printer is registered, `FINISHED`
+    Finished,
+
+    /// A state this version of the crate doesn't know about
+    Unknown(String),
+}
+
+impl From<&str> for ConnectRegistration {
+    fn from(registration: &str) -> Self {
+        match registration {
+            "NO_REGISTRATION" => ConnectRegistration::NotRegistered,
+            "IN_PROGRESS" => ConnectRegistration::InProgress,
+            "FINISHED" => ConnectRegistration::Finished,
+            _ => ConnectRegistration::Unknown(registration.to_string()),
+        }
+    }
+}
+
+/// The Prusa Connect part of `/api/connection`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConnectStatus {
+    pub hostname: String,
+    pub port: u16,
+    pub tls: bool,
+
+    /// The registration state as sent by the printer, see `registration()`
+    #[serde(rename = "registration")]
+    pub raw_registration: String,
+
+    /// True if the printer uses its own certificate for the connection
+    #[serde(default)]
+    pub custom_cert: bool,
+}
+
+impl ConnectStatus {
+    pub fn registration(&self) -> ConnectRegistration {
+        ConnectRegistration::from(self.raw_registration.as_str())
+    }
+
+    /// Returns the server the printer is set up to use
+    pub fn server(&self) -> ConnectServer {
+        ConnectServer {
+            hostname: self.hostname.clone(),
+            port: self.port,
+            tls: self.tls,
+        }
+    }
+}
+
+/// What `Printer::register_connect()` returns to finish the registration with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrationCode {
+    /// The code to enter in Prusa Connect
+    pub code: String,
+
+    /// The page of Prusa Connect that adds the printer with the code filled in
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct RawConnection {
+    connect: ConnectStatus,
+}
+
+#[derive(Serialize)]
+struct RegisterRequest<'a> {
+    connect: &'a ConnectServer,
+}
+
+#[derive(Deserialize)]
+struct RegisterResponse {
+    url: String,
+}
+
+impl Printer {
+    /// Returns the Prusa Connect server the printer uses and whether it is registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Unsupported` if the printer doesn't have `/api/connection`,
+    /// and an Err if it responds with another error status or a response that can't be parsed.
+    pub async fn connect_status(&self) -> Result<ConnectStatus, Box<dyn Error>> {
+        let res = self
+            .send(self.request(http::Method::GET, "/api/connection"))
+            .await?;
+        check_connection_response(&res, "read the Prusa Connect status")?;
+
+        let raw_connection_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(serde_json::from_str::<RawConnection>(&raw_connection_text)?.connect)
+    }
+
+    /// Starts registering the printer with the given Prusa Connect server, and returns the code
+    /// that finishes the registration once it is entered in Prusa Connect.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Unsupported` if the printer doesn't have `/api/connection`,
+    /// and an Err if it refuses the registration, for example because it is already registered.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{connect::ConnectServer, PrinterBuilder};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let registration = printer.register_connect(&ConnectServer::default()).await.unwrap();
+    /// println!("Open {} to add the printer", registration.url);
+    /// # })
+    /// ```
+    pub async fn register_connect(
+        &self,
+        server: &ConnectServer,
+    ) -> Result<RegistrationCode, Box<dyn Error>> {
+        let body = serde_json::to_vec(&RegisterRequest { connect: server })?;
+
+        let _lock = self.lock_commands("register with Prusa Connect").await?;
+        let res = self
+            .send_body(
+                self.request(http::Method::POST, "/api/connection")
+                    .header(http::header::CONTENT_TYPE, "application/json"),
+                TransportBody::Bytes(body.into()),
+            )
+            .await?;
+        check_connection_response(&res, "register with Prusa Connect")?;
+
+        let url = serde_json::from_slice::<RegisterResponse>(res.body())?.url;
+        let code = url
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(RegistrationCode { code, url })
+    }
+
+    /// Removes the registration with Prusa Connect, the printer has to be registered again
+    /// to show up in Prusa Connect.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Unsupported` if the printer doesn't have `/api/connection`,
+    /// and an Err if it responds with another error status.
+    pub async fn unregister_connect(&self) -> Result<(), Box<dyn Error>> {
+        let _lock = self.lock_commands("unregister from Prusa Connect").await?;
+        let res = self
+            .send(self.request(http::Method::DELETE, "/api/connection"))
+            .await?;
+        check_connection_response(&res, "unregister from Prusa Connect")
+    }
+}
+
+/// Maps the error statuses of `/api/connection`, `action` is used in the error message
+fn check_connection_response(
+    res: &http::Response<bytes::Bytes>,
+    action: &str,
+) -> Result<(), Box<dyn Error>> {
+    if res.status() == http::StatusCode::NOT_FOUND {
+        return Err(PrinterError::Unsupported("Prusa Connect registration".to_string()).into());
+    }
+
+    if !res.status().is_success() {
+        return Err(format!(
+            "Failed to {}, server responded with {}: {}",
+            action,
+            res.status(),
+            String::from_utf8_lossy(res.body())
+        )
+        .into());
+    }
+
+    Ok(())
+}
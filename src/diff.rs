@@ -0,0 +1,212 @@
+//! Comparing two snapshots of the printer field by field, see `RawPrinter::diff()`.
+//!
+//! Unlike the events of `Printer::watch()`, a diff lists every change, not only the ones
+//! that mean something happened, which makes it useful for logging what a refresh changed.
+
+use std::fmt;
+
+use crate::{
+    events::Heater,
+    raw_printer::{LinkState, PrinterFlags, PrinterStorageInfo, RawPrinter},
+    units::{TemperatureSource, Temperatures},
+};
+
+/// The changes between two snapshots of the printer, as returned by `RawPrinter::diff()`.
+///
+/// Displayed as a comma separated list of the changes, like
+/// `state IDLE -> PRINTING, nozzle 25.0°C -> 180.0°C (target 215.0°C)`.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::raw_printer::{LinkState, RawPrinter};
+///
+/// let before = RawPrinter::builder().build();
+/// let after = RawPrinter::builder()
+///     .link_state(LinkState::Printing)
+///     .nozzle_temp(180.0, 215.0)
+///     .build();
+///
+/// let diff = before.diff(&after);
+///
+/// assert_eq!(diff.state, Some((LinkState::Idle, LinkState::Printing)));
+/// assert_eq!(diff.temperatures[0].actual_delta(), 157.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PrinterDiff {
+    /// The link state before and after, None if it didn't change
+    pub state: Option<(LinkState, LinkState)>,
+
+    /// The flags that changed, with their new value. Named like the fields of `PrinterFlags`
+    pub flags: Vec<(&'static str, bool)>,
+
+    /// The heaters whose actual or target temperature changed
+    pub temperatures: Vec<TemperatureChange>,
+
+    /// The storages whose space changed, or that were added or removed
+    pub storage: Vec<StorageChange>,
+}
+
+/// The temperatures of a heater before and after
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureChange {
+    pub heater: Heater,
+    pub from: Temperatures,
+    pub to: Temperatures,
+}
+
+impl TemperatureChange {
+    /// Returns how many degrees the actual temperature rose, negative if it fell
+    pub fn actual_delta(&self) -> f32 {
+        self.to.actual.value() - self.from.actual.value()
+    }
+
+    /// Returns true if the target temperature was changed
+    pub fn target_changed(&self) -> bool {
+        self.from.target != self.to.target
+    }
+}
+
+/// The space of a storage before and after, None if the storage wasn't reported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageChange {
+    /// `local` or `sd_card`, like the fields of `PrinterStorage`
+    pub storage: &'static str,
+    pub from: Option<PrinterStorageInfo>,
+    pub to: Option<PrinterStorageInfo>,
+}
+
+impl StorageChange {
+    /// Returns how many bytes of free space were gained, negative if space was used up.
+    /// None if the storage was added or removed
+    pub fn free_space_delta(&self) -> Option<i64> {
+        let (from, to) = (self.from.as_ref()?, self.to.as_ref()?);
+
+        Some(to.free_space as i64 - from.free_space as i64)
+    }
+}
+
+impl PrinterDiff {
+    /// Returns true if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.state.is_none()
+            && self.flags.is_empty()
+            && self.temperatures.is_empty()
+            && self.storage.is_empty()
+    }
+}
+
+impl fmt::Display for PrinterDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+
+        if let Some((from, to)) = &self.state {
+            changes.push(format!("state {} -> {}", from, to));
+        }
+
+        for (flag, value) in &self.flags {
+            changes.push(format!("{} {}", flag, value));
+        }
+
+        for change in &self.temperatures {
+            let heater = match change.heater {
+                Heater::Nozzle => "nozzle",
+                Heater::Bed => "bed",
+            };
+            let mut text = format!("{} {} -> {}", heater, change.from.actual, change.to.actual);
+
+            if change.target_changed() {
+                text.push_str(&format!(" (target {})", change.to.target));
+            }
+
+            changes.push(text);
+        }
+
+        for change in &self.storage {
+            match (&change.from, &change.to) {
+                (None, _) => changes.push(format!("{} added", change.storage)),
+                (_, None) => changes.push(format!("{} removed", change.storage)),
+                (Some(from), Some(to)) => changes.push(format!(
+                    "{} free {} -> {} bytes",
+                    change.storage, from.free_space, to.free_space
+                )),
+            }
+        }
+
+        if changes.is_empty() {
+            return write!(f, "no changes");
+        }
+
+        write!(f, "{}", changes.join(", "))
+    }
+}
+
+impl RawPrinter {
+    /// Lists what changed from this snapshot to `other`, see `PrinterDiff`
+    pub fn diff(&self, other: &RawPrinter) -> PrinterDiff {
+        let (from, to) = (self.link_state(), other.link_state());
+        let state = (from != to).then_some((from, to));
+
+        let flags = changed_flags(self.flags(), other.flags());
+
+        let temperatures = [Heater::Nozzle, Heater::Bed]
+            .into_iter()
+            .filter_map(|heater| {
+                let (from, to) = (temperatures(self, heater), temperatures(other, heater));
+
+                (from != to).then_some(TemperatureChange { heater, from, to })
+            })
+            .collect();
+
+        let storage = [
+            ("local", &self.storage().local, &other.storage().local),
+            ("sd_card", &self.storage().sd_card, &other.storage().sd_card),
+        ]
+        .into_iter()
+        .filter(|(_, from, to)| from != to)
+        .map(|(storage, from, to)| StorageChange {
+            storage,
+            from: from.clone(),
+            to: to.clone(),
+        })
+        .collect();
+
+        PrinterDiff {
+            state,
+            flags,
+            temperatures,
+            storage,
+        }
+    }
+}
+
+fn temperatures(printer: &RawPrinter, heater: Heater) -> Temperatures {
+    let view = printer.temperatures();
+    let reading = match heater {
+        Heater::Nozzle => view.nozzle,
+        Heater::Bed => view.bed,
+    };
+
+    reading.resolve(TemperatureSource::Telemetry)
+}
+
+/// The link state is left out, it is compared as a `LinkState`
+fn changed_flags(from: &PrinterFlags, to: &PrinterFlags) -> Vec<(&'static str, bool)> {
+    [
+        ("operational", from.operational, to.operational),
+        ("paused", from.paused, to.paused),
+        ("printing", from.printing, to.printing),
+        ("cancelling", from.cancelling, to.cancelling),
+        ("pausing", from.pausing, to.pausing),
+        ("sd_ready", from.sd_ready, to.sd_ready),
+        ("error", from.error, to.error),
+        ("ready", from.ready, to.ready),
+        ("closed_or_error", from.closed_or_error, to.closed_or_error),
+        ("finished", from.finished, to.finished),
+        ("prepared", from.prepared, to.prepared),
+    ]
+    .into_iter()
+    .filter(|(_, from, to)| from != to)
+    .map(|(flag, _, to)| (flag, to))
+    .collect()
+}
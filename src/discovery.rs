@@ -0,0 +1,156 @@
+//! Finding printers on the local network over mDNS, enabled with the `discovery` feature.
+//!
+//! PrusaLink announces itself as an OctoPrint compatible server, and printers with a web interface
+//! also announce a http service with the printer in its name. Both are browsed.
+
+#[cfg(target_arch = "wasm32")]
+compile_error!("the discovery feature needs UDP sockets, which aren't available on wasm");
+
+use std::{error::Error, net::SocketAddr, time::Duration};
+
+use futures::{stream, StreamExt};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use crate::{
+    address::PrinterAddress,
+    time::{self, Instant},
+    PrinterBuilder,
+};
+
+/// The mDNS service types that are browsed for printers
+pub const SERVICE_TYPES: [&str; 2] = ["_octoprint._tcp.local.", "_http._tcp.local."];
+
+/// Printer models recognized in service and host names, the longer names first so `MK4S` isn't read as `MK4`
+const KNOWN_MODELS: [&str; 9] = [
+    "CORE One", "MK3.9", "MK3.5", "MK4S", "MK3S", "MINI", "MK4", "MK3", "XL",
+];
+
+/// A printer found by `discover_printers()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredPrinter {
+    /// The name the printer announces itself with, for example `PrusaLink MK4`
+    pub name: String,
+
+    /// The mDNS host name, for example `prusa-mk4.local.`
+    pub hostname: String,
+
+    pub address: PrinterAddress,
+
+    /// The model of the printer, if it could be told from the announcement
+    pub model: Option<String>,
+}
+
+impl DiscoveredPrinter {
+    /// Reads a resolved mDNS service, returns None if it isn't a printer or has no address.
+    ///
+    /// Http services are only taken as printers if their name mentions Prusa,
+    /// every OctoPrint compatible service is taken.
+    pub fn from_service(info: &ServiceInfo) -> Option<Self> {
+        let service_type = info.get_type();
+        let name = info
+            .get_fullname()
+            .strip_suffix(service_type)
+            .unwrap_or(info.get_fullname())
+            .trim_end_matches('.')
+            .to_string();
+
+        if service_type.starts_with("_http.") && !name.to_lowercase().contains("prusa") {
+            return None;
+        }
+
+        // Prefer IPv4, link-local IPv6 addresses need a scope that doesn't fit in a url
+        let ip = info
+            .get_addresses()
+            .iter()
+            .min_by_key(|ip| ip.is_ipv6())
+            .copied()?;
+        let hostname = info.get_hostname().to_string();
+
+        let model = info
+            .get_property_val_str("model")
+            .map(str::to_string)
+            .or_else(|| model_hint(&name).or_else(|| model_hint(&hostname)));
+
+        Some(Self {
+            name,
+            hostname,
+            address: SocketAddr::new(ip, info.get_port()).into(),
+            model,
+        })
+    }
+
+    /// Returns a builder for the printer, with the address already set
+    pub fn builder(&self, api_key: impl Into<String>) -> PrinterBuilder {
+        PrinterBuilder::new(&self.address, api_key)
+    }
+}
+
+/// Returns the first known printer model mentioned in `name`
+fn model_hint(name: &str) -> Option<String> {
+    let name = name.to_uppercase();
+
+    KNOWN_MODELS
+        .iter()
+        .find(|model| name.contains(&model.to_uppercase()))
+        .map(|model| model.to_string())
+}
+
+/// Browses the local network for printers for `timeout`, and returns every printer that answered.
+///
+/// A printer announcing several services is only returned once.
+///
+/// # Errors
+///
+/// Returns an Err if the mDNS sockets can't be opened, for example without a network interface.
+///
+/// # Example
+///
+/// ```no_run
+/// # use prusa_link_rs::discovery::discover_printers;
+/// # use std::time::Duration;
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// for found in discover_printers(Duration::from_secs(3)).await.unwrap() {
+///     println!("{} ({:?}) at {}", found.name, found.model, found.address);
+///
+///     let printer = found.builder("api_key").build();
+/// }
+/// # })
+/// ```
+pub async fn discover_printers(
+    timeout: Duration,
+) -> Result<Vec<DiscoveredPrinter>, Box<dyn Error>> {
+    let daemon = ServiceDaemon::new()?;
+
+    let receivers = SERVICE_TYPES
+        .iter()
+        .map(|service_type| daemon.browse(service_type))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut events =
+        stream::select_all(receivers.into_iter().map(|receiver| receiver.into_stream()));
+
+    let deadline = Instant::now() + timeout;
+    let mut found: Vec<DiscoveredPrinter> = Vec::new();
+
+    while let Some(Some(event)) = time::timeout(
+        deadline.saturating_duration_since(Instant::now()),
+        events.next(),
+    )
+    .await
+    {
+        let ServiceEvent::ServiceResolved(info) = event else {
+            continue;
+        };
+
+        if let Some(printer) = DiscoveredPrinter::from_service(&info) {
+            if !found.iter().any(|known| known.address == printer.address) {
+                found.push(printer);
+            }
+        }
+    }
+
+    // Stops the thread of the daemon, an error only means it has already stopped
+    let _ = daemon.shutdown();
+
+    Ok(found)
+}
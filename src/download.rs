@@ -0,0 +1,205 @@
+//! Downloading files from a storage of the printer.
+//!
+//! Files are fetched in chunks with `Range` requests, so a download that was interrupted
+//! can continue where it stopped instead of starting over, see `Printer::download_local_file()`.
+
+use std::error::Error;
+
+use bytes::Bytes;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{path::PrinterPath, storage::Storage, Printer};
+
+/// Size of the ranges a download is split into, progress is reported after each range
+const DOWNLOAD_CHUNK_SIZE: u64 = 1024 * 1024;
+
+impl Printer {
+    /// Downloads a file from the given storage of the printer into memory,
+    /// `path` is the path of the file on the storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the file doesn't exist or the printer responds with an error status.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let gcode = printer.download_file("usb", "prints/benchy.gcode").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn download_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.download_file_with_progress(storage, path, |_, _| {})
+            .await
+    }
+
+    /// Same as `download_file()`, but calls `progress` with the number of bytes received
+    /// and the size of the file every time a chunk of the file has arrived.
+    pub async fn download_file_with_progress<F>(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        progress: F,
+    ) -> Result<Vec<u8>, Box<dyn Error>>
+    where
+        F: FnMut(u64, u64) + Send,
+    {
+        let mut data = Vec::new();
+        self.download_to_writer(storage, path, &mut data, 0, progress)
+            .await?;
+
+        Ok(data)
+    }
+
+    /// Downloads a file into `writer`, starting at byte `offset` of the file.
+    ///
+    /// Use an offset to continue a download that was interrupted, with the number of bytes
+    /// that were already received. `progress` is called with the number of bytes of the file
+    /// received so far, including the skipped ones, and the size of the file.
+    ///
+    /// Returns the size of the file. Printers that ignore the `Range` header send the whole file,
+    /// in which case the bytes before `offset` are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the file doesn't exist, the printer responds with an error status,
+    /// `offset` is past the end of the file, or writing fails.
+    pub async fn download_to_writer<W, F>(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        mut writer: W,
+        offset: u64,
+        mut progress: F,
+    ) -> Result<u64, Box<dyn Error>>
+    where
+        W: AsyncWrite + Unpin + Send,
+        F: FnMut(u64, u64) + Send,
+    {
+        let storage = storage.into();
+        let file = self.get_file_info(&storage, path).await?;
+
+        let url = match file.refs.as_ref().and_then(|refs| refs.download.clone()) {
+            Some(url) => url,
+            None => format!("/{}/{}", storage, PrinterPath::new(path)?.url_encoded()),
+        };
+
+        let mut total = file.size;
+        if let Some(total) = total.filter(|&total| offset > total) {
+            return Err(format!(
+                "Can't continue the download of {} at byte {}, the file only has {} bytes",
+                path, offset, total
+            )
+            .into());
+        }
+
+        let mut received = offset;
+        while total.is_none_or(|total| received < total) {
+            let end = received + DOWNLOAD_CHUNK_SIZE - 1;
+            let end = total.map_or(end, |total| end.min(total - 1));
+
+            let res = self
+                .send(
+                    self.request(http::Method::GET, &url)
+                        .header(http::header::RANGE, format!("bytes={}-{}", received, end)),
+                )
+                .await?;
+
+            let chunk = match res.status() {
+                http::StatusCode::PARTIAL_CONTENT => {
+                    total = total.or_else(|| content_range_total(&res));
+                    res.body().clone()
+                }
+                // The range was ignored and the whole file was sent
+                http::StatusCode::OK => {
+                    let body = res.body();
+                    total = Some(body.len() as u64);
+                    body.slice((received as usize).min(body.len())..)
+                }
+                // Without the size of the file, the end is only noticed when the range is past it
+                http::StatusCode::RANGE_NOT_SATISFIABLE if total.is_none() => Bytes::new(),
+                status => {
+                    return Err(format!(
+                        "Failed to download {}, server responded with {}",
+                        path, status
+                    )
+                    .into())
+                }
+            };
+
+            if chunk.is_empty() {
+                break;
+            }
+
+            writer.write_all(&chunk).await?;
+            received += chunk.len() as u64;
+            progress(received, total.unwrap_or(received));
+        }
+
+        writer.flush().await?;
+        Ok(received)
+    }
+
+    /// Downloads a file to the local filesystem, continuing the download if `local_path`
+    /// already holds the start of the file from an earlier attempt. Not available on wasm
+    ///
+    /// Returns the size of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the local file can't be written or the download fails, see `download_to_writer()`.
+    /// The bytes received until then are kept, so calling the function again continues the download.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// while let Err(err) = printer.download_local_file("usb", "big.bgcode", "big.bgcode").await {
+    ///     println!("Download interrupted, continuing: {}", err);
+    /// }
+    /// # })
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn download_local_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        local_path: impl AsRef<std::path::Path>,
+    ) -> Result<u64, Box<dyn Error>> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(local_path)
+            .await?;
+        let offset = file.metadata().await?.len();
+
+        self.download_to_writer(storage, path, file, offset, |_, _| {})
+            .await
+    }
+}
+
+/// Returns the size of the file from a `Content-Range` header like `bytes 0-1023/4096`
+fn content_range_total(res: &http::Response<Bytes>) -> Option<u64> {
+    res.headers()
+        .get(http::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,279 @@
+use std::{error::Error, fmt, time::Duration};
+
+use crate::{
+    address::AddressError, job_check::JobMismatch, model::Version, raw_job::JobId,
+    ready::NotReadyReason,
+};
+
+/// Errors returned by the crate that callers might want to handle specifically.
+///
+/// The functions on `Printer` return a `Box<dyn Error>`, which can be downcast to this type:
+///
+/// ```rust
+/// # use prusa_link_rs::error::PrinterError;
+/// # fn handle(err: Box<dyn std::error::Error>) {
+/// if let Some(PrinterError::Timeout(waited)) = err.downcast_ref::<PrinterError>() {
+///     println!("gave up after {:?}", waited);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrinterError {
+    /// The printer didn't reach the expected condition in the given time
+    Timeout(Duration),
+
+    /// A file was rejected before it was sent to the printer, the message says why
+    InvalidFile(String),
+
+    /// A path was rejected before it was sent to the printer, the message says why.
+    /// See `path::PrinterPath`.
+    InvalidPath(String),
+
+    /// The uploaded file doesn't have the size of the local file, `found` is None if the file
+    /// isn't on the printer at all. See `PrinterBuilder::verify_uploads()`.
+    UploadVerificationFailed {
+        path: String,
+        expected: u64,
+        found: Option<u64>,
+    },
+
+    /// The upload was refused with `409 Conflict` because a file with the same path exists,
+    /// `message` is the answer of the printer
+    FileExists { path: String, message: String },
+
+    /// The upload was refused with `409 Conflict` because the file with the same path
+    /// is being printed, `message` is the answer of the printer
+    FileInUse { path: String, message: String },
+
+    /// The upload was refused with `413 Payload Too Large`, `message` is the answer of the printer
+    FileTooLarge { path: String, message: String },
+
+    /// The file doesn't fit on the storage, both values are in bytes
+    InsufficientStorage { needed: u64, available: u64 },
+
+    /// The firmware of the printer doesn't support the feature, the message names it
+    Unsupported(String),
+
+    /// No request was sent because the last `failures` requests failed,
+    /// the next one is let through in `retry_in`. See `PrinterBuilder::circuit_breaker()`.
+    CircuitOpen { failures: u32, retry_in: Duration },
+
+    /// Another command is running on the printer, the message names the command that was refused.
+    /// See `PrinterBuilder::command_lock()`.
+    Busy(String),
+
+    /// The printer can't take a new print, see `Printer::ensure_ready()`
+    NotReady(NotReadyReason),
+
+    /// The file wasn't sliced for the printer, with every difference that was found.
+    /// See `Printer::start_print_checked()`.
+    IncompatibleJob {
+        path: String,
+        mismatches: Vec<JobMismatch>,
+    },
+
+    /// There is no job to send the command to, see `Printer::try_pause()`
+    NoActiveJob,
+
+    /// The job can't be deleted because it is still running, see `Printer::delete_job()`
+    JobActive(JobId),
+
+    /// PrusaLink is older than `required`, `found` is None if the printer didn't report its version.
+    /// See `Printer::require_min_version()`.
+    FirmwareTooOld {
+        required: Version,
+        found: Option<Version>,
+    },
+}
+
+impl fmt::Display for PrinterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrinterError::Timeout(waited) => write!(f, "Timed out after {:?}", waited),
+            PrinterError::InvalidFile(reason) => write!(f, "Invalid file: {}", reason),
+            PrinterError::InvalidPath(reason) => write!(f, "Invalid path: {}", reason),
+            PrinterError::UploadVerificationFailed {
+                path,
+                expected,
+                found: Some(found),
+            } => write!(
+                f,
+                "The upload of {} is incomplete, the printer has {} of {} bytes",
+                path, found, expected
+            ),
+            PrinterError::UploadVerificationFailed {
+                path,
+                expected: _,
+                found: None,
+            } => write!(f, "The upload of {} is missing on the printer", path),
+            PrinterError::FileExists { path, message } => {
+                write!(f, "{} already exists on the printer: {}", path, message)
+            }
+            PrinterError::FileInUse { path, message } => {
+                write!(
+                    f,
+                    "{} is being printed and can't be replaced: {}",
+                    path, message
+                )
+            }
+            PrinterError::FileTooLarge { path, message } => {
+                write!(f, "{} is too large for the printer: {}", path, message)
+            }
+            PrinterError::InsufficientStorage { needed, available } => write!(
+                f,
+                "Not enough space on the storage, {} bytes needed but only {} bytes available",
+                needed, available
+            ),
+            PrinterError::Unsupported(feature) => {
+                write!(f, "The firmware of the printer doesn't support {}", feature)
+            }
+            PrinterError::CircuitOpen { failures, retry_in } => write!(
+                f,
+                "The printer failed the last {} requests, retrying in {:?}",
+                failures, retry_in
+            ),
+            PrinterError::Busy(command) => write!(
+                f,
+                "Couldn't {}, another command is running on the printer",
+                command
+            ),
+            PrinterError::NotReady(reason) => write!(f, "Not ready to print, {}", reason),
+            PrinterError::IncompatibleJob { path, mismatches } => {
+                write!(f, "{} can't be printed on this printer", path)?;
+                for (i, mismatch) in mismatches.iter().enumerate() {
+                    write!(f, "{} {}", if i == 0 { ":" } else { "," }, mismatch)?;
+                }
+                Ok(())
+            }
+            PrinterError::NoActiveJob => write!(f, "There is no job on the printer"),
+            PrinterError::JobActive(id) => {
+                write!(f, "Job {} is still running, stop it first", id)
+            }
+            PrinterError::FirmwareTooOld {
+                required,
+                found: Some(found),
+            } => write!(
+                f,
+                "PrusaLink {} or newer is required, the printer runs {}",
+                required, found
+            ),
+            PrinterError::FirmwareTooOld {
+                required,
+                found: None,
+            } => write!(
+                f,
+                "PrusaLink {} or newer is required, the printer didn't report its version",
+                required
+            ),
+        }
+    }
+}
+
+impl Error for PrinterError {}
+
+/// Why `PrinterBuilder::try_build()` rejected the configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    EmptyApiKey,
+
+    /// The port is not between 1 and 65535
+    InvalidPort(u32),
+
+    InvalidAddress(AddressError),
+
+    /// The crate was built without the `reqwest` feature and no transport was given
+    MissingTransport,
+
+    /// The url given to `PrinterBuilder::proxy()` can't be used, with the message of reqwest
+    InvalidProxy(String),
+
+    /// A header given to `PrinterBuilder::header()` has an invalid name or value, with the name of the header
+    InvalidHeader(String),
+
+    /// A variable `PrinterBuilder::from_env()` needs is not set
+    MissingEnvVar(&'static str),
+
+    /// A variable read by `PrinterBuilder::from_env()` has a value that can't be used, given as the second field
+    InvalidEnvVar(&'static str, String),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::EmptyApiKey => write!(f, "The api key is empty"),
+            BuildError::InvalidPort(port) => {
+                write!(f, "Invalid port {}, it must be between 1 and 65535", port)
+            }
+            BuildError::InvalidAddress(err) => write!(f, "Invalid address: {}", err),
+            BuildError::MissingTransport => write!(
+                f,
+                "No transport was given, and the reqwest feature is disabled"
+            ),
+            BuildError::InvalidProxy(message) => write!(f, "Invalid proxy: {}", message),
+            BuildError::InvalidHeader(name) => write!(f, "Invalid header {}", name),
+            BuildError::MissingEnvVar(name) => {
+                write!(f, "The environment variable {} is not set", name)
+            }
+            BuildError::InvalidEnvVar(name, value) => {
+                write!(
+                    f,
+                    "Invalid value `{}` of the environment variable {}",
+                    value, name
+                )
+            }
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BuildError::InvalidAddress(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<AddressError> for BuildError {
+    fn from(err: AddressError) -> Self {
+        BuildError::InvalidAddress(err)
+    }
+}
+
+/// Why `Printer::check_connection()` failed, with enough detail to tell the user what to fix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionError {
+    /// The host couldn't be reached, the message is the underlying error
+    Unreachable(String),
+
+    /// The printer rejected the api key
+    InvalidApiKey,
+
+    /// Something answered, but not over the expected protocol or not as PrusaLink,
+    /// for example https was used for a http printer
+    WrongProtocol(String),
+
+    /// The printer answered with an unexpected status code
+    UnexpectedStatus(u16),
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::Unreachable(reason) => {
+                write!(f, "The printer is unreachable: {}", reason)
+            }
+            ConnectionError::InvalidApiKey => write!(f, "The printer rejected the api key"),
+            ConnectionError::WrongProtocol(reason) => write!(
+                f,
+                "The printer didn't answer as PrusaLink, check the scheme and port: {}",
+                reason
+            ),
+            ConnectionError::UnexpectedStatus(status) => {
+                write!(f, "The printer responded with status {}", status)
+            }
+        }
+    }
+}
+
+impl Error for ConnectionError {}
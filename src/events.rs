@@ -0,0 +1,128 @@
+use crate::{
+    raw_printer::{LinkState, RawPrinter},
+    units::Celsius,
+};
+
+/// How close, in degrees celsius, the actual temperature has to be to the target
+/// temperature for it to count as reached
+pub const TEMPERATURE_TOLERANCE: f32 = 2.0;
+
+/// A heater of the printer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Heater {
+    Nozzle,
+    Bed,
+}
+
+/// Something that happened on the printer between two refreshes,
+/// as yielded by `Printer::watch()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrinterEvent {
+    /// The link state of the printer changed
+    StateChanged { from: LinkState, to: LinkState },
+
+    /// The printer started a new print
+    PrintStarted,
+
+    /// The print ended without being stopped or running into an error
+    PrintFinished,
+
+    /// The print was stopped or ended with an error
+    PrintFailed,
+
+    /// A heater reached its target temperature
+    TemperatureReached {
+        heater: Heater,
+        temperature: Celsius,
+    },
+
+    /// The printer information could not be refreshed, the message is the error that occurred
+    RefreshFailed(String),
+}
+
+impl PrinterEvent {
+    /// Returns the kind of the event, which handlers registered with `Printer::on()` are selected by
+    pub fn kind(&self) -> EventKind {
+        match self {
+            PrinterEvent::StateChanged { .. } => EventKind::StateChanged,
+            PrinterEvent::PrintStarted => EventKind::PrintStarted,
+            PrinterEvent::PrintFinished => EventKind::PrintFinished,
+            PrinterEvent::PrintFailed => EventKind::PrintFailed,
+            PrinterEvent::TemperatureReached { .. } => EventKind::TemperatureReached,
+            PrinterEvent::RefreshFailed(_) => EventKind::RefreshFailed,
+        }
+    }
+}
+
+/// The kinds of `PrinterEvent`, without the data the events carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    StateChanged,
+    PrintStarted,
+    PrintFinished,
+    PrintFailed,
+    TemperatureReached,
+    RefreshFailed,
+}
+
+/// Compares two consecutive snapshots of the printer and returns the events that
+/// happened between them, in the order they should be reported.
+pub fn diff_events(previous: &RawPrinter, current: &RawPrinter) -> Vec<PrinterEvent> {
+    let mut events = Vec::new();
+
+    if let Some((from, to)) = previous.diff(current).state {
+        events.push(PrinterEvent::StateChanged { from, to });
+    }
+
+    let was_printing = previous.get_printing() || previous.get_paused();
+    let is_printing = current.get_printing() || current.get_paused();
+
+    if !was_printing && is_printing {
+        events.push(PrinterEvent::PrintStarted);
+    } else if was_printing && !is_printing {
+        let failed = current.get_error()
+            || matches!(current.link_state(), LinkState::Stopped | LinkState::Error);
+
+        events.push(if failed {
+            PrinterEvent::PrintFailed
+        } else {
+            PrinterEvent::PrintFinished
+        });
+    }
+
+    for heater in [Heater::Nozzle, Heater::Bed] {
+        let target = heater_target(current, heater);
+
+        if temperature_reached(current, heater)
+            && !(temperature_reached(previous, heater) && heater_target(previous, heater) == target)
+        {
+            events.push(PrinterEvent::TemperatureReached {
+                heater,
+                temperature: target,
+            });
+        }
+    }
+
+    events
+}
+
+fn heater_target(printer: &RawPrinter, heater: Heater) -> Celsius {
+    match heater {
+        Heater::Nozzle => printer.get_target_nozzle_temp(),
+        Heater::Bed => printer.get_target_bed_temp(),
+    }
+}
+
+fn heater_actual(printer: &RawPrinter, heater: Heater) -> Celsius {
+    match heater {
+        Heater::Nozzle => printer.get_nozzle_temp(),
+        Heater::Bed => printer.get_bed_temp(),
+    }
+}
+
+/// A heater only counts as having reached its temperature if it is actually heating
+fn temperature_reached(printer: &RawPrinter, heater: Heater) -> bool {
+    let target = heater_target(printer, heater).value();
+
+    target > 0.0 && (heater_actual(printer, heater).value() - target).abs() <= TEMPERATURE_TOLERANCE
+}
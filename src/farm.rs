@@ -0,0 +1,269 @@
+//! Managing many printers at once, for print farms.
+
+use std::{
+    collections::{hash_map::RandomState, BTreeMap},
+    error::Error,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use futures::{stream, StreamExt};
+use serde::Serialize;
+
+use crate::{
+    error::PrinterError,
+    raw_job::RawJob,
+    raw_printer::RawPrinter,
+    time::{self, Instant},
+    Printer,
+};
+
+/// The number of printers refreshed at the same time by default
+pub const DEFAULT_PARALLELISM: usize = 8;
+
+/// The state of a printer in a `Farm` as of its last refresh
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PrinterSnapshot {
+    pub printer: RawPrinter,
+
+    /// The current job, always None on printers without the job endpoint
+    pub job: Option<RawJob>,
+}
+
+impl PrinterSnapshot {
+    /// Returns the snapshot as a single line of json, with the printer and the job
+    /// in the format PrusaLink sends them, for logging or time-series ingestion
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Same as `to_json()`, but indented to be read by people
+    pub fn to_pretty_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Holds many printers by name, refreshing them concurrently.
+///
+/// The queries like `idle_printers()` use the state of the last `refresh_all()`,
+/// printers that haven't been refreshed successfully yet are left out of them.
+///
+/// # Example
+///
+/// ```no_run
+/// # use prusa_link_rs::{farm::Farm, PrinterBuilder};
+/// # use std::time::Duration;
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// let mut farm = Farm::new()
+///     .parallelism(4)
+///     .spread(Duration::from_secs(5))
+///     .jitter(Duration::from_millis(500));
+/// farm.add("mk4-a", PrinterBuilder::new("192.168.1.50", "api_key").build());
+/// farm.add("mini", PrinterBuilder::new("192.168.1.51", "api_key").build());
+///
+/// for (name, result) in farm.refresh_all().await {
+///     if let Err(err) = result {
+///         println!("{} is unavailable: {}", name, err);
+///     }
+/// }
+///
+/// println!("{} jobs running", farm.jobs_running());
+/// println!("Idle: {:?}", farm.idle_printers());
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct Farm {
+    printers: BTreeMap<String, Printer>,
+    parallelism: usize,
+    spread: Duration,
+    jitter: Duration,
+}
+
+impl Default for Farm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Farm {
+    pub fn new() -> Self {
+        Self {
+            printers: BTreeMap::new(),
+            parallelism: DEFAULT_PARALLELISM,
+            spread: Duration::ZERO,
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// Use this function to change how many printers are refreshed at the same time,
+    /// by default `DEFAULT_PARALLELISM`. Values below 1 are treated as 1.
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Use this function to spread the refreshes of `refresh_all()` evenly over the given duration
+    /// instead of starting them all at once, usually the interval `refresh_all()` is called at.
+    ///
+    /// The printers are started in alphabetical order, the first one right away.
+    pub fn spread(mut self, spread: Duration) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Use this function to delay every refresh of `refresh_all()` by a random time of up to `jitter`,
+    /// so farms polling on the same interval drift apart instead of sending their requests in bursts.
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Adds a printer under the given name, returning the printer it replaces
+    pub fn add(&mut self, name: impl Into<String>, printer: Printer) -> Option<Printer> {
+        self.printers.insert(name.into(), printer)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Printer> {
+        self.printers.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Printer> {
+        self.printers.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Printer> {
+        self.printers.get_mut(name)
+    }
+
+    /// Returns the names of the printers, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.printers.keys().map(String::as_str)
+    }
+
+    /// Returns the printers with their names, in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Printer)> {
+        self.printers
+            .iter()
+            .map(|(name, printer)| (name.as_str(), printer))
+    }
+
+    /// Returns the printers with their names, in alphabetical order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut Printer)> {
+        self.printers
+            .iter_mut()
+            .map(|(name, printer)| (name.as_str(), printer))
+    }
+
+    pub fn len(&self) -> usize {
+        self.printers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.printers.is_empty()
+    }
+
+    /// Refreshes the printer information and the job of every printer,
+    /// with at most `parallelism` printers at the same time.
+    ///
+    /// With `spread()` or `jitter()` every printer waits for its turn before it is refreshed,
+    /// so this takes at least as long as the spread.
+    ///
+    /// Returns the result of every printer keyed by name, a failing printer doesn't stop
+    /// the others from being refreshed. Use `refresh_errors()` to only get the failures.
+    pub async fn refresh_all(&mut self) -> BTreeMap<String, Result<(), Box<dyn Error>>> {
+        let started = Instant::now();
+        let count = self.printers.len() as u32;
+        let delays = (0..count)
+            .map(|index| self.spread * index / count.max(1) + random_duration(self.jitter))
+            .collect::<Vec<_>>();
+
+        stream::iter(self.printers.iter_mut().zip(delays))
+            .map(|((name, printer), delay)| async move {
+                // The delay counts from the start of the sweep, not from when a slot became free
+                let wait = delay.saturating_sub(started.elapsed());
+                if !wait.is_zero() {
+                    time::sleep(wait).await;
+                }
+
+                (name.clone(), refresh_printer(printer).await)
+            })
+            .buffer_unordered(self.parallelism)
+            .collect()
+            .await
+    }
+
+    /// Refreshes every printer like `refresh_all()`, returning the errors of the printers
+    /// that couldn't be refreshed, keyed by name
+    pub async fn refresh_errors(&mut self) -> BTreeMap<String, Box<dyn Error>> {
+        self.refresh_all()
+            .await
+            .into_iter()
+            .filter_map(|(name, result)| result.err().map(|err| (name, err)))
+            .collect()
+    }
+
+    /// Returns the state of every printer as of the last refresh, keyed by name
+    pub fn snapshots(&self) -> BTreeMap<&str, PrinterSnapshot> {
+        self.printers
+            .iter()
+            .filter_map(|(name, printer)| {
+                let snapshot = PrinterSnapshot {
+                    printer: printer.printer.clone()?,
+                    job: printer.job.clone(),
+                };
+
+                Some((name.as_str(), snapshot))
+            })
+            .collect()
+    }
+
+    /// Returns the names of the printers that are not doing anything and have no errors
+    pub fn idle_printers(&self) -> Vec<&str> {
+        self.names_where(RawPrinter::is_idle)
+    }
+
+    /// Returns the names of the printers that report an error
+    pub fn printers_with_errors(&self) -> Vec<&str> {
+        self.names_where(RawPrinter::has_error)
+    }
+
+    /// Returns the number of printers that are printing or have a paused print
+    pub fn jobs_running(&self) -> usize {
+        self.names_where(|printer| printer.get_printing() || printer.get_paused())
+            .len()
+    }
+
+    fn names_where<F>(&self, predicate: F) -> Vec<&str>
+    where
+        F: Fn(&RawPrinter) -> bool,
+    {
+        self.printers
+            .iter()
+            .filter(|(_, printer)| printer.printer.as_ref().is_some_and(&predicate))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// Refreshes the printer and its job, printers without the job endpoint are refreshed without it
+pub(crate) async fn refresh_printer(printer: &mut Printer) -> Result<(), Box<dyn Error>> {
+    printer.refresh().await?;
+
+    match printer.refresh_job().await {
+        Err(err) if matches!(err.downcast_ref(), Some(PrinterError::Unsupported(_))) => Ok(()),
+        result => result,
+    }
+}
+
+/// Returns a random duration of at most `max`
+fn random_duration(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+
+    // Every `RandomState` gets different random keys, which is random enough for jitter
+    let random = RandomState::new().build_hasher().finish();
+
+    max.mul_f64(random as f64 / u64::MAX as f64)
+}
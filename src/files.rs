@@ -0,0 +1,184 @@
+//! The files of a storage as a tree, as returned by `Printer::file_tree()`.
+//!
+//! Paths are relative to the root of the storage, without a leading slash, and are built from
+//! the long names of the files, like `projects/benchy.bgcode`. Storages on the printer are FAT
+//! formatted, so paths and patterns are compared ignoring ASCII case.
+
+use crate::raw_storage::FileEntry;
+
+/// A file or folder with the files and folders below it
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::{files::FileTree, raw_storage::FileEntry};
+///
+/// let root: FileEntry = serde_json::from_str(
+///     r#"{
+///         "type": "FOLDER",
+///         "name": "usb",
+///         "children": [
+///             { "type": "FOLDER", "name": "projects", "children": [
+///                 { "type": "PRINT_FILE", "name": "benchy.bgcode", "size": 4237152 }
+///             ] },
+///             { "type": "PRINT_FILE", "name": "cube.gcode", "size": 81234 }
+///         ]
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// let tree = FileTree::new("", root);
+///
+/// assert!(tree.find("projects/benchy.bgcode").is_some());
+/// assert_eq!(tree.filter("*.bgcode").count(), 1);
+/// assert_eq!(tree.files().count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTree {
+    /// The path of the entry from the root of the storage, empty for the root
+    pub path: String,
+
+    /// The entry itself, its `children` are moved to `FileTree::children`
+    pub entry: FileEntry,
+
+    pub children: Vec<FileTree>,
+}
+
+impl FileTree {
+    /// Creates a tree from an entry with its children filled in, `path` is the path of the entry
+    pub fn new(path: impl Into<String>, mut entry: FileEntry) -> Self {
+        let path = path.into().trim_matches('/').to_string();
+        let children = std::mem::take(&mut entry.children)
+            .into_iter()
+            .map(|child| FileTree::new(join(&path, child.display_name()), child))
+            .collect();
+
+        Self {
+            path,
+            entry,
+            children,
+        }
+    }
+
+    pub fn is_folder(&self) -> bool {
+        self.entry.is_folder()
+    }
+
+    /// Returns the entry and everything below it, depth first and in the order the printer listed them
+    pub fn iter(&self) -> FileTreeIter<'_> {
+        FileTreeIter { stack: vec![self] }
+    }
+
+    /// Returns every file below the entry, leaving out the folders
+    pub fn files(&self) -> impl Iterator<Item = &FileTree> {
+        self.iter().filter(|tree| !tree.is_folder())
+    }
+
+    /// Returns the entry at `path`, relative to the root of the storage like `FileTree::path`
+    pub fn find(&self, path: &str) -> Option<&FileTree> {
+        let path = path.trim_matches('/');
+
+        self.iter()
+            .find(|tree| tree.path.eq_ignore_ascii_case(path))
+    }
+
+    /// Returns the files matching a glob pattern, see `glob_match()`
+    pub fn filter<'a>(&'a self, pattern: &'a str) -> impl Iterator<Item = &'a FileTree> {
+        self.files()
+            .filter(move |tree| glob_match(pattern, &tree.path))
+    }
+}
+
+impl<'a> IntoIterator for &'a FileTree {
+    type Item = &'a FileTree;
+    type IntoIter = FileTreeIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Depth first iterator over a `FileTree`, returned by `FileTree::iter()`
+#[derive(Debug, Clone)]
+pub struct FileTreeIter<'a> {
+    stack: Vec<&'a FileTree>,
+}
+
+impl<'a> Iterator for FileTreeIter<'a> {
+    type Item = &'a FileTree;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = self.stack.pop()?;
+        self.stack.extend(tree.children.iter().rev());
+
+        Some(tree)
+    }
+}
+
+/// Returns true if `path` matches the glob `pattern`, ignoring ASCII case.
+///
+/// `*` matches any part of a name, `?` any single character, and `**` any number of folders.
+/// A pattern without a `/`, like `*.bgcode`, is matched against the file name only,
+/// so it matches files in every folder.
+///
+/// ```rust
+/// use prusa_link_rs::files::glob_match;
+///
+/// assert!(glob_match("*.bgcode", "projects/benchy.bgcode"));
+/// assert!(glob_match("projects/**/*.gcode", "projects/2024/march/cube.gcode"));
+/// assert!(glob_match("projects/**/*.gcode", "projects/cube.gcode"));
+/// assert!(!glob_match("projects/*.gcode", "projects/2024/cube.gcode"));
+/// ```
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let path = path.trim_matches('/');
+    let pattern = pattern.trim_matches('/');
+
+    if !pattern.contains('/') {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        return name_match(pattern, name);
+    }
+
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+
+    segments_match(&pattern, &path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path)) => name_match(segment, name) && segments_match(rest, path),
+            None => false,
+        },
+    }
+}
+
+/// Matches a single name against a pattern with `*` and `?`
+fn name_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    chars_match(&pattern, &name)
+}
+
+fn chars_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| chars_match(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && chars_match(rest, &name[1..]),
+        Some((char, rest)) => name
+            .first()
+            .is_some_and(|first| first.eq_ignore_ascii_case(char) && chars_match(rest, &name[1..])),
+    }
+}
+
+/// Appends a name to a path of a `FileTree`
+pub(crate) fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", path, name)
+    }
+}
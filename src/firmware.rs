@@ -0,0 +1,155 @@
+//! Rolling out firmware updates, see `Printer::upload_firmware()`.
+//!
+//! The printers with the Buddy board (MK4, XL, MINI) flash a `.bbf` firmware file found in the root
+//! of the usb stick after a restart, once the update is confirmed on the touchscreen or with the knob.
+//! `upload_firmware()` puts the file there, and `firmware_update_status()` shows the running version
+//! and the files still waiting to be flashed, so a farm can be updated without walking usb sticks around.
+
+use std::{error::Error, path::Path};
+
+use crate::{
+    capabilities::RawVersion,
+    error::PrinterError,
+    model::{parse_version, Version},
+    raw_storage::FileEntry,
+    storage::Storage,
+    upload::UploadSource,
+    Printer,
+};
+
+/// Extension of the firmware files of the Buddy board
+pub const FIRMWARE_EXTENSION: &str = "bbf";
+
+/// The running firmware and the firmware files waiting on the usb stick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirmwareUpdateStatus {
+    /// The version the printer runs, None if it didn't report it
+    pub current: Option<Version>,
+
+    /// The `.bbf` files in the root of the usb stick
+    pub pending: Vec<FileEntry>,
+}
+
+impl FirmwareUpdateStatus {
+    /// Returns the highest version found in the names of the pending files,
+    /// like `6.1.3` from `MK4_firmware_6.1.3.bbf`
+    pub fn pending_version(&self) -> Option<Version> {
+        self.pending
+            .iter()
+            .filter_map(|file| version_in_name(file.display_name()))
+            .max()
+    }
+
+    /// Returns true if a pending file is newer than the running firmware, or if either version
+    /// is unknown while there is a pending file
+    pub fn update_pending(&self) -> bool {
+        match (&self.current, self.pending_version()) {
+            (Some(current), Some(pending)) => pending > *current,
+            _ => !self.pending.is_empty(),
+        }
+    }
+}
+
+impl Printer {
+    /// Uploads a firmware file to the root of the usb stick, where the printer finds it after a restart.
+    /// `file_name` is the name the file gets on the printer and has to end in `.bbf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::InvalidFile` without sending anything if the name isn't a `.bbf` file
+    /// or contains a folder. Upload errors are returned as with `upload_file()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::path::Path;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let firmware = Path::new("MK4_firmware_6.1.3.bbf");
+    /// printer
+    ///     .upload_firmware("MK4_firmware_6.1.3.bbf", firmware)
+    ///     .await
+    ///     .unwrap();
+    /// printer.reboot(true).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn upload_firmware(
+        &self,
+        file_name: &str,
+        source: impl Into<UploadSource>,
+    ) -> Result<(), Box<dyn Error>> {
+        if file_name.contains(['/', '\\']) {
+            return Err(PrinterError::InvalidFile(format!(
+                "{} has to be in the root of the usb stick",
+                file_name
+            ))
+            .into());
+        }
+
+        if !is_firmware(file_name) {
+            return Err(PrinterError::InvalidFile(format!(
+                "{} isn't a .{} firmware file",
+                file_name, FIRMWARE_EXTENSION
+            ))
+            .into());
+        }
+
+        let _lock = self.lock_commands("upload firmware").await?;
+        self.upload_source(&Storage::Usb, file_name, source.into(), false)
+            .await
+    }
+
+    /// Returns the firmware version the printer runs and the firmware files waiting on the usb stick.
+    ///
+    /// The version is requested again every time, so it shows the new version after the update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the version or the files on the usb stick can't be read.
+    pub async fn firmware_update_status(&self) -> Result<FirmwareUpdateStatus, Box<dyn Error>> {
+        let res = self
+            .send(self.request(http::Method::GET, "/api/version"))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the version, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let version = serde_json::from_slice::<RawVersion>(res.body())?;
+        let pending = self
+            .list_files(Storage::Usb, "")
+            .await?
+            .into_iter()
+            .filter(|file| !file.is_folder() && is_firmware(file.display_name()))
+            .collect();
+
+        Ok(FirmwareUpdateStatus {
+            current: version.firmware.as_deref().and_then(parse_version),
+            pending,
+        })
+    }
+}
+
+/// Returns true if the file at `path` is a firmware file, judging by its extension
+pub fn is_firmware(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(FIRMWARE_EXTENSION))
+}
+
+/// Returns the first version like `6.1.3` in the name of a firmware file
+fn version_in_name(name: &str) -> Option<Version> {
+    let stem = name.strip_suffix(".bbf").unwrap_or(name);
+
+    stem.split(['_', '-', ' '])
+        .filter(|part| part.contains('.'))
+        .find_map(parse_version)
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{BufRead, Read},
+    time::Duration,
+};
+
+use crate::{error::PrinterError, upload::validate_bgcode_header};
+
+/// Metadata of a sliced file, as written by PrusaSlicer.
+///
+/// The commonly needed values are parsed into fields, everything else is available in `entries`
+/// under the key PrusaSlicer uses, for example `bed_temperature` or `fill_density`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GcodeMetadata {
+    /// Estimated print time in normal mode
+    pub estimated_time: Option<Duration>,
+
+    /// Filament used in millimeters
+    pub filament_used_mm: Option<f32>,
+
+    /// Filament used in grams
+    pub filament_used_g: Option<f32>,
+
+    pub layer_height: Option<f32>,
+
+    /// Nozzle temperature after the first layer, in degrees celsius
+    pub nozzle_temp: Option<f32>,
+
+    /// Nozzle diameter the file was sliced for, in millimeters
+    pub nozzle_diameter: Option<f32>,
+
+    /// Filament type the file was sliced for, for example `PLA`
+    pub filament_type: Option<String>,
+
+    /// Printer model the file was sliced for, for example `MK4`
+    pub printer_model: Option<String>,
+
+    /// All key value pairs found in the file
+    pub entries: HashMap<String, String>,
+}
+
+impl GcodeMetadata {
+    /// Builds the metadata from the raw key value pairs
+    pub(crate) fn from_entries(entries: HashMap<String, String>) -> Self {
+        let number = |key: &str| entries.get(key).and_then(|value| parse_first_number(value));
+        let text = |key: &str| {
+            entries
+                .get(key)
+                .map(|value| first_value(value).to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            estimated_time: entries
+                .get("estimated printing time (normal mode)")
+                .and_then(|value| parse_print_time(value)),
+            filament_used_mm: number("filament used [mm]"),
+            filament_used_g: number("filament used [g]"),
+            layer_height: number("layer_height"),
+            nozzle_temp: number("temperature"),
+            nozzle_diameter: number("nozzle_diameter"),
+            filament_type: text("filament_type"),
+            printer_model: text("printer_model"),
+            entries,
+        }
+    }
+}
+
+/// Reads the metadata of a local `.gcode` or `.bgcode` file, chosen by the extension.
+///
+/// The file is read on a blocking thread, since plain g-code files have their metadata at the end.
+/// Not available on wasm, use `parse_gcode()` or `parse_bgcode()` on the contents of the file there.
+///
+/// # Errors
+///
+/// Returns an Err if the file can't be read, or a `PrinterError::InvalidFile` if a binary
+/// g-code file is corrupted.
+///
+/// # Example
+///
+/// ```no_run
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// let metadata = prusa_link_rs::gcode_meta::read_metadata("benchy.bgcode").await.unwrap();
+///
+/// if metadata.printer_model.as_deref() != Some("MK4") {
+///     println!("This file wasn't sliced for a MK4");
+/// }
+/// # })
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn read_metadata(
+    path: impl AsRef<std::path::Path>,
+) -> Result<GcodeMetadata, Box<dyn Error>> {
+    let path = path.as_ref().to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let file = std::io::BufReader::new(std::fs::File::open(&path)?);
+
+        if crate::upload::is_bgcode(&path.to_string_lossy()) {
+            parse_bgcode(file)
+        } else {
+            Ok(parse_gcode(file)?)
+        }
+    })
+    .await?
+    .map_err(|err| err as Box<dyn Error>)
+}
+
+/// Parses the `; key = value` comments PrusaSlicer writes into plain g-code files
+pub fn parse_gcode<R: BufRead>(reader: R) -> std::io::Result<GcodeMetadata> {
+    let mut entries = HashMap::new();
+
+    for line in reader.split(b'\n') {
+        // G-code files aren't guaranteed to be valid utf-8, and only comments are interesting anyway
+        let line = String::from_utf8_lossy(&line?).into_owned();
+
+        if let Some((key, value)) = line
+            .strip_prefix(';')
+            .and_then(|comment| comment.split_once('='))
+        {
+            entries
+                .entry(key.trim().to_string())
+                .or_insert_with(|| value.trim().to_string());
+        }
+    }
+
+    Ok(GcodeMetadata::from_entries(entries))
+}
+
+/// Block types of binary g-code files that contain metadata
+const BGCODE_METADATA_BLOCKS: [u16; 4] = [0, 2, 3, 4];
+
+/// Block type of g-code blocks, after the first one no more metadata follows
+const BGCODE_GCODE_BLOCK: u16 = 1;
+
+/// Block type of thumbnails, which have bigger parameters than the other blocks
+const BGCODE_THUMBNAIL_BLOCK: u16 = 5;
+
+/// Metadata blocks are a few kilobytes, bigger ones are refused instead of being read into memory
+const BGCODE_METADATA_LIMIT: u64 = 1024 * 1024;
+
+/// Reads the data of a metadata block, `data_size` comes from the file and can't be trusted
+fn read_bgcode_block(
+    reader: &mut impl Read,
+    data_size: u32,
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    if u64::from(data_size) > BGCODE_METADATA_LIMIT {
+        return Err(too_large_block().into());
+    }
+
+    let mut data = Vec::new();
+    reader.take(data_size.into()).read_to_end(&mut data)?;
+
+    if data.len() < data_size as usize {
+        return Err(PrinterError::InvalidFile("the file is truncated".to_string()).into());
+    }
+
+    Ok(data)
+}
+
+fn too_large_block() -> PrinterError {
+    PrinterError::InvalidFile(format!(
+        "a metadata block is larger than {} bytes",
+        BGCODE_METADATA_LIMIT
+    ))
+}
+
+/// Parses the metadata blocks of a binary g-code file.
+///
+/// Uncompressed and deflate compressed metadata blocks are supported,
+/// which covers what PrusaSlicer writes with its default settings.
+///
+/// # Errors
+///
+/// Returns a `PrinterError::InvalidFile` if the file is not a valid binary g-code file,
+/// or if a metadata block is larger than 1 MiB.
+pub fn parse_bgcode<R: Read>(mut reader: R) -> Result<GcodeMetadata, Box<dyn Error + Send + Sync>> {
+    let mut header = [0; 10];
+    match reader.read_exact(&mut header) {
+        Ok(()) => validate_bgcode_header(&header)?,
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+            return Err(PrinterError::InvalidFile("the file is too short".to_string()).into())
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    let has_checksum = u16::from_le_bytes([header[8], header[9]]) == 1;
+    let mut entries = HashMap::new();
+
+    loop {
+        let mut block_header = [0; 8];
+        match reader.read_exact(&mut block_header) {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+
+        let block_type = u16::from_le_bytes([block_header[0], block_header[1]]);
+        let compression = u16::from_le_bytes([block_header[2], block_header[3]]);
+        let uncompressed_size = u32::from_le_bytes([
+            block_header[4],
+            block_header[5],
+            block_header[6],
+            block_header[7],
+        ]);
+
+        if block_type == BGCODE_GCODE_BLOCK {
+            break;
+        }
+
+        let data_size = if compression == 0 {
+            uncompressed_size
+        } else {
+            let mut size = [0; 4];
+            reader.read_exact(&mut size)?;
+            u32::from_le_bytes(size)
+        };
+
+        let params_size = if block_type == BGCODE_THUMBNAIL_BLOCK {
+            6
+        } else {
+            2
+        };
+        std::io::copy(&mut (&mut reader).take(params_size), &mut std::io::sink())?;
+
+        // Heatshrink is only used for g-code by PrusaSlicer, skip anything else
+        let metadata = BGCODE_METADATA_BLOCKS.contains(&block_type) && compression <= 1;
+        let data = if metadata {
+            read_bgcode_block(&mut reader, data_size)?
+        } else {
+            let skipped = std::io::copy(
+                &mut (&mut reader).take(data_size.into()),
+                &mut std::io::sink(),
+            )?;
+            if skipped < data_size.into() {
+                return Err(PrinterError::InvalidFile("the file is truncated".to_string()).into());
+            }
+            Vec::new()
+        };
+
+        if has_checksum {
+            std::io::copy(&mut (&mut reader).take(4), &mut std::io::sink())?;
+        }
+
+        if !metadata {
+            continue;
+        }
+
+        let data = if compression == 1 {
+            let mut inflated = Vec::new();
+            flate2::read::ZlibDecoder::new(data.as_slice())
+                .take(BGCODE_METADATA_LIMIT + 1)
+                .read_to_end(&mut inflated)?;
+            if inflated.len() as u64 > BGCODE_METADATA_LIMIT {
+                return Err(too_large_block().into());
+            }
+            inflated
+        } else {
+            data
+        };
+
+        for line in String::from_utf8_lossy(&data).lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                entries
+                    .entry(key.trim().to_string())
+                    .or_insert_with(|| value.trim().to_string());
+            }
+        }
+    }
+
+    Ok(GcodeMetadata::from_entries(entries))
+}
+
+/// Multi extruder printers get comma separated values, of which the first one is used
+fn first_value(value: &str) -> &str {
+    value.split([',', ';']).next().unwrap_or_default().trim()
+}
+
+fn parse_first_number(value: &str) -> Option<f32> {
+    first_value(value).parse().ok()
+}
+
+/// Parses print times like `1d 2h 3m 4s` or `45m 12s`
+fn parse_print_time(value: &str) -> Option<Duration> {
+    let mut seconds = 0;
+
+    for part in value.split_whitespace() {
+        let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let number: u64 = number.parse().ok()?;
+
+        let unit = match unit {
+            "d" => 24 * 60 * 60,
+            "h" => 60 * 60,
+            "m" => 60,
+            "s" => 1,
+            _ => return None,
+        };
+
+        // A corrupt file can have any number here
+        seconds = number
+            .checked_mul(unit)
+            .and_then(|part| part.checked_add(seconds))?;
+    }
+
+    Some(Duration::from_secs(seconds))
+}
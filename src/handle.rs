@@ -0,0 +1,82 @@
+//! Sharing one printer between tasks, like an axum handler, a background poller and a command.
+//!
+//! A `PrinterHandle` is cheap to clone, every clone uses the same connection, cache and configuration.
+//! The functions of `Printer` that only need `&self`, like the commands, run at the same time through
+//! `read()`, while the getters that refresh the cache need `write()` and run one after another.
+//!
+//! ```no_run
+//! use prusa_link_rs::PrinterBuilder;
+//! use std::time::Duration;
+//! # use tokio_test::block_on;
+//! # block_on(async {
+//!
+//! let printer = PrinterBuilder::new("192.168.1.50", "api_key").build_handle();
+//!
+//! let poller = printer.clone();
+//! tokio::spawn(async move {
+//!     loop {
+//!         if let Err(err) = poller.write().await.refresh().await {
+//!             println!("Refresh failed: {}", err);
+//!         }
+//!         tokio::time::sleep(Duration::from_secs(5)).await;
+//!     }
+//! });
+//!
+//! println!("Nozzle is at {}", printer.write().await.get_nozzle_temp().await.unwrap());
+//! # })
+//! ```
+
+use std::{error::Error, sync::Arc};
+
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{health::Health, raw_printer::RawPrinter, stats::RequestStats, Printer};
+
+/// A shared `Printer`, see the module documentation
+#[derive(Debug, Clone)]
+pub struct PrinterHandle {
+    printer: Arc<RwLock<Printer>>,
+}
+
+impl PrinterHandle {
+    pub fn new(printer: Printer) -> Self {
+        Self {
+            printer: Arc::new(RwLock::new(printer)),
+        }
+    }
+
+    /// Waits until no clone is refreshing the printer, and returns it for the functions that take `&self`
+    pub async fn read(&self) -> RwLockReadGuard<'_, Printer> {
+        self.printer.read().await
+    }
+
+    /// Waits until no other clone uses the printer, and returns it for the functions that take `&mut self`.
+    ///
+    /// Keep the guard only as long as needed, the other clones wait while it is kept,
+    /// for example during `Printer::wait_until_finished()`.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, Printer> {
+        self.printer.write().await
+    }
+
+    /// Returns the printer information, refreshed if it is outdated.
+    /// The information is cloned, so no lock is kept afterwards
+    pub async fn printer_info(&self) -> Result<RawPrinter, Box<dyn Error>> {
+        Ok(self.write().await.cached_or_refresh().await?.clone())
+    }
+
+    /// Returns how the requests to the printer went recently, see `Printer::health()`
+    pub async fn health(&self) -> Health {
+        self.read().await.health()
+    }
+
+    /// Returns the requests sent by all clones, see `Printer::stats()`
+    pub async fn stats(&self) -> RequestStats {
+        self.read().await.stats()
+    }
+}
+
+impl From<Printer> for PrinterHandle {
+    fn from(printer: Printer) -> Self {
+        Self::new(printer)
+    }
+}
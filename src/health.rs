@@ -0,0 +1,101 @@
+//! Tracking whether a printer can be reached, read with `Printer::health()`.
+//!
+//! Every request opens its connection again if needed, so a printer that comes back after an outage
+//! is used again with the next request. The health only records how the last requests went.
+
+use std::time::Duration;
+
+use crate::{
+    error::PrinterError,
+    time::{Instant, SystemTime},
+};
+
+/// How the requests to a printer went recently.
+///
+/// A request counts as failed when the printer couldn't be reached or didn't answer in time.
+/// Responses with an error status count as successes, since the printer answered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Health {
+    /// The number of requests that failed since the last one that succeeded
+    pub consecutive_failures: u32,
+
+    /// When the printer last answered, None if it never did
+    pub last_success: Option<SystemTime>,
+
+    /// When a request last failed, None if none did
+    pub last_failure: Option<SystemTime>,
+
+    /// Why the last failed request failed
+    pub last_error: Option<String>,
+
+    failed_at: Option<Instant>,
+
+    /// When a request was last let through an open circuit to probe the printer
+    probe_at: Option<Instant>,
+}
+
+impl Health {
+    /// Returns true if the printer answered the last request
+    pub fn is_healthy(&self) -> bool {
+        self.last_success.is_some() && self.consecutive_failures == 0
+    }
+
+    pub(crate) fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.probe_at = None;
+        self.last_success = Some(SystemTime::now());
+    }
+
+    pub(crate) fn record_failure(&mut self, error: String) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_failure = Some(SystemTime::now());
+        self.last_error = Some(error);
+        self.failed_at = Some(Instant::now());
+    }
+}
+
+/// Stops sending requests to a printer that failed `threshold` requests in a row,
+/// until `cooldown` has passed since the last failure. A single request is then let through
+/// to probe whether the printer is back, the others still fail right away until it succeeds.
+/// If it fails the circuit stays open for another cooldown, and if it hasn't finished after
+/// a cooldown another request is let through.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Returns an error if the circuit is open and no request should be sent,
+    /// marks the request as the probe if it is let through an open circuit
+    pub(crate) fn check(&self, health: &mut Health) -> Result<(), PrinterError> {
+        let Some(failed_at) = health.failed_at else {
+            return Ok(());
+        };
+
+        if health.consecutive_failures < self.threshold {
+            return Ok(());
+        }
+
+        let waiting_since = health
+            .probe_at
+            .map_or(failed_at, |probe_at| probe_at.max(failed_at));
+        let retry_in = self.cooldown.saturating_sub(waiting_since.elapsed());
+        if retry_in.is_zero() {
+            health.probe_at = Some(Instant::now());
+            return Ok(());
+        }
+
+        Err(PrinterError::CircuitOpen {
+            failures: health.consecutive_failures,
+            retry_in,
+        })
+    }
+}
@@ -0,0 +1,232 @@
+use std::{collections::VecDeque, fmt::Write, time::Duration};
+
+use crate::{raw_printer::RawPrinter, time::SystemTime};
+
+/// The telemetry of the printer at a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySample {
+    pub timestamp: SystemTime,
+    pub nozzle_temp: f32,
+    pub bed_temp: f32,
+    pub z_height: Option<f32>,
+    pub print_speed: Option<f32>,
+}
+
+impl TelemetrySample {
+    /// Creates a sample from the telemetry of the printer, timestamped with the current time
+    pub fn from_printer(printer: &RawPrinter) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            nozzle_temp: printer.get_nozzle_temp().value(),
+            bed_temp: printer.get_bed_temp().value(),
+            z_height: printer.get_z_height_telemetry(),
+            print_speed: printer.get_print_speed_telemetry(),
+        }
+    }
+
+    fn get(&self, field: TelemetryField) -> Option<f32> {
+        match field {
+            TelemetryField::NozzleTemp => Some(self.nozzle_temp),
+            TelemetryField::BedTemp => Some(self.bed_temp),
+            TelemetryField::ZHeight => self.z_height,
+            TelemetryField::PrintSpeed => self.print_speed,
+        }
+    }
+}
+
+/// The header line of `TelemetryHistory::to_csv()`
+pub const CSV_HEADER: &str = "timestamp,nozzle_temp,bed_temp,z_height,print_speed";
+
+/// The values that are recorded in a `TelemetrySample`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TelemetryField {
+    NozzleTemp,
+    BedTemp,
+    ZHeight,
+    PrintSpeed,
+}
+
+/// Minimum, maximum and average of a telemetry field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeriesStats {
+    pub min: f32,
+    pub max: f32,
+    pub avg: f32,
+}
+
+/// Ring buffer of the last `capacity` telemetry samples.
+///
+/// Enable it with `PrinterBuilder::telemetry_history()` to have a sample recorded on every refresh,
+/// once the buffer is full the oldest sample is dropped.
+#[derive(Debug, Clone)]
+pub struct TelemetryHistory {
+    capacity: usize,
+    samples: VecDeque<TelemetrySample>,
+}
+
+impl TelemetryHistory {
+    /// Creates an empty history holding at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Adds a sample, dropping the oldest one if the history is full
+    pub fn push(&mut self, sample: TelemetrySample) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    /// Records the current telemetry of the printer
+    pub fn record(&mut self, printer: &RawPrinter) {
+        self.push(TelemetrySample::from_printer(printer));
+    }
+
+    /// Removes all samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the samples from oldest to newest
+    pub fn samples(&self) -> impl Iterator<Item = &TelemetrySample> {
+        self.samples.iter()
+    }
+
+    /// Returns the newest sample
+    pub fn latest(&self) -> Option<&TelemetrySample> {
+        self.samples.back()
+    }
+
+    /// Returns the timestamped values of a field from oldest to newest,
+    /// skipping samples where the printer didn't report the field
+    pub fn series(&self, field: TelemetryField) -> Vec<(SystemTime, f32)> {
+        self.samples
+            .iter()
+            .filter_map(|sample| sample.get(field).map(|value| (sample.timestamp, value)))
+            .collect()
+    }
+
+    pub fn nozzle_temps(&self) -> Vec<(SystemTime, f32)> {
+        self.series(TelemetryField::NozzleTemp)
+    }
+
+    pub fn bed_temps(&self) -> Vec<(SystemTime, f32)> {
+        self.series(TelemetryField::BedTemp)
+    }
+
+    pub fn z_heights(&self) -> Vec<(SystemTime, f32)> {
+        self.series(TelemetryField::ZHeight)
+    }
+
+    pub fn print_speeds(&self) -> Vec<(SystemTime, f32)> {
+        self.series(TelemetryField::PrintSpeed)
+    }
+
+    /// Returns the samples taken at most `window` before the newest sample
+    pub fn window(&self, window: Duration) -> impl Iterator<Item = &TelemetrySample> {
+        let start = self
+            .latest()
+            .and_then(|latest| latest.timestamp.checked_sub(window));
+
+        self.samples
+            .iter()
+            .filter(move |sample| start.is_none_or(|start| sample.timestamp >= start))
+    }
+
+    /// Returns the samples from oldest to newest as csv, starting with `CSV_HEADER`,
+    /// for spreadsheets and other tools.
+    ///
+    /// The timestamp is in seconds since the unix epoch with millisecond precision,
+    /// fields the printer didn't report are left empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use prusa_link_rs::history::{TelemetryHistory, TelemetrySample};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let mut history = TelemetryHistory::new(10);
+    /// history.push(TelemetrySample {
+    ///     timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_500),
+    ///     nozzle_temp: 215.0,
+    ///     bed_temp: 60.0,
+    ///     z_height: Some(0.2),
+    ///     print_speed: None,
+    /// });
+    ///
+    /// assert_eq!(
+    ///     history.to_csv(),
+    ///     "timestamp,nozzle_temp,bed_temp,z_height,print_speed\n1700000000.500,215,60,0.2,\n"
+    /// );
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let optional =
+            |value: Option<f32>| value.map(|value| value.to_string()).unwrap_or_default();
+
+        let mut csv = format!("{}\n", CSV_HEADER);
+
+        for sample in &self.samples {
+            let timestamp = sample
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default();
+
+            // Writing to a String can't fail
+            let _ = writeln!(
+                csv,
+                "{}.{:03},{},{},{},{}",
+                timestamp.as_secs(),
+                timestamp.subsec_millis(),
+                sample.nozzle_temp,
+                sample.bed_temp,
+                optional(sample.z_height),
+                optional(sample.print_speed),
+            );
+        }
+
+        csv
+    }
+
+    /// Returns the minimum, maximum and average of a field over the samples taken
+    /// at most `window` before the newest sample, or None if there are no values in the window
+    pub fn stats(&self, field: TelemetryField, window: Duration) -> Option<SeriesStats> {
+        let mut values = self.window(window).filter_map(|sample| sample.get(field));
+
+        let first = values.next()?;
+        let (mut min, mut max, mut sum, mut count) = (first, first, first, 1);
+
+        for value in values {
+            min = min.min(value);
+            max = max.max(value);
+            sum += value;
+            count += 1;
+        }
+
+        Some(SeriesStats {
+            min,
+            max,
+            avg: sum / count as f32,
+        })
+    }
+}
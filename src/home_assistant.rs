@@ -0,0 +1,215 @@
+//! Home Assistant MQTT discovery payloads, enabled with the `home-assistant` feature.
+//!
+//! Home Assistant creates an entity for every config published under its discovery prefix,
+//! at `<discovery prefix>/<component>/<object id>/config`. `Discovery` generates the configs
+//! for the sensors and buttons of a printer, reading from and writing to the topics given in `EntityTopics`.
+//!
+//! The sensors expect the payloads `mqtt::MqttBridge` publishes: the link state as the state payload,
+//! and a telemetry JSON object with the fields `nozzle_temp`, `target_nozzle_temp`, `bed_temp`,
+//! `target_bed_temp`, `progress` and `time_remaining`. The buttons publish `pause`, `resume` and `stop`
+//! on the command topic.
+
+use serde_json::{json, Value};
+
+use crate::raw_info::RawInfo;
+
+/// The discovery prefix Home Assistant listens on by default
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// The topics the entities of a printer read from and write to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityTopics {
+    /// Where `online` or `offline` is published
+    pub availability: String,
+
+    /// Where the link state of the printer is published
+    pub state: String,
+
+    /// Where the telemetry JSON object is published
+    pub telemetry: String,
+
+    /// Where the buttons publish their commands
+    pub command: String,
+}
+
+impl EntityTopics {
+    /// Returns the topics `availability`, `state`, `telemetry` and `command` under the given topic
+    pub fn under(base: &str) -> Self {
+        Self {
+            availability: format!("{}/availability", base),
+            state: format!("{}/state", base),
+            telemetry: format!("{}/telemetry", base),
+            command: format!("{}/command", base),
+        }
+    }
+}
+
+/// The config of one entity, to be published retained on `topic`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryEntity {
+    /// The kind of entity, `sensor` or `button`
+    pub component: &'static str,
+
+    /// What the entity is about within the printer, like `nozzle_temp`
+    pub key: &'static str,
+
+    pub topic: String,
+    pub config: Value,
+}
+
+/// Generates the discovery configs of a printer.
+///
+/// # Example
+///
+/// ```
+/// use prusa_link_rs::home_assistant::{Discovery, EntityTopics};
+///
+/// let discovery = Discovery::new("mk4", EntityTopics::under("prusalink/mk4")).name("Prusa MK4");
+///
+/// for entity in discovery.entities() {
+///     println!("{}: {}", entity.topic, entity.config);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discovery {
+    object_id: String,
+    name: String,
+    serial: Option<String>,
+    discovery_prefix: String,
+    topics: EntityTopics,
+}
+
+impl Discovery {
+    /// Creates the discovery of a printer, the object id is used in the topics and the unique ids of the entities
+    /// with everything but letters, digits, `-` and `_` replaced by `_`.
+    ///
+    /// The device is named after the object id until `name()` is used.
+    pub fn new(object_id: impl Into<String>, topics: EntityTopics) -> Self {
+        let object_id = object_id_of(&object_id.into());
+
+        Self {
+            name: object_id.clone(),
+            object_id,
+            serial: None,
+            discovery_prefix: DEFAULT_DISCOVERY_PREFIX.to_string(),
+            topics,
+        }
+    }
+
+    /// Creates the discovery of a printer with the name and serial number from its info,
+    /// see `Printer::get_info()`
+    pub fn from_info(object_id: impl Into<String>, info: &RawInfo, topics: EntityTopics) -> Self {
+        let mut discovery = Self::new(object_id, topics);
+
+        if let Some(name) = &info.name {
+            discovery.name = name.clone();
+        }
+        discovery.serial = info.serial.clone();
+
+        discovery
+    }
+
+    /// Use this function to set the name of the device shown in Home Assistant
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Use this function to publish under a different prefix than `DEFAULT_DISCOVERY_PREFIX`
+    pub fn discovery_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.discovery_prefix = prefix.into();
+        self
+    }
+
+    /// Returns the configs of the sensors and the buttons of the printer
+    pub fn entities(&self) -> Vec<DiscoveryEntity> {
+        let mut entities = vec![self.sensor("state", "State", &self.topics.state, None)];
+
+        for (key, name, unit) in [
+            ("nozzle_temp", "Nozzle temperature", "°C"),
+            ("target_nozzle_temp", "Nozzle target temperature", "°C"),
+            ("bed_temp", "Bed temperature", "°C"),
+            ("target_bed_temp", "Bed target temperature", "°C"),
+            ("progress", "Progress", "%"),
+            ("time_remaining", "Time remaining", "s"),
+        ] {
+            let mut entity = self.sensor(key, name, &self.topics.telemetry, Some(unit));
+
+            entity.config["value_template"] = Value::from(format!("{{{{ value_json.{} }}}}", key));
+            match unit {
+                "°C" => entity.config["device_class"] = Value::from("temperature"),
+                "s" => entity.config["device_class"] = Value::from("duration"),
+                _ => {}
+            }
+
+            entities.push(entity);
+        }
+
+        for (key, name) in [("pause", "Pause"), ("resume", "Resume"), ("stop", "Stop")] {
+            let mut entity = self.entity("button", key, name);
+
+            entity.config["command_topic"] = Value::from(self.topics.command.as_str());
+            entity.config["payload_press"] = Value::from(key);
+
+            entities.push(entity);
+        }
+
+        entities
+    }
+
+    fn sensor(
+        &self,
+        key: &'static str,
+        name: &str,
+        state_topic: &str,
+        unit: Option<&str>,
+    ) -> DiscoveryEntity {
+        let mut entity = self.entity("sensor", key, name);
+
+        entity.config["state_topic"] = Value::from(state_topic);
+        if let Some(unit) = unit {
+            entity.config["unit_of_measurement"] = Value::from(unit);
+        }
+
+        entity
+    }
+
+    fn entity(&self, component: &'static str, key: &'static str, name: &str) -> DiscoveryEntity {
+        let mut device = json!({
+            "identifiers": [format!("prusalink_{}", self.object_id)],
+            "name": self.name,
+            "manufacturer": "Prusa Research",
+        });
+        if let Some(serial) = &self.serial {
+            device["serial_number"] = Value::from(serial.as_str());
+        }
+
+        DiscoveryEntity {
+            component,
+            key,
+            topic: format!(
+                "{}/{}/{}_{}/config",
+                self.discovery_prefix, component, self.object_id, key
+            ),
+            config: json!({
+                "name": name,
+                "unique_id": format!("prusalink_{}_{}", self.object_id, key),
+                "availability_topic": self.topics.availability,
+                "device": device,
+            }),
+        }
+    }
+}
+
+/// Turns a name into something that can be used as a single topic level and in ids
+pub(crate) fn object_id_of(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,59 @@
+use std::{fmt, sync::Arc};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+
+use crate::{
+    events::{EventKind, PrinterEvent},
+    transport::TransportBody,
+};
+
+/// A closure called with every request before it is sent
+pub type RequestHook = Arc<dyn Fn(&mut http::Request<TransportBody>) + Send + Sync>;
+
+/// A closure called with every response the printer sends back
+pub type ResponseHook = Arc<dyn Fn(&http::Response<Bytes>) + Send + Sync>;
+
+/// An async callback called with the events of a kind, see `Printer::on()`
+pub type EventHandler = Arc<dyn Fn(PrinterEvent) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// The hooks registered with `PrinterBuilder::on_request()` and `PrinterBuilder::on_response()`,
+/// and the event handlers registered with `Printer::on()`, called in the order they were added
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    pub(crate) on_request: Vec<RequestHook>,
+    pub(crate) on_response: Vec<ResponseHook>,
+    pub(crate) on_event: Vec<(EventKind, EventHandler)>,
+}
+
+impl Hooks {
+    pub(crate) fn request(&self, request: &mut http::Request<TransportBody>) {
+        for hook in &self.on_request {
+            hook(request);
+        }
+    }
+
+    pub(crate) fn response(&self, response: &http::Response<Bytes>) {
+        for hook in &self.on_response {
+            hook(response);
+        }
+    }
+
+    pub(crate) async fn event(&self, event: &PrinterEvent) {
+        let kind = event.kind();
+
+        for (_, handler) in self.on_event.iter().filter(|(k, _)| *k == kind) {
+            handler(event.clone()).await;
+        }
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("on_request", &self.on_request.len())
+            .field("on_response", &self.on_response.len())
+            .field("on_event", &self.on_event.len())
+            .finish()
+    }
+}
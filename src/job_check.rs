@@ -0,0 +1,295 @@
+//! Checking that a file was sliced for the printer before printing it, see `Printer::start_print_checked()`.
+//!
+//! The slicer writes the material, nozzle diameter and printer model into the file. A file sliced
+//! for PETG prints badly with PLA loaded and a file sliced for a 0.6 mm nozzle doesn't print at all
+//! with a 0.4 mm one, but the printer only warns about it on its display, if at all.
+
+use std::{error::Error, fmt};
+
+use crate::{
+    error::PrinterError, gcode_meta::GcodeMetadata, material::Material, model::PrinterModel,
+    raw_info::RawInfo, raw_printer::RawPrinter, storage::Storage, Printer,
+};
+
+/// What `Printer::start_print_checked()` compares between the file and the printer.
+///
+/// Everything is checked by default. Values the file or the printer don't report are never a mismatch,
+/// files from other slicers usually have no metadata at all.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::{
+///     gcode_meta::GcodeMetadata,
+///     job_check::{JobExpectations, JobMismatch},
+///     material::Material,
+///     model::PrinterModel,
+///     raw_printer::{PrinterTelemetry, RawPrinter},
+/// };
+/// # use prusa_link_rs::raw_info::RawInfo;
+/// # let info: RawInfo = serde_json::from_str(r#"{"nozzle_diameter": 0.4}"#).unwrap();
+///
+/// let file = GcodeMetadata {
+///     filament_type: Some("PETG".to_string()),
+///     ..Default::default()
+/// };
+/// let printer = RawPrinter::builder()
+///     .telemetry(PrinterTelemetry {
+///         material: Some("PLA".to_string()),
+///         ..Default::default()
+///     })
+///     .build();
+///
+/// assert_eq!(
+///     JobExpectations::new().check(&file, &info, &printer, PrinterModel::Mk4),
+///     Err(vec![JobMismatch::Material {
+///         file: Material::Petg,
+///         loaded: Some(Material::Pla),
+///     }])
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JobExpectations {
+    material: bool,
+    nozzle_diameter: bool,
+    model: bool,
+    multi_material: bool,
+    nozzle_tolerance: f32,
+}
+
+impl Default for JobExpectations {
+    fn default() -> Self {
+        Self {
+            material: true,
+            nozzle_diameter: true,
+            model: true,
+            multi_material: true,
+            nozzle_tolerance: 0.01,
+        }
+    }
+}
+
+impl JobExpectations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Don't compare the material of the file with the loaded material,
+    /// for materials that print fine with the profile of another one
+    pub fn skip_material(mut self) -> Self {
+        self.material = false;
+        self
+    }
+
+    /// Don't compare the nozzle diameter of the file with the one set on the printer
+    pub fn skip_nozzle_diameter(mut self) -> Self {
+        self.nozzle_diameter = false;
+        self
+    }
+
+    /// Don't compare the printer model of the file with the model of the printer,
+    /// for files sliced for a compatible model like the MK4 and MK4S
+    pub fn skip_model(mut self) -> Self {
+        self.model = false;
+        self
+    }
+
+    /// Don't check that a file sliced for several filaments has a multi material unit or several tools to print on
+    pub fn skip_multi_material(mut self) -> Self {
+        self.multi_material = false;
+        self
+    }
+
+    /// How many millimeters the nozzle diameters may differ, 0.01 by default
+    pub fn nozzle_tolerance(mut self, nozzle_tolerance: f32) -> Self {
+        self.nozzle_tolerance = nozzle_tolerance;
+        self
+    }
+
+    /// Returns every mismatch between the metadata of the file and the printer.
+    /// `model` is the detected model of the printer, see `Printer::identity()`.
+    pub fn check(
+        &self,
+        file: &GcodeMetadata,
+        info: &RawInfo,
+        printer: &RawPrinter,
+        model: PrinterModel,
+    ) -> Result<(), Vec<JobMismatch>> {
+        let mut mismatches = Vec::new();
+
+        let filaments = file
+            .entries
+            .get("filament_type")
+            .map(|types| types.split(';').filter(|t| !t.trim().is_empty()).count())
+            .unwrap_or(0);
+        let multi_material = filaments > 1;
+
+        if self.multi_material
+            && multi_material
+            && !info.mmu.unwrap_or(false)
+            && printer.tool_count() < 2
+        {
+            mismatches.push(JobMismatch::MultiMaterial { filaments });
+        }
+
+        // With several filaments the telemetry only reports the one in use, so it can't be compared
+        let file_material = file.filament_type.as_deref().and_then(Material::parse);
+        let loaded = printer.get_material_telemetry().map(Material::parse);
+        if let (true, false, Some(file), Some(loaded)) =
+            (self.material, multi_material, file_material, loaded)
+        {
+            let matches = loaded
+                .as_ref()
+                .is_some_and(|loaded| loaded.name().eq_ignore_ascii_case(file.name()));
+
+            if !matches {
+                mismatches.push(JobMismatch::Material { file, loaded });
+            }
+        }
+
+        if let (true, Some(file), Some(printer)) = (
+            self.nozzle_diameter,
+            file.nozzle_diameter,
+            info.nozzle_diameter,
+        ) {
+            if (file - printer).abs() > self.nozzle_tolerance {
+                mismatches.push(JobMismatch::NozzleDiameter { file, printer });
+            }
+        }
+
+        let file_model = file
+            .printer_model
+            .as_deref()
+            .map(PrinterModel::from_name)
+            .unwrap_or(PrinterModel::Unknown);
+        if self.model
+            && file_model != PrinterModel::Unknown
+            && model != PrinterModel::Unknown
+            && file_model != model
+        {
+            mismatches.push(JobMismatch::Model {
+                file: file_model,
+                printer: model,
+            });
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+}
+
+/// A difference between a file and the printer, see `PrinterError::IncompatibleJob`
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobMismatch {
+    /// The file was sliced for another material, `loaded` is None if nothing is loaded
+    Material {
+        file: Material,
+        loaded: Option<Material>,
+    },
+
+    /// The file was sliced for another nozzle, both in millimeters
+    NozzleDiameter { file: f32, printer: f32 },
+
+    /// The file was sliced for another printer model
+    Model {
+        file: PrinterModel,
+        printer: PrinterModel,
+    },
+
+    /// The file was sliced for several filaments, but the printer has no multi material unit
+    /// and a single tool
+    MultiMaterial { filaments: usize },
+}
+
+impl fmt::Display for JobMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobMismatch::Material {
+                file,
+                loaded: Some(loaded),
+            } => write!(f, "sliced for {} but {} is loaded", file, loaded),
+            JobMismatch::Material { file, loaded: None } => {
+                write!(f, "sliced for {} but no filament is loaded", file)
+            }
+            JobMismatch::NozzleDiameter { file, printer } => write!(
+                f,
+                "sliced for a {} mm nozzle but the printer has a {} mm nozzle",
+                file, printer
+            ),
+            JobMismatch::Model { file, printer } => {
+                write!(
+                    f,
+                    "sliced for the {} but the printer is a {}",
+                    file, printer
+                )
+            }
+            JobMismatch::MultiMaterial { filaments } => write!(
+                f,
+                "sliced for {} filaments but the printer has no multi material unit",
+                filaments
+            ),
+        }
+    }
+}
+
+impl Printer {
+    /// Starts printing a file after checking that it was sliced for the printer,
+    /// see `JobExpectations` for what is compared.
+    ///
+    /// The metadata of the file, the printer information and the loaded material are all fetched
+    /// again on every call, since the nozzle could have been swapped since the last print.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::IncompatibleJob` with every mismatch if the file wasn't sliced for the printer,
+    /// the print isn't started then. If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, job_check::JobExpectations};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer
+    ///     .start_print_checked("usb", "benchy.bgcode", JobExpectations::new().skip_model())
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn start_print_checked(
+        &mut self,
+        storage: impl Into<Storage>,
+        path: &str,
+        expectations: JobExpectations,
+    ) -> Result<(), Box<dyn Error>> {
+        let storage = storage.into();
+
+        let file = self.get_file_info(storage.clone(), path).await?;
+
+        let info = self.get_info().await?;
+
+        self.refresh().await?;
+        let model = self.identity().await?.model;
+
+        expectations
+            .check(
+                &file.metadata(),
+                &info,
+                self.printer.as_ref().unwrap(),
+                model,
+            )
+            .map_err(|mismatches| PrinterError::IncompatibleJob {
+                path: path.to_string(),
+                mismatches,
+            })?;
+
+        self.start_print(storage, path).await
+    }
+}
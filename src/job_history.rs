@@ -0,0 +1,294 @@
+//! A log of the jobs the printer ran, see `PrinterBuilder::job_history()`.
+//!
+//! PrusaLink only knows the current job, once the next one starts the previous one is gone.
+//! The history is recorded from the events of `Printer::watch()`, so only jobs that started
+//! or ended while the printer was watched are in it.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::PrinterEvent,
+    gcode_meta::GcodeMetadata,
+    raw_job::{JobFile, JobId, RawJob},
+    raw_printer::{LinkState, RawPrinter},
+    storage::Storage,
+    time::{self, SystemTime},
+    Printer,
+};
+
+/// How a job ended
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum JobOutcome {
+    Finished,
+
+    /// The job was stopped from the printer or through the api
+    Stopped,
+
+    /// The printer ran into an error
+    Failed,
+
+    /// The end of the job was missed, because the next job started before the printer was refreshed
+    Unknown,
+}
+
+/// A single job in the `JobHistory`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JobRecord {
+    /// The id of the job on the printer, which starts from 1 again after a reboot
+    pub id: Option<JobId>,
+
+    /// The long name of the file if the printer knows it, the short name otherwise
+    pub file_name: Option<String>,
+
+    /// None if the job was already running when the printer was first watched,
+    /// and the printer didn't report how long it had been printing
+    #[serde(with = "time::option_timestamp")]
+    pub started: Option<SystemTime>,
+
+    /// None while the job is running
+    #[serde(with = "time::option_timestamp")]
+    pub ended: Option<SystemTime>,
+
+    /// None while the job is running
+    pub outcome: Option<JobOutcome>,
+
+    /// The filament the slicer estimated for the whole file, in grams
+    pub filament_used_g: Option<f32>,
+
+    /// The filament the slicer estimated for the whole file, in millimeters
+    pub filament_used_mm: Option<f32>,
+}
+
+impl JobRecord {
+    /// Creates a running record from the job and the metadata of its file, if they could be read
+    pub fn new(
+        started: Option<SystemTime>,
+        job: Option<&RawJob>,
+        metadata: Option<&GcodeMetadata>,
+    ) -> Self {
+        let file = job.and_then(RawJob::get_file);
+
+        Self {
+            id: job.map(RawJob::get_id),
+            file_name: file.map(|file| file.display_name.clone().unwrap_or(file.name.clone())),
+            started,
+            ended: None,
+            outcome: None,
+            filament_used_g: metadata.and_then(|metadata| metadata.filament_used_g),
+            filament_used_mm: metadata.and_then(|metadata| metadata.filament_used_mm),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.outcome.is_none()
+    }
+
+    /// Returns how long the job ran, None while it is running or if the start isn't known
+    pub fn duration(&self) -> Option<Duration> {
+        self.ended?.duration_since(self.started?).ok()
+    }
+}
+
+/// The jobs the printer ran, from oldest to newest.
+///
+/// Enable it with `PrinterBuilder::job_history()` to have the jobs recorded while the printer
+/// is watched with `Printer::watch()` or `Printer::run()`. The history can be saved with serde
+/// and given back to the builder with `PrinterBuilder::restore_job_history()` to keep it across restarts.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::{
+///     events::PrinterEvent,
+///     job_history::{JobHistory, JobOutcome},
+///     raw_printer::{LinkState, RawPrinter},
+/// };
+///
+/// let mut history = JobHistory::new();
+///
+/// let printing = RawPrinter::builder().link_state(LinkState::Printing).build();
+/// history.record(&PrinterEvent::PrintStarted, &printing, None, None);
+/// assert!(history.running().is_some());
+///
+/// let stopped = RawPrinter::builder().link_state(LinkState::Stopped).build();
+/// history.record(&PrinterEvent::PrintFailed, &stopped, None, None);
+///
+/// assert_eq!(history.latest().unwrap().outcome, Some(JobOutcome::Stopped));
+/// assert_eq!(history.with_outcome(JobOutcome::Finished).count(), 0);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct JobHistory {
+    records: Vec<JobRecord>,
+}
+
+impl JobHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Updates the history with an event of `Printer::watch()`, other events than the
+    /// start and end of a print are ignored.
+    ///
+    /// `printer` is the state the event was detected in, `job` and `metadata` describe the job
+    /// and its file if they could be read. They are only used for a new record, which is started
+    /// by `PrintStarted` or by an end without a running record.
+    pub fn record(
+        &mut self,
+        event: &PrinterEvent,
+        printer: &RawPrinter,
+        job: Option<&RawJob>,
+        metadata: Option<&GcodeMetadata>,
+    ) {
+        let now = SystemTime::now();
+
+        let outcome = match event {
+            PrinterEvent::PrintStarted => {
+                self.push(JobRecord::new(Some(now), job, metadata));
+                return;
+            }
+            PrinterEvent::PrintFinished => JobOutcome::Finished,
+            PrinterEvent::PrintFailed if printer.link_state() == LinkState::Stopped => {
+                JobOutcome::Stopped
+            }
+            PrinterEvent::PrintFailed => JobOutcome::Failed,
+            _ => return,
+        };
+
+        if self.running().is_none() {
+            // The job was already running when the printer was first watched
+            let started = job.and_then(|job| now.checked_sub(job.get_time_printing()));
+            self.push(JobRecord::new(started, job, metadata));
+        }
+
+        let record = self.records.last_mut().unwrap();
+        record.ended = Some(now);
+        record.outcome = Some(outcome);
+    }
+
+    /// Adds a record. A record that is still running is ended with `JobOutcome::Unknown`,
+    /// since the printer can only run one job at a time.
+    pub fn push(&mut self, record: JobRecord) {
+        if let Some(running) = self.records.last_mut().filter(|last| last.is_running()) {
+            running.ended = record.started;
+            running.outcome = Some(JobOutcome::Unknown);
+        }
+
+        self.records.push(record);
+    }
+
+    /// Returns true if a new record is started for the event, and the job should be read for it
+    pub fn starts_record(&self, event: &PrinterEvent) -> bool {
+        match event {
+            PrinterEvent::PrintStarted => true,
+            PrinterEvent::PrintFinished | PrinterEvent::PrintFailed => self.running().is_none(),
+            _ => false,
+        }
+    }
+
+    /// Removes all records
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Returns the records from oldest to newest
+    pub fn records(&self) -> impl Iterator<Item = &JobRecord> {
+        self.records.iter()
+    }
+
+    /// Returns the newest record
+    pub fn latest(&self) -> Option<&JobRecord> {
+        self.records.last()
+    }
+
+    /// Returns the job that is running, None if the printer isn't printing
+    pub fn running(&self) -> Option<&JobRecord> {
+        self.latest().filter(|record| record.is_running())
+    }
+
+    /// Returns the records of the jobs that ended with the given outcome, from oldest to newest
+    pub fn with_outcome(&self, outcome: JobOutcome) -> impl Iterator<Item = &JobRecord> {
+        self.records
+            .iter()
+            .filter(move |record| record.outcome == Some(outcome))
+    }
+
+    /// Returns the records of the jobs that started at or after `since`, from oldest to newest.
+    /// Jobs without a known start are left out.
+    pub fn since(&self, since: SystemTime) -> impl Iterator<Item = &JobRecord> {
+        self.records
+            .iter()
+            .filter(move |record| record.started.is_some_and(|started| started >= since))
+    }
+
+    /// Returns the estimated filament of all finished jobs in grams,
+    /// jobs whose file had no estimate are left out
+    pub fn filament_used_g(&self) -> f32 {
+        self.with_outcome(JobOutcome::Finished)
+            .filter_map(|record| record.filament_used_g)
+            .sum()
+    }
+}
+
+impl Printer {
+    /// Records the events in the job history if it is enabled,
+    /// reading the job and the metadata of its file for new records
+    pub(crate) async fn record_jobs(&mut self, events: &[PrinterEvent], printer: &RawPrinter) {
+        for event in events {
+            let Some(history) = self.job_history.as_ref() else {
+                return;
+            };
+
+            let (job, metadata) = if history.starts_record(event) {
+                self.job_details().await
+            } else {
+                (None, None)
+            };
+
+            if let Some(history) = self.job_history.as_mut() {
+                history.record(event, printer, job.as_ref(), metadata.as_ref());
+            }
+        }
+    }
+
+    /// Reads the current job and the metadata of its file. Errors are ignored,
+    /// the job is recorded without the details then.
+    async fn job_details(&self) -> (Option<RawJob>, Option<GcodeMetadata>) {
+        let job = self.get_job_info().await.ok().flatten();
+
+        let metadata = match job.as_ref().and_then(RawJob::get_file) {
+            Some(file) => {
+                let (storage, path) = file_location(file);
+                self.get_file_info(storage, &path)
+                    .await
+                    .ok()
+                    .map(|file| file.metadata())
+            }
+            None => None,
+        };
+
+        (job, metadata)
+    }
+}
+
+/// Returns the storage and the path on it of the file of a job
+fn file_location(file: &JobFile) -> (Storage, String) {
+    let folder = file.path.trim_matches('/');
+    let (storage, folder) = folder.split_once('/').unwrap_or((folder, ""));
+
+    (
+        Storage::from(storage),
+        crate::files::join(folder, &file.name),
+    )
+}
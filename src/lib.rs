@@ -1,10 +1,99 @@
-use std::{
-    error::Error,
-    time::{Duration, Instant},
-};
+use std::{collections::VecDeque, error::Error, time::Duration};
 
+use futures::{stream, Stream, StreamExt};
+use secrecy::{ExposeSecret, SecretString};
+
+pub mod address;
+pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod capabilities;
+pub mod cleanup;
+pub mod clock;
+pub mod command_lock;
+mod commands;
+pub mod compat;
+pub mod config;
+pub mod connect;
+pub mod diff;
+#[cfg(feature = "discovery")]
+pub mod discovery;
+mod download;
+pub mod error;
+pub mod events;
+pub mod farm;
+pub mod files;
+pub mod firmware;
+pub mod gcode_meta;
+pub mod handle;
+pub mod health;
+pub mod history;
+#[cfg(feature = "home-assistant")]
+pub mod home_assistant;
+pub mod hooks;
+pub mod job_check;
+pub mod job_history;
+pub mod logs;
+pub mod material;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod model;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod path;
+pub mod progress;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod queue;
+pub mod rate_limit;
+pub mod raw_info;
+pub mod raw_job;
+pub mod raw_network;
 pub mod raw_printer;
+pub mod raw_status;
+pub mod raw_storage;
+pub mod ready;
+pub mod retry;
+pub mod stats;
+pub mod storage;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+mod time;
+pub mod transport;
+pub mod units;
+pub mod upload;
+#[cfg(feature = "axum")]
+pub mod web;
+use address::*;
+use cache::CachePolicy;
+use capabilities::*;
+use clock::{Clock, SystemClock};
+use command_lock::{CommandLock, CommandLocker};
+use compat::*;
+use error::*;
+use events::*;
+use files::FileTree;
+use health::*;
+use history::*;
+use hooks::*;
+use job_history::JobHistory;
+use material::Material;
+use model::{PrinterIdentity, PrinterKind, PrinterModel};
+use path::files_url;
+use progress::PrintProgress;
+use rate_limit::{RateLimit, RateLimiter};
+use raw_info::*;
+use raw_job::*;
+use raw_network::NetworkInfo;
 use raw_printer::*;
+use raw_status::*;
+use raw_storage::*;
+use retry::RetryPolicy;
+use stats::RequestStats;
+use storage::Storage;
+use time::{Instant, Ticker};
+use transport::*;
+use units::{Celsius, TemperatureSource, Temperatures};
 
 /// Builds a Printer struct with the given address and api key
 ///
@@ -12,28 +101,74 @@ use raw_printer::*;
 #[derive(Debug)]
 pub struct PrinterBuilder {
     address: String,
-    api_key: String,
-    port: u32,
+    api_key: SecretString,
+    port: Option<u32>,
     auto_refresh: Option<Duration>,
+    history_capacity: Option<usize>,
+    job_history: Option<JobHistory>,
+    base_path: Option<String>,
+    compatibility: Compatibility,
+    hooks: Hooks,
+    transport: Option<std::sync::Arc<dyn HttpTransport>>,
+    rate_limit: Option<RateLimit>,
+    serve_stale: Option<Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    proxy: Option<String>,
+    no_proxy: bool,
+    clock: std::sync::Arc<dyn Clock>,
+    cache_policy: CachePolicy,
+    command_lock: Option<CommandLock>,
+    headers: http::HeaderMap,
+    invalid_header: Option<String>,
+    verify_uploads: bool,
+    upload_retry: Option<RetryPolicy>,
+    upload_throttle: Option<u64>,
 }
 
 /// Contains all the information about the printer
 /// as well as some helper functions to get the information.
 #[derive(Debug)]
 pub struct Printer {
+    scheme: Scheme,
     address: String,
-    api_key: String,
+    base_path: String,
+    api_key: SecretString,
     port: u32,
-    client: reqwest::Client,
+    sender: Sender,
     printer: Option<RawPrinter>,
     last_refresh: Option<Instant>,
+    job: Option<RawJob>,
+    last_job_refresh: Option<Instant>,
+    status: Option<RawStatus>,
+    last_status_refresh: Option<Instant>,
+    info: Option<RawInfo>,
     auto_refresh: Option<Duration>,
+    history: Option<TelemetryHistory>,
+    job_history: Option<JobHistory>,
+    capabilities: tokio::sync::OnceCell<Capabilities>,
+    identity: tokio::sync::OnceCell<PrinterIdentity>,
+    compatibility: Compatibility,
+    serve_stale: Option<Duration>,
+    stale: bool,
+    clock: std::sync::Arc<dyn Clock>,
+    cache_policy: CachePolicy,
+    revalidation: Option<tokio::sync::oneshot::Receiver<Result<(RawPrinter, Instant), String>>>,
+    command_locker: Option<CommandLocker>,
+    invalidated: std::sync::atomic::AtomicBool,
+    headers: http::HeaderMap,
+    verify_uploads: bool,
+    upload_retry: Option<RetryPolicy>,
+    upload_throttle: Option<u64>,
 }
 
 impl PrinterBuilder {
     /// Creates a new PrinterBuilder with the given address and api key
     ///
-    /// If you want to use a different port than the default port 80, you can use the `port()` function
+    /// The address can be a url like `http://192.168.1.50:8080`, a host name like `prusa-mk4.local`,
+    /// a `host:port` string, or an `IpAddr` or `SocketAddr`. See `PrinterAddress` for the accepted formats.
+    ///
+    /// If the address doesn't specify a port, the default port of the scheme is used (80 for http).
+    /// If you want to use a different port, you can use the `port()` function
     ///
     /// Of you want to refresh the printer information manually you can set `auto_refresh` to None.
     /// By default `auto_refresh` is set 2 second, and it is not recommended to set it to a value lower than 1 second
@@ -51,18 +186,84 @@ impl PrinterBuilder {
     ///
     /// let printer = printer_builder.build();
     /// ```
-    pub fn new(address: String, api_key: String) -> Self {
+    pub fn new(address: impl ToString, api_key: impl Into<String>) -> Self {
         Self {
-            address,
-            api_key,
-            port: 80,
+            address: address.to_string(),
+            api_key: SecretString::new(api_key.into()),
+            port: None,
             auto_refresh: Some(Duration::from_secs(2)),
+            history_capacity: None,
+            job_history: None,
+            base_path: None,
+            compatibility: Compatibility::default(),
+            hooks: Hooks::default(),
+            transport: None,
+            rate_limit: None,
+            serve_stale: None,
+            circuit_breaker: None,
+            proxy: None,
+            no_proxy: false,
+            clock: std::sync::Arc::new(SystemClock),
+            cache_policy: CachePolicy::default(),
+            command_lock: None,
+            headers: http::HeaderMap::new(),
+            invalid_header: None,
+            verify_uploads: false,
+            upload_retry: None,
+            upload_throttle: None,
+        }
+    }
+
+    /// Creates a PrinterBuilder from the environment variables `PRUSALINK_HOST` and `PRUSALINK_API_KEY`,
+    /// and `PRUSALINK_PORT` if it is set.
+    ///
+    /// The host can be anything `new()` accepts as the address. Only the api key is supported,
+    /// the username and password of PrusaLink can't be used instead of it.
+    /// Call `dotenv::dotenv()` first to read the variables from a `.env` file as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BuildError::MissingEnvVar` if the host or the api key is not set,
+    /// and a `BuildError::InvalidEnvVar` if the port is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use prusa_link_rs::PrinterBuilder;
+    ///
+    /// let printer = PrinterBuilder::from_env()
+    ///     .and_then(|builder| builder.try_build())
+    ///     .unwrap();
+    /// ```
+    pub fn from_env() -> Result<Self, BuildError> {
+        let var = |name: &'static str| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        };
+
+        let host = var("PRUSALINK_HOST").ok_or(BuildError::MissingEnvVar("PRUSALINK_HOST"))?;
+        let api_key =
+            var("PRUSALINK_API_KEY").ok_or(BuildError::MissingEnvVar("PRUSALINK_API_KEY"))?;
+
+        let mut builder = Self::new(host, api_key);
+
+        if let Some(port) = var("PRUSALINK_PORT") {
+            let port = port
+                .trim()
+                .parse()
+                .map_err(|_| BuildError::InvalidEnvVar("PRUSALINK_PORT", port))?;
+
+            builder = builder.port(port);
         }
+
+        Ok(builder)
     }
 
-    /// Use this function to set a different port than the default port 80
+    /// Use this function to set a different port than the default port 80,
+    /// this takes precedence over a port given in the address
     pub fn port(mut self, port: u32) -> Self {
-        self.port = port;
+        self.port = Some(port);
         self
     }
 
@@ -77,253 +278,3259 @@ impl PrinterBuilder {
         self
     }
 
-    /// Builds the Printer struct
-    pub fn build(self) -> Printer {
-        let address = self.address;
-        let port = self.port;
-        let api_key = self.api_key;
-        let client = reqwest::Client::new();
-        let printer = None;
-        let last_refresh = None;
-        let auto_refresh = self.auto_refresh;
-
-        Printer {
-            address,
-            port,
-            api_key,
-            client,
-            printer,
-            last_refresh,
-            auto_refresh,
-        }
+    /// Use this function to record the telemetry of the printer on every refresh,
+    /// keeping the last `capacity` samples.
+    ///
+    /// The recorded history can be read with `Printer::telemetry_history()`.
+    pub fn telemetry_history(mut self, capacity: usize) -> Self {
+        self.history_capacity = Some(capacity);
+        self
     }
-}
 
-impl Printer {
-    /// Returns the current PrusaLink version in a json format
-    pub async fn get_version(&self) -> Result<String, Box<dyn Error>> {
-        let url = format!("http://{}:{}/api/version", self.address, self.port);
+    /// Use this function to record the start, end and outcome of every job while the printer
+    /// is watched with `Printer::watch()` or `Printer::run()`.
+    ///
+    /// The recorded jobs can be read with `Printer::job_history()`.
+    pub fn job_history(mut self) -> Self {
+        self.job_history = Some(JobHistory::new());
+        self
+    }
 
-        let res = self
-            .client
-            .get(&url)
-            .header("X-Api-Key", &self.api_key)
-            .send()
-            .await?;
+    /// Same as `job_history()`, but continues a history saved from an earlier run
+    pub fn restore_job_history(mut self, history: JobHistory) -> Self {
+        self.job_history = Some(history);
+        self
+    }
 
-        let body = res.text().await?;
+    /// Use this function if the printer is behind a reverse proxy that routes by path,
+    /// every request is sent below the given prefix, for example `/printers/mk4a/api/v1/status`.
+    ///
+    /// This takes precedence over a path given in the address.
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = Some(base_path.into());
+        self
+    }
 
-        Ok(body)
+    /// Use this function to talk to printers whose responses differ from PrusaLink on the Buddy firmware,
+    /// such as the MK3 PrusaLink on a Raspberry Pi with `Compatibility::OctoPrint`.
+    ///
+    /// By default `Compatibility::PrusaLink` is used.
+    pub fn compatibility(mut self, compatibility: Compatibility) -> Self {
+        self.compatibility = compatibility;
+        self
     }
 
-    /// Returns a RawPrinter struct with all the information about the printer
+    /// Use this function to change every request before it is sent, for example to add headers
+    /// a reverse proxy needs. Hooks are called in the order they were added.
     ///
-    /// # Errors
+    /// # Example
     ///
-    /// If the server returns an empty response, the function will return an Err.
-    /// This can happen if the server is not running or if the api key is incorrect.
+    /// ```rust
+    /// use prusa_link_rs::PrinterBuilder;
     ///
-    /// Remember to check that youre using the right address and port.
-    pub async fn get_printer_info(&mut self) -> Result<RawPrinter, Box<dyn Error>> {
-        let url = format!("http://{}:{}/api/printer", self.address, self.port);
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .on_request(|request| {
+    ///         request
+    ///             .headers_mut()
+    ///             .insert("X-Forwarded-User", "octofarm".parse().unwrap());
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn on_request<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut http::Request<TransportBody>) + Send + Sync + 'static,
+    {
+        self.hooks.on_request.push(std::sync::Arc::new(hook));
+        self
+    }
 
-        let raw_printer_text = self
-            .client
-            .get(&url)
-            .header("X-Api-Key", self.api_key())
-            .send()
-            .await?
-            .text()
-            .await?;
+    /// Use this function to see every response of the printer, for example to record metrics.
+    /// Hooks are called in the order they were added, with the whole body already read.
+    pub fn on_response<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&http::Response<bytes::Bytes>) + Send + Sync + 'static,
+    {
+        self.hooks.on_response.push(std::sync::Arc::new(hook));
+        self
+    }
 
-        if raw_printer_text.trim().is_empty() {
-            return Err("Received an empty response from the server".into());
-        }
+    /// Use this function to send the requests with a different HTTP client than reqwest,
+    /// or with a test double. See `HttpTransport` for an example.
+    ///
+    /// Without the default `reqwest` feature a transport has to be given.
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(std::sync::Arc::new(transport));
+        self
+    }
 
-        Ok(serde_json::from_str::<RawPrinter>(&raw_printer_text)?)
+    /// Use this function to decide with another clock than the system clock when the cached information
+    /// is outdated, for example with the `test_utils::MockClock` of the `test-utils` feature.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = std::sync::Arc::new(clock);
+        self
     }
 
-    /// Refreshes the internal printer information by sending a request to the printer.
+    /// Use this function to answer from the cache while it is being refreshed, or to turn the cache off,
+    /// see `CachePolicy`. The policy is `CachePolicy::Strict` by default.
     ///
-    /// # Errors
+    /// # Example
     ///
-    /// If the server returns an empty response, the function will return an Err.
-    /// This can happen if the server is not running or if the api key is incorrect.
+    /// ```rust
+    /// use prusa_link_rs::{cache::CachePolicy, PrinterBuilder};
+    /// use std::time::Duration;
+    ///
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .auto_refresh(Duration::from_secs(1))
+    ///     .cache_policy(CachePolicy::StaleWhileRevalidate {
+    ///         max_stale: Duration::from_secs(10),
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn cache_policy(mut self, cache_policy: CachePolicy) -> Self {
+        self.cache_policy = cache_policy;
+        self
+    }
+
+    /// Use this function to limit how often requests are sent to the printer,
+    /// requests going over the limit wait for their turn instead of being sent right away.
+    ///
+    /// By default requests are not limited.
     ///
     /// # Example
-    /// ```should_panic
-    /// # use prusa_link_rs::PrinterBuilder;
-    /// # use std::time::Duration;
-    /// # use tokio_test::block_on;
-    /// # block_on(async {
-    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
-    ///    .build();
     ///
-    /// printer.refresh().await.unwrap(); // Errors since this is not a valid address
-    /// # })
-    pub async fn refresh(&mut self) -> Result<(), Box<dyn Error>> {
-        let url = format!("http://{}:{}/api/printer", self.address, self.port);
-
-        let raw_printer_text = self
-            .client
-            .get(&url)
-            .header("X-Api-Key", self.api_key())
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        if raw_printer_text.trim().is_empty() {
-            return Err("Received an empty response from the server".into());
-        }
-
-        self.printer = Some(serde_json::from_str::<RawPrinter>(&raw_printer_text)?);
-        self.last_refresh = Some(Instant::now());
-
-        Ok(())
+    /// ```rust
+    /// use prusa_link_rs::{rate_limit::RateLimit, PrinterBuilder};
+    /// use std::time::Duration;
+    ///
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .rate_limit(RateLimit::TokenBucket {
+    ///         burst: 3,
+    ///         refill: Duration::from_millis(500),
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
     }
 
-    // Get the printer jobs.
-    // TODO: Implement this function
-    
-    // Create a new printer job.
-    // TODO: Implement this function
-
-    // Get the printer status.
-    // TODO: Implement this function
-
-    // Get the printe storage information.
-    // TODO: Implement this function
-
-    // Get the printer files.
-    // TODO: Implement this function
-
-    // Get the printer files recursively.
-    // TODO: Implement this function
-
-    // Post gcode to the printer.
-    // TODO: Implement this function
-
-    // Create files/directories on the printer.
-    // TODO: Implement this function
-
-    // Check if file exists on the printer.
-    // TODO: Implement this function
-
-    // Print gcode from printer storage.
-    // TODO: Implement this function
+    /// Use this function to run only one command on the printer at a time, for when the `Printer`
+    /// is shared between tasks. `CommandLock::Reject` fails a command with a `PrinterError::Busy`
+    /// while another one is running, `CommandLock::Queue` waits for it to finish instead.
+    ///
+    /// Commands are the functions that change the printer, like `start_print()`, `stop_job()`
+    /// or `upload_and_print()`. Getters are never held back.
+    ///
+    /// By default commands are not locked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use prusa_link_rs::{command_lock::CommandLock, PrinterBuilder};
+    ///
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .command_lock(CommandLock::Reject)
+    ///     .build();
+    /// ```
+    pub fn command_lock(mut self, command_lock: CommandLock) -> Self {
+        self.command_lock = Some(command_lock);
+        self
+    }
 
-    // Delete files/directories on the printer.
-    // TODO: Implement this function
+    /// Use this function to check every upload after it finished, by asking the printer for the size
+    /// of the file and comparing it to the size of the local file. An upload that was cut short,
+    /// for example by a flaky Wi-Fi connection, then fails with a `PrinterError::UploadVerificationFailed`
+    /// instead of leaving a truncated file on the printer silently.
+    ///
+    /// PrusaLink doesn't report a checksum of the files, so only the size can be compared.
+    /// The check takes one extra request per upload. By default uploads are not verified.
+    pub fn verify_uploads(mut self) -> Self {
+        self.verify_uploads = true;
+        self
+    }
 
-    /// Returns the current nozzle temperature of the printer as an f32.
+    /// Use this function to try uploads again when the connection fails during the transfer,
+    /// or when `verify_uploads()` finds the file incomplete. The partial file is deleted from the printer
+    /// after every failed attempt, also after the last one, so no half-written file is left behind.
     ///
-    /// If auto refresh is enabled, the function will refresh the printer information if
-    /// the specified time on `last_refresh` has passed, otherwise it will use the cached information.
+    /// Uploads from memory and from local files are retried, uploads from a reader are not,
+    /// since the reader can only be read once. Files the printer refuses, for example because
+    /// they already exist, are never retried. By default uploads are not retried.
     ///
-    /// If auto refresh is disabled, the function will only refresh the printer information if
-    /// there is no cached information.
+    /// # Example
     ///
-    /// # Errors
+    /// ```rust
+    /// use prusa_link_rs::{retry::RetryPolicy, PrinterBuilder};
     ///
-    /// If the server returns an empty response, the function will return an Err.
-    /// This can happen if the server is not running or if the api key is incorrect.
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .retry_uploads(RetryPolicy::new(3))
+    ///     .verify_uploads()
+    ///     .build();
+    /// ```
+    pub fn retry_uploads(mut self, policy: RetryPolicy) -> Self {
+        self.upload_retry = Some(policy);
+        self
+    }
+
+    /// Use this function to send uploads no faster than `bytes_per_sec` on average, so a large file
+    /// doesn't take all of the printer's Wi-Fi and the status requests sent meanwhile still get through.
+    /// A rate of 0 is treated as 1.
+    ///
+    /// By default uploads are sent as fast as the connection allows. Ignored on wasm,
+    /// where the browser sends the whole file at once.
     ///
     /// # Example
     ///
-    /// ```should_panic
-    /// # use prusa_link_rs::PrinterBuilder;
-    /// # use std::time::Duration;
-    /// # use tokio_test::block_on;
-    /// # block_on(async {
-    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
-    ///     .auto_refresh(Duration::from_secs(5))
-    ///     .build();
+    /// ```rust
+    /// use prusa_link_rs::PrinterBuilder;
     ///
-    /// let nozzle_temp = printer.get_nozzle_temp().await.unwrap(); // Errors since this is not a valid address
-    /// # })
+    /// // At most 500 kB/s
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .throttle_uploads(500_000)
+    ///     .build();
     /// ```
-    pub async fn get_nozzle_temp(&mut self) -> Result<f32, Box<dyn Error>> {
-        self.refresh_if_necessary().await?;
-
-        let printer = self.printer.as_ref().unwrap();
+    pub fn throttle_uploads(mut self, bytes_per_sec: u64) -> Self {
+        self.upload_throttle = Some(bytes_per_sec);
+        self
+    }
 
-        Ok(printer.get_nozzle_temp())
+    /// Use this function to keep the cached getters working during short outages.
+    ///
+    /// When an automatic refresh fails because the printer can't be reached, the cached information
+    /// is returned instead of an error as long as it isn't older than `max_age`,
+    /// and `Printer::is_stale()` returns true until a refresh succeeds again.
+    ///
+    /// By default the getters return the error right away.
+    pub fn serve_stale(mut self, max_age: Duration) -> Self {
+        self.serve_stale = Some(max_age);
+        self
     }
 
-    /// Returns the current bed temperature of the printer.
+    /// Use this function to stop sending requests to a printer that failed `failures` requests in a row,
+    /// for example because it is turned off, instead of waiting for every request to time out.
     ///
-    /// If `auto_refresh` is Some, the function will refresh the printer information if
-    /// the specified time on `last_refresh` has passed, otherwise it will use the cached information.
+    /// While the circuit is open the requests fail right away with a `TransportError` of the kind
+    /// `TransportErrorKind::CircuitOpen`. After `cooldown` a single request is sent again, also
+    /// when the printer is shared between tasks, and the circuit closes if it succeeds.
+    /// Values of `failures` below 1 are treated as 1.
     ///
-    /// If `auto_refresh` is None, the function will only refresh the printer information if
-    /// there is no cached information.
+    /// # Example
     ///
-    /// # Errors
+    /// ```rust
+    /// use prusa_link_rs::PrinterBuilder;
+    /// use std::time::Duration;
     ///
-    /// If the server returns an empty response, the function will return an `Err`.
-    /// This can happen if the server is not running or if the api key is incorrect.
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .circuit_breaker(3, Duration::from_secs(60))
+    ///     .build();
+    /// ```
+    pub fn circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some(CircuitBreaker::new(failures, cooldown));
+        self
+    }
+
+    /// Use this function to send a header with every request, in addition to the `X-Api-Key`,
+    /// for example the `Authorization` header of an auth proxy in front of PrusaLink.
+    /// Setting the same header again replaces the value.
+    ///
+    /// The values are marked as sensitive, so they are not shown in the debug output of the printer.
     ///
     /// # Example
     ///
-    /// ```should_panic
-    /// # use prusa_link_rs::PrinterBuilder;
-    /// # use std::time::Duration;
-    /// # use tokio_test::block_on;
-    /// # block_on(async {
-    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
-    ///     .auto_refresh(Duration::from_secs(5))
-    ///     .build();
+    /// ```rust
+    /// use prusa_link_rs::PrinterBuilder;
     ///
-    /// let nozzle_temp = printer.get_bed_temp().await.unwrap(); // Errors since this is not a valid address
-    /// # })
+    /// let printer = PrinterBuilder::new("https://printers.example.com/mk4", "api_key")
+    ///     .header("Authorization", "Bearer 0123456789")
+    ///     .try_build()
+    ///     .unwrap();
     /// ```
-    pub async fn get_bed_temp(&mut self) -> Result<f32, Box<dyn Error>> {
-        self.refresh_if_necessary().await?;
-
-        let printer = self.printer.as_ref().unwrap();
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        let parsed = (
+            http::HeaderName::from_bytes(name.as_bytes()),
+            http::HeaderValue::from_str(value.as_ref()),
+        );
 
-        Ok(printer.get_bed_temp())
-    }
-}
+        match parsed {
+            (Ok(name), Ok(mut value)) => {
+                value.set_sensitive(true);
+                self.headers.insert(name, value);
+            }
+            _ => self.invalid_header = Some(name.to_string()),
+        }
 
-// impl block for minor helper functions
-impl Printer {
-    /// Returns a reference to the address string
-    pub fn address(&self) -> &str {
-        &self.address
+        self
     }
 
-    /// Returns a reference to the api_key string
-    pub fn api_key(&self) -> &str {
-        &self.api_key
+    /// Use this function to send a different `User-Agent` header, by default the one of the transport is sent
+    pub fn user_agent(self, user_agent: impl AsRef<str>) -> Self {
+        self.header(http::header::USER_AGENT, user_agent)
     }
 
-    /// Changes the APIs url
-    pub fn change_address(&mut self, address: String) {
-        self.address = address;
+    /// Use this function to send the requests through a proxy, for example `http://bastion.local:3128`,
+    /// or `socks5://localhost:1080` with the `socks` feature.
+    ///
+    /// By default the proxy of the `HTTP_PROXY` and `HTTPS_PROXY` environment variables is used, if any.
+    /// The proxy is ignored when a transport is given, and on wasm where the browser decides.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self.no_proxy = false;
+        self
     }
 
-    /// changes the APIs api key
-    pub fn change_api_key(&mut self, api_key: String) {
-        self.api_key = api_key;
+    /// Use this function to connect to the printer directly,
+    /// even if a proxy is set in the environment variables
+    pub fn no_proxy(mut self) -> Self {
+        self.proxy = None;
+        self.no_proxy = true;
+        self
     }
 
-    /// Refreshed the printer information if auto_refresh is enabled and the specified time has passed
-    /// since the last refresh.
+    /// Builds the Printer struct
     ///
-    /// If auto_refresh is disabled, the function will refresh the printer information if
-    /// there is no cached information.
-    async fn refresh_if_necessary(&mut self) -> Result<(), Box<dyn Error>> {
-        if match (self.last_refresh, self.auto_refresh) {
-                (Some(time), Some(duration)) if time.elapsed() > duration => true,
-                (None, _) => true,
-                _ => false,
-            }
-        {
-            self.refresh().await?;
+    /// The configuration is not checked, if the address can't be parsed it is used as the host name as it is,
+    /// and mistakes only show up as failing requests. Use `try_build()` to get an error instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the crate was built without the `reqwest` feature and no transport was given,
+    /// if the url given to `proxy()` is invalid, or if a header given to `header()` is invalid.
+    pub fn build(self) -> Printer {
+        let address = self
+            .address
+            .parse::<PrinterAddress>()
+            .unwrap_or_else(|_| PrinterAddress {
+                scheme: Scheme::Http,
+                host: self.address.clone(),
+                port: None,
+                path: String::new(),
+            });
+
+        let transport = self
+            .default_transport()
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        if let Some(name) = self.invalid_header {
+            panic!("{}", BuildError::InvalidHeader(name));
         }
 
-        Ok(())
+        self.build_with_address(address, transport)
     }
+
+    /// Builds the Printer struct, checking the configuration first
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BuildError` if the api key is empty, the port is not between 1 and 65535,
+    /// the address, the proxy url or a header can't be parsed, or no transport was given without the `reqwest` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use prusa_link_rs::{PrinterBuilder, error::BuildError};
+    /// let err = PrinterBuilder::new("192.168.1.50", "api_key")
+    ///     .port(70000)
+    ///     .try_build()
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(err, BuildError::InvalidPort(70000));
+    /// ```
+    pub fn try_build(self) -> Result<Printer, BuildError> {
+        if self.api_key.expose_secret().trim().is_empty() {
+            return Err(BuildError::EmptyApiKey);
+        }
+
+        if let Some(port) = self.port {
+            if port == 0 || port > u16::MAX.into() {
+                return Err(BuildError::InvalidPort(port));
+            }
+        }
+
+        if let Some(name) = self.invalid_header {
+            return Err(BuildError::InvalidHeader(name));
+        }
+
+        let address = self.address.parse::<PrinterAddress>()?;
+        let transport = self.default_transport()?;
+
+        Ok(self.build_with_address(address, transport))
+    }
+
+    /// Builds a `PrinterHandle`, for sharing the printer between tasks. See `build()` for when this panics
+    pub fn build_handle(self) -> handle::PrinterHandle {
+        handle::PrinterHandle::new(self.build())
+    }
+
+    /// Builds a blocking Printer, for code that doesn't use async
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the runtime the requests run on can't be created.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> std::io::Result<blocking::Printer> {
+        blocking::Printer::new(self.build())
+    }
+
+    /// Returns the given transport, or reqwest if none was given and the feature is enabled
+    fn default_transport(&self) -> Result<std::sync::Arc<dyn HttpTransport>, BuildError> {
+        if let Some(transport) = &self.transport {
+            return Ok(transport.clone());
+        }
+
+        #[cfg(feature = "reqwest")]
+        return Ok(std::sync::Arc::new(self.reqwest_transport()?));
+
+        #[cfg(not(feature = "reqwest"))]
+        Err(BuildError::MissingTransport)
+    }
+
+    /// Returns a reqwest transport with the proxy settings of the builder
+    #[cfg(feature = "reqwest")]
+    fn reqwest_transport(&self) -> Result<ReqwestTransport, BuildError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.proxy.is_some() || self.no_proxy {
+            let mut client = reqwest::Client::builder();
+
+            if let Some(url) = &self.proxy {
+                let proxy = reqwest::Proxy::all(url.as_str())
+                    .map_err(|err| BuildError::InvalidProxy(err.to_string()))?;
+                client = client.proxy(proxy);
+            } else {
+                client = client.no_proxy();
+            }
+
+            let client = client
+                .build()
+                .map_err(|err| BuildError::InvalidProxy(err.to_string()))?;
+
+            return Ok(ReqwestTransport::with_client(client));
+        }
+
+        Ok(ReqwestTransport::new())
+    }
+
+    fn build_with_address(
+        self,
+        address: PrinterAddress,
+        transport: std::sync::Arc<dyn HttpTransport>,
+    ) -> Printer {
+        let scheme = address.scheme;
+        let port = self.port.unwrap_or(address.port_or_default().into());
+        let base_path = match self.base_path {
+            Some(base_path) => normalize_path(&base_path),
+            None => address.path,
+        };
+        let address = address.host;
+        let api_key = self.api_key;
+        let printer = None;
+        let last_refresh = None;
+        let job = None;
+        let last_job_refresh = None;
+        let status = None;
+        let last_status_refresh = None;
+        let info = None;
+        let auto_refresh = self.auto_refresh;
+        let history = self.history_capacity.map(TelemetryHistory::new);
+        let job_history = self.job_history;
+        let capabilities = tokio::sync::OnceCell::new();
+        let identity = tokio::sync::OnceCell::new();
+        let compatibility = self.compatibility;
+        let sender = Sender {
+            transport,
+            hooks: self.hooks,
+            rate_limiter: self
+                .rate_limit
+                .map(|limit| std::sync::Arc::new(RateLimiter::new(limit))),
+            health: std::sync::Arc::new(std::sync::Mutex::new(Health::default())),
+            stats: std::sync::Arc::new(std::sync::Mutex::new(RequestStats::default())),
+            circuit_breaker: self.circuit_breaker,
+        };
+        let serve_stale = self.serve_stale;
+        let stale = false;
+        let clock = self.clock;
+        let cache_policy = self.cache_policy;
+        let revalidation = None;
+        let command_locker = self.command_lock.map(CommandLocker::new);
+        let invalidated = std::sync::atomic::AtomicBool::new(false);
+        let headers = self.headers;
+        let verify_uploads = self.verify_uploads;
+        let upload_retry = self.upload_retry;
+        let upload_throttle = self.upload_throttle;
+
+        Printer {
+            scheme,
+            address,
+            base_path,
+            port,
+            api_key,
+            sender,
+            printer,
+            last_refresh,
+            job,
+            last_job_refresh,
+            status,
+            last_status_refresh,
+            info,
+            auto_refresh,
+            history,
+            job_history,
+            capabilities,
+            identity,
+            compatibility,
+            serve_stale,
+            stale,
+            clock,
+            cache_policy,
+            revalidation,
+            command_locker,
+            invalidated,
+            headers,
+            verify_uploads,
+            upload_retry,
+            upload_throttle,
+        }
+    }
+}
+
+impl Printer {
+    /// Returns the current PrusaLink version in a json format
+    pub async fn get_version(&self) -> Result<String, Box<dyn Error>> {
+        let res = self
+            .send(self.request(http::Method::GET, "/api/version"))
+            .await?;
+
+        let body = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(body)
+    }
+
+    /// Checks that the printer can be reached and accepts the api key,
+    /// using the lightweight `/api/version` endpoint
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ConnectionError` telling apart an unreachable host, a wrong api key,
+    /// and a server that doesn't speak PrusaLink on the given scheme and port.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, error::ConnectionError};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("192.168.1.50", "api_key").build();
+    ///
+    /// match printer.check_connection().await {
+    ///     Ok(()) => println!("Connected"),
+    ///     Err(ConnectionError::InvalidApiKey) => println!("Check the api key in the printer settings"),
+    ///     Err(err) => println!("{}", err),
+    /// }
+    /// # })
+    /// ```
+    pub async fn check_connection(&self) -> Result<(), ConnectionError> {
+        let res = self
+            .send(self.request(http::Method::GET, "/api/version"))
+            .await
+            .map_err(|err| match err.kind() {
+                TransportErrorKind::Connect
+                | TransportErrorKind::Timeout
+                | TransportErrorKind::CircuitOpen => ConnectionError::Unreachable(err.to_string()),
+                TransportErrorKind::Other => ConnectionError::WrongProtocol(err.to_string()),
+            })?;
+
+        match res.status().as_u16() {
+            401 | 403 => return Err(ConnectionError::InvalidApiKey),
+            status if !res.status().is_success() => {
+                return Err(ConnectionError::UnexpectedStatus(status))
+            }
+            _ => (),
+        }
+
+        let body = String::from_utf8_lossy(res.body());
+
+        // Every PrusaLink version reports at least the api version
+        match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(version) if version.get("api").is_some() => Ok(()),
+            _ => Err(ConnectionError::WrongProtocol(
+                "/api/version didn't return a PrusaLink version".to_string(),
+            )),
+        }
+    }
+
+    /// Returns what the firmware of the printer supports, detected from `/api/version`.
+    ///
+    /// The version is only requested once, the capabilities are cached until the address changes.
+    /// If the printer doesn't report its version, every feature is assumed to be supported.
+    ///
+    /// Functions using endpoints that the firmware lacks return a `PrinterError::Unsupported`,
+    /// or fall back to the legacy endpoints where possible.
+    ///
+    /// # Errors
+    ///
+    /// If the printer can't be reached the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if !printer.capabilities().await.unwrap().upload_by_put {
+    ///     println!("Uploads need a firmware update");
+    /// }
+    /// # })
+    /// ```
+    pub async fn capabilities(&self) -> Result<&Capabilities, Box<dyn Error>> {
+        self.capabilities
+            .get_or_try_init(|| async {
+                let res = self
+                    .send(self.request(http::Method::GET, "/api/version"))
+                    .await?;
+
+                if !res.status().is_success() {
+                    return Ok(Capabilities::assumed());
+                }
+
+                let version_text = String::from_utf8_lossy(res.body()).into_owned();
+
+                Ok(match serde_json::from_str::<RawVersion>(&version_text) {
+                    Ok(version) => Capabilities::from_version(&version),
+                    Err(_) => Capabilities::assumed(),
+                })
+            })
+            .await
+    }
+
+    /// Returns the model of the printer and the versions of its firmware and of PrusaLink,
+    /// detected from `/api/version` and `/api/v1/info` the first time this is called.
+    ///
+    /// # Errors
+    ///
+    /// If the printer can't be reached the function will return an Err. A version or info that can't be
+    /// parsed only leaves out what would have been detected from it.
+    pub async fn identity(&self) -> Result<&PrinterIdentity, Box<dyn Error>> {
+        self.identity
+            .get_or_try_init(|| async {
+                let res = self
+                    .send(self.request(http::Method::GET, "/api/version"))
+                    .await?;
+                let version = serde_json::from_slice::<RawVersion>(res.body()).ok();
+
+                let info = match self.get_info().await {
+                    Ok(info) => Some(info),
+                    Err(err) if err.is::<TransportError>() => return Err(err),
+                    Err(_) => None,
+                };
+
+                Ok(PrinterIdentity::detect(version.as_ref(), info.as_ref()))
+            })
+            .await
+    }
+
+    /// Returns the model of the printer, see `identity()`
+    ///
+    /// # Errors
+    ///
+    /// If the printer can't be reached the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let model = printer.model().await.unwrap();
+    /// if model.supports_bgcode() {
+    ///     println!("The {} can print binary g-code", model);
+    /// }
+    /// # })
+    /// ```
+    pub async fn model(&self) -> Result<PrinterModel, Box<dyn Error>> {
+        Ok(self.identity().await?.model)
+    }
+
+    /// Checks that the printer runs PrusaLink `min_version` or newer, for example before using
+    /// endpoints older firmware answers with a 404. The version is detected once, see `identity()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::FirmwareTooOld` if PrusaLink is older or didn't report its version,
+    /// and an Err if `min_version` isn't a version or the printer can't be reached.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer.require_min_version("2.1.0").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn require_min_version(&self, min_version: &str) -> Result<(), Box<dyn Error>> {
+        let required = model::parse_version(min_version)
+            .ok_or_else(|| format!("Invalid version {}", min_version))?;
+        let found = self.identity().await?.prusa_link.clone();
+
+        match found {
+            Some(found) if found >= required => Ok(()),
+            found => Err(PrinterError::FirmwareTooOld { required, found }.into()),
+        }
+    }
+
+    /// Returns a RawPrinter struct with all the information about the printer
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an Err.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// Remember to check that youre using the right address and port.
+    pub async fn get_printer_info(&mut self) -> Result<RawPrinter, Box<dyn Error>> {
+        self.fetch_printer_info().await
+    }
+
+    /// Returns the cached printer information, refreshing it first if it is outdated,
+    /// following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// Use this to read fields the getters of `Printer` don't cover without sending a request every time,
+    /// unlike `get_printer_info()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let printer_info = printer.cached_or_refresh().await.unwrap();
+    /// println!("Z height: {:?}", printer_info.telemetry().z_height);
+    /// # })
+    /// ```
+    pub async fn cached_or_refresh(&mut self) -> Result<&RawPrinter, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        Ok(self.printer.as_ref().unwrap())
+    }
+
+    /// Requests `/api/printer`, `get_printer_info()` without the mutable borrow
+    async fn fetch_printer_info(&self) -> Result<RawPrinter, Box<dyn Error>> {
+        let res = self
+            .send(self.request(http::Method::GET, "/api/printer"))
+            .await?;
+
+        parse_printer_info(self.compatibility, res.body())
+    }
+
+    /// Refreshes the internal printer information by sending a request to the printer.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an Err.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    /// ```should_panic
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///    .build();
+    ///
+    /// printer.refresh().await.unwrap(); // Errors since this is not a valid address
+    /// # })
+    pub async fn refresh(&mut self) -> Result<(), Box<dyn Error>> {
+        let printer = self.fetch_printer_info().await?;
+        self.store_printer(printer, self.clock.now());
+
+        Ok(())
+    }
+
+    /// Returns the job that is currently running on the printer, or None if there is no job.
+    ///
+    /// This always sends a request to the printer, use the cached getters such as
+    /// `progress_percent()` if you don't need the whole job.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn get_job_info(&self) -> Result<Option<RawJob>, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the job endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, "/api/v1/job"))
+            .await?;
+
+        if res.status() == http::StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the job, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_job_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(Some(serde_json::from_str::<RawJob>(&raw_job_text)?))
+    }
+
+    /// Refreshes the internal job information by sending a request to the printer.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn refresh_job(&mut self) -> Result<(), Box<dyn Error>> {
+        self.job = self.get_job_info().await?;
+        self.last_job_refresh = Some(self.clock.now());
+
+        Ok(())
+    }
+
+    /// Returns the job with the given id, or None if the printer doesn't know it,
+    /// for example because it has already been replaced by a newer job.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn get_job(&self, id: JobId) -> Result<Option<RawJob>, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the job endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, &format!("/api/v1/job/{}", id)))
+            .await?;
+
+        if res.status() == http::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get job {}, server responded with {}",
+                id,
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_job_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(Some(serde_json::from_str::<RawJob>(&raw_job_text)?))
+    }
+
+    /// Pauses the job with the given id.
+    ///
+    /// Like every command, this marks the cached information as outdated,
+    /// so the next getter refreshes it instead of returning the state from before the command.
+    ///
+    /// # Errors
+    ///
+    /// If the job isn't running or doesn't exist, the printer responds with an error status
+    /// and the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if let Some(job) = printer.get_job_info().await.unwrap() {
+    ///     printer.pause_job(job.get_id()).await.unwrap();
+    /// }
+    /// # })
+    /// ```
+    pub async fn pause_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.job_command(http::Method::PUT, id, "/pause", "pause")
+            .await
+    }
+
+    /// Resumes the paused job with the given id.
+    ///
+    /// # Errors
+    ///
+    /// If the job isn't paused or doesn't exist, the printer responds with an error status
+    /// and the function will return an Err.
+    pub async fn resume_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.job_command(http::Method::PUT, id, "/resume", "resume")
+            .await
+    }
+
+    /// Continues the job with the given id after the printer asked for attention,
+    /// for example after a filament change.
+    ///
+    /// # Errors
+    ///
+    /// If the printer isn't waiting for attention or the job doesn't exist,
+    /// the printer responds with an error status and the function will return an Err.
+    pub async fn continue_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.job_command(http::Method::PUT, id, "/continue", "continue")
+            .await
+    }
+
+    /// Stops the job with the given id, this is a `DELETE` of the job and can't be undone.
+    ///
+    /// # Errors
+    ///
+    /// If the job has already ended or doesn't exist, the printer responds with an error status
+    /// and the function will return an Err.
+    pub async fn stop_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.job_command(http::Method::DELETE, id, "", "stop").await
+    }
+
+    /// Starts printing a file that is already on the printer, `storage` is for example `usb` or `local`
+    /// and `path` the path of the file on it.
+    ///
+    /// # Errors
+    ///
+    /// If the file doesn't exist or the printer is busy, the printer responds with an error status
+    /// and the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer.start_print("usb", "prints/benchy.bgcode").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn start_print(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let storage = storage.into();
+        self.require(|capabilities| capabilities.v1_api, "the files endpoint")
+            .await?;
+
+        let _lock = self.lock_commands(&format!("print {}", path)).await?;
+        let res = self
+            .send(self.request(http::Method::POST, &files_url(&storage, path)?))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to start printing {}, server responded with {}",
+                path,
+                res.status()
+            )
+            .into());
+        }
+
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Deletes the job with the given id after it has ended, clearing it from the printer.
+    ///
+    /// PrusaLink stops a running job on the same `DELETE` request, so the current job is checked first
+    /// and a job that is still running is never deleted, use `stop_job()` for that.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::JobActive` if the job is still running, or if the printer refuses
+    /// with `409 Conflict`. Any other error status is returned as an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, raw_job::JobId};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer.delete_job(JobId(297)).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn delete_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        let _lock = self.lock_commands(&format!("delete job {}", id)).await?;
+        let current = self.get_job_info().await?;
+        if current.is_some_and(|job| job.get_id() == id && job.is_active()) {
+            return Err(PrinterError::JobActive(id).into());
+        }
+
+        let res = self
+            .send(self.request(http::Method::DELETE, &format!("/api/v1/job/{}", id)))
+            .await?;
+
+        if res.status() == http::StatusCode::CONFLICT {
+            return Err(PrinterError::JobActive(id).into());
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to delete job {}, server responded with {}",
+                id,
+                res.status()
+            )
+            .into());
+        }
+
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Polls the printer every `interval` and yields the events that happened between two polls,
+    /// such as state changes, prints starting or finishing, and heaters reaching their target.
+    ///
+    /// The first poll only establishes the baseline, so no events are yielded for the state
+    /// the printer is already in. Failed polls are yielded as `PrinterEvent::RefreshFailed`
+    /// and the stream keeps polling, it never ends on its own.
+    ///
+    /// The cached printer information is updated on every poll. The printer is polled at most
+    /// every 100 ms, a shorter or zero `interval` is raised to that.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, events::PrinterEvent};
+    /// # use std::time::Duration;
+    /// # use futures::StreamExt;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let mut events = Box::pin(printer.watch(Duration::from_secs(5)));
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     if event == PrinterEvent::PrintFinished {
+    ///         println!("Print done!");
+    ///     }
+    /// }
+    /// # })
+    /// ```
+    pub fn watch(&mut self, interval: Duration) -> impl Stream<Item = PrinterEvent> + '_ {
+        let ticker = Ticker::new(interval);
+
+        let state = (self, ticker, None::<RawPrinter>, VecDeque::new());
+
+        stream::unfold(
+            state,
+            |(printer, mut ticker, mut previous, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (printer, ticker, previous, pending)));
+                    }
+
+                    ticker.tick().await;
+
+                    // The error isn't `Send`, so it can't be kept across the awaits below
+                    if let Err(err) = printer.refresh().await {
+                        pending.push_back(PrinterEvent::RefreshFailed(err.to_string()));
+                        continue;
+                    }
+
+                    let current = printer.printer.clone();
+
+                    if let (Some(previous), Some(current)) = (&previous, &current) {
+                        let events = diff_events(previous, current);
+                        printer.record_jobs(&events, current).await;
+                        pending.extend(events);
+                    }
+
+                    previous = current;
+                }
+            },
+        )
+    }
+
+    /// Registers an async callback that `run()` calls with every event of the given kind,
+    /// for those who prefer callbacks over consuming the stream of `watch()`.
+    ///
+    /// Handlers are called one after the other, in the order they were registered,
+    /// and the printer isn't refreshed while a handler runs.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, events::{EventKind, PrinterEvent}};
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer.on(EventKind::PrintFinished, |_| async {
+    ///     println!("Print done!");
+    /// });
+    /// printer.on(EventKind::RefreshFailed, |event| async move {
+    ///     eprintln!("{:?}", event);
+    /// });
+    ///
+    /// printer.run(Duration::from_secs(5)).await;
+    /// # })
+    /// ```
+    pub fn on<F, Fut>(&mut self, kind: EventKind, handler: F)
+    where
+        F: Fn(PrinterEvent) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let handler: EventHandler = std::sync::Arc::new(move |event| Box::pin(handler(event)));
+
+        self.sender.hooks.on_event.push((kind, handler));
+    }
+
+    /// Refreshes the printer every `interval` and calls the handlers registered with `on()`
+    /// with the events of `watch()`.
+    ///
+    /// This never returns, spawn it as a task or race it against something that does.
+    pub async fn run(&mut self, interval: Duration) {
+        let hooks = self.sender.hooks.clone();
+        let mut events = std::pin::pin!(self.watch(interval));
+
+        while let Some(event) = events.next().await {
+            hooks.event(&event).await;
+        }
+    }
+
+    /// Reboots the computer PrusaLink runs on, with the `POST /api/system/commands/core/reboot` command
+    /// of the Raspberry Pi version of PrusaLink. `confirm` has to be true, so this isn't done by accident.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err without sending anything if `confirm` is false.
+    /// Returns a `PrinterError::Unsupported` if the firmware doesn't have the command,
+    /// and an Err if the printer responds with another error status.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if printer.is_idle().await.unwrap() {
+    ///     printer.reboot(true).await.unwrap();
+    /// }
+    /// # })
+    /// ```
+    pub async fn reboot(&self, confirm: bool) -> Result<(), Box<dyn Error>> {
+        self.system_command("reboot", confirm).await
+    }
+
+    /// Shuts down the computer PrusaLink runs on, see `reboot()`.
+    /// The printer can't be reached afterwards until it is switched on again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err without sending anything if `confirm` is false.
+    /// Returns a `PrinterError::Unsupported` if the firmware doesn't have the command,
+    /// and an Err if the printer responds with another error status.
+    pub async fn shutdown(&self, confirm: bool) -> Result<(), Box<dyn Error>> {
+        self.system_command("shutdown", confirm).await
+    }
+
+    /// Waits until the current print has ended, and returns the state the printer ended up in,
+    /// for example `LinkState::Finished` or `LinkState::Stopped`.
+    ///
+    /// The printer is refreshed every `poll_interval`, at most every 100 ms even if a shorter
+    /// or zero interval is given. If the printer isn't printing when this is called
+    /// the function returns right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Timeout` if the print hasn't ended after `timeout`,
+    /// pass `Duration::MAX` to wait forever.
+    /// Any error while refreshing the printer information is returned as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let state = printer
+    ///     .wait_until_finished(Duration::from_secs(10), Duration::from_secs(4 * 60 * 60))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// println!("Print ended with {}", state);
+    /// # })
+    /// ```
+    pub async fn wait_until_finished(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<LinkState, Box<dyn Error>> {
+        self.poll_until(poll_interval, timeout, |printer| {
+            !(printer.get_printing() || printer.get_pausing() || printer.get_paused())
+        })
+        .await
+    }
+
+    /// Waits until the printer is in the given state.
+    ///
+    /// The printer is refreshed every `poll_interval`, at most every 100 ms even if a shorter
+    /// or zero interval is given. If the printer is already in the state
+    /// the function returns right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Timeout` if the printer hasn't entered the state after `timeout`,
+    /// pass `Duration::MAX` to wait forever.
+    /// Any error while refreshing the printer information is returned as well.
+    pub async fn wait_for_state(
+        &mut self,
+        state: LinkState,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.poll_until(poll_interval, timeout, |printer| {
+            printer.link_state() == state
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Waits until the nozzle is within `tolerance` degrees of `target`, for example to pre-heat
+    /// the printer before uploading a job.
+    ///
+    /// The printer is refreshed at the auto refresh interval, or every `config::DEFAULT_REFRESH`
+    /// if auto refresh is off. If the nozzle is already at the temperature the function returns right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Timeout` if the nozzle hasn't reached the temperature after `timeout`,
+    /// pass `Duration::MAX` to wait forever.
+    /// Any error while refreshing the printer information is returned as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer
+    ///     .wait_for_nozzle_temp(215.0, 2.0, Duration::from_secs(300))
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn wait_for_nozzle_temp(
+        &mut self,
+        target: impl Into<Celsius>,
+        tolerance: f32,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.wait_for_temp(Heater::Nozzle, target.into(), tolerance, timeout)
+            .await
+    }
+
+    /// Waits until the bed is within `tolerance` degrees of `target`,
+    /// see `wait_for_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Timeout` if the bed hasn't reached the temperature after `timeout`,
+    /// pass `Duration::MAX` to wait forever.
+    /// Any error while refreshing the printer information is returned as well.
+    pub async fn wait_for_bed_temp(
+        &mut self,
+        target: impl Into<Celsius>,
+        tolerance: f32,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        self.wait_for_temp(Heater::Bed, target.into(), tolerance, timeout)
+            .await
+    }
+
+    /// Returns the status of the printer from `/api/v1/status`, which combines the state,
+    /// the most important telemetry and the progress of the current job.
+    ///
+    /// This always sends a request to the printer.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn get_status(&self) -> Result<RawStatus, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the status endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, "/api/v1/status"))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the status, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_status_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(serde_json::from_str::<RawStatus>(&raw_status_text)?)
+    }
+
+    /// Refreshes the internal status information by sending a request to the printer.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn refresh_status(&mut self) -> Result<(), Box<dyn Error>> {
+        self.status = Some(self.get_status().await?);
+        self.last_status_refresh = Some(self.clock.now());
+
+        Ok(())
+    }
+
+    /// Returns information about the printer from `/api/v1/info`,
+    /// such as its name, nozzle diameter and whether a multi material unit is connected.
+    ///
+    /// This always sends a request to the printer.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn get_info(&self) -> Result<RawInfo, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the info endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, "/api/v1/info"))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the printer info, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_info_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(serde_json::from_str::<RawInfo>(&raw_info_text)?)
+    }
+
+    /// Returns the network settings of the printer from `/api/v1/network`, such as its addresses
+    /// and the Wi-Fi network with its signal strength, to find printers with a bad connection.
+    ///
+    /// This always sends a request to the printer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Unsupported` if the PrusaLink version doesn't have the endpoint.
+    /// If the server returns another error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let network = printer.get_network_info().await.unwrap();
+    /// if let Some(wifi) = network.wifi() {
+    ///     println!("{:?} at {:?}%", wifi.ssid, wifi.signal_quality());
+    /// }
+    /// # })
+    /// ```
+    pub async fn get_network_info(&self) -> Result<NetworkInfo, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the network endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, "/api/v1/network"))
+            .await?;
+
+        if matches!(
+            res.status(),
+            http::StatusCode::NOT_FOUND | http::StatusCode::NOT_IMPLEMENTED
+        ) {
+            return Err(PrinterError::Unsupported("the network endpoint".to_string()).into());
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the network info, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_network_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(serde_json::from_str::<NetworkInfo>(&raw_network_text)?)
+    }
+
+    /// Returns the storages of the printer, such as the usb stick or the internal storage,
+    /// with their free and total space.
+    ///
+    /// Firmware without the `/api/v1/storage` endpoint reports the storages from `/api/printer` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Unsupported` if the printer reports the endpoint as missing
+    /// with `404 Not Found` or `501 Not Implemented`.
+    /// If the server returns another error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn get_storage_info(&self) -> Result<Vec<StorageInfo>, Box<dyn Error>> {
+        if !self.capabilities().await?.v1_api {
+            let storage = self.fetch_printer_info().await?.storage().clone();
+
+            return Ok(StorageInfo::from_legacy(&storage));
+        }
+
+        let res = self
+            .send(self.request(http::Method::GET, "/api/v1/storage"))
+            .await?;
+
+        if matches!(
+            res.status(),
+            http::StatusCode::NOT_FOUND | http::StatusCode::NOT_IMPLEMENTED
+        ) {
+            return Err(PrinterError::Unsupported("the storage endpoint".to_string()).into());
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the storage information, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_storage_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(serde_json::from_str::<RawStorageList>(&raw_storage_text)?.storage_list)
+    }
+
+    /// Returns the files and folders in the folder `path` of the storage, use an empty path for the root.
+    ///
+    /// # Errors
+    ///
+    /// If the folder doesn't exist, the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    /// Returns a `PrinterError::Unsupported` on firmware without the `/api/v1` endpoints.
+    pub async fn list_files(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<Vec<FileEntry>, Box<dyn Error>> {
+        Ok(self.list_folder(&storage.into(), path).await?.children)
+    }
+
+    /// Returns the folder `path` of the storage with everything below it, use an empty path for the root.
+    ///
+    /// Every folder is listed with its own request, so this can take a while on storages with many folders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if any of the folders can't be listed, see `list_files()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let tree = printer.file_tree("usb", "").await.unwrap();
+    ///
+    /// for file in tree.filter("*.bgcode") {
+    ///     println!("{} ({:?} bytes)", file.path, file.entry.size);
+    /// }
+    /// # })
+    /// ```
+    pub async fn file_tree(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<FileTree, Box<dyn Error>> {
+        let storage = storage.into();
+        let path = path.trim_matches('/');
+        let mut folders = std::collections::HashMap::new();
+        let mut pending = vec![path.to_string()];
+
+        while let Some(folder) = pending.pop() {
+            let entry = self.list_folder(&storage, &folder).await?;
+
+            pending.extend(
+                entry
+                    .children
+                    .iter()
+                    .filter(|child| child.is_folder())
+                    .map(|child| files::join(&folder, child.display_name())),
+            );
+            folders.insert(folder, entry);
+        }
+
+        let mut root = folders.remove(path).unwrap();
+        fill_folders(path, &mut root, &mut folders);
+
+        Ok(FileTree::new(path, root))
+    }
+
+    /// Returns every file of the storage whose path matches a glob pattern, like `*.bgcode`
+    /// or `projects/**/*.gcode`, see `files::glob_match()` for the syntax.
+    ///
+    /// The pattern is matched here and not by the printer, so the whole storage is listed first
+    /// with `file_tree()`. The files are returned in the order of `FileTree::iter()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if any of the folders can't be listed, see `list_files()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// for file in printer.find_files("usb", "old/**/*.gcode").await.unwrap() {
+    ///     println!("{}", file.path);
+    /// }
+    /// # })
+    /// ```
+    pub async fn find_files(
+        &self,
+        storage: impl Into<Storage>,
+        pattern: &str,
+    ) -> Result<Vec<FileTree>, Box<dyn Error>> {
+        let tree = self.file_tree(storage, "").await?;
+
+        Ok(tree.filter(pattern).cloned().collect())
+    }
+
+    /// Returns the details of a single file, including the metadata the slicer wrote into it.
+    /// `storage` is for example `usb` or `local` and `path` the path of the file on it.
+    ///
+    /// # Errors
+    ///
+    /// If the file doesn't exist, the printer responds with `404 Not Found` and the function
+    /// will return an Err, as for any other error status or a response that can't be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let file = printer.get_file_info("usb", "prints/benchy.bgcode").await.unwrap();
+    ///
+    /// println!("{} takes {:?} to print", file.display_name(), file.estimated_print_time());
+    /// # })
+    /// ```
+    pub async fn get_file_info(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<FileDetail, Box<dyn Error>> {
+        self.find_file(&storage.into(), path).await?.ok_or_else(|| {
+            format!(
+                "Failed to get the file {}, server responded with {}",
+                path,
+                http::StatusCode::NOT_FOUND
+            )
+            .into()
+        })
+    }
+
+    /// Same as `get_file_info()`, but returns None if the file doesn't exist
+    async fn find_file(
+        &self,
+        storage: &Storage,
+        path: &str,
+    ) -> Result<Option<FileDetail>, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the files endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, &files_url(storage, path)?))
+            .await?;
+
+        if res.status() == http::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the file {}, server responded with {}",
+                path,
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_file_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(Some(serde_json::from_str::<FileDetail>(&raw_file_text)?))
+    }
+
+    /// Deletes a file from the storage, `storage` is for example `usb` or `local`
+    /// and `path` the path of the file on it.
+    ///
+    /// # Errors
+    ///
+    /// If the file doesn't exist or can't be deleted, for example because it is being printed,
+    /// the printer responds with an error status and the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer.delete_file("usb", "prints/benchy.bgcode").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn delete_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let storage = storage.into();
+        self.require(|capabilities| capabilities.v1_api, "the files endpoint")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::DELETE, &files_url(&storage, path)?))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to delete {}, server responded with {}",
+                path,
+                res.status()
+            )
+            .into());
+        }
+
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Lists the folder `path` of the storage, with its entries in `children`
+    async fn list_folder(
+        &self,
+        storage: &Storage,
+        path: &str,
+    ) -> Result<FileEntry, Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "listing files")
+            .await?;
+
+        let res = self
+            .send(self.request(http::Method::GET, &files_url(storage, path)?))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to list the files, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        let raw_files_text = String::from_utf8_lossy(res.body()).into_owned();
+
+        Ok(serde_json::from_str::<FileEntry>(&raw_files_text)?)
+    }
+
+    /// Downloads the thumbnail of a file as PNG bytes.
+    ///
+    /// Returns None if the file doesn't have a thumbnail of the requested size,
+    /// for example because it was sliced without thumbnails.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, raw_job::ThumbnailSize};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let job = printer.get_job_info().await.unwrap().unwrap();
+    /// let refs = job.get_file().unwrap().refs.as_ref().unwrap();
+    ///
+    /// if let Some(png) = printer.get_thumbnail(refs, ThumbnailSize::Large).await.unwrap() {
+    ///     std::fs::write("preview.png", png).unwrap();
+    /// }
+    /// # })
+    /// ```
+    pub async fn get_thumbnail(
+        &self,
+        refs: &FileRefs,
+        size: ThumbnailSize,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let Some(thumbnail_ref) = refs.thumbnail_ref(size) else {
+            return Ok(None);
+        };
+
+        let res = self
+            .send(self.request(http::Method::GET, thumbnail_ref))
+            .await?;
+
+        if res.status() == http::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get the thumbnail, server responded with {}",
+                res.status()
+            )
+            .into());
+        }
+
+        Ok(Some(res.into_body().to_vec()))
+    }
+
+    // Create files/directories on the printer.
+    // TODO: Implement this function
+
+    // Check if file exists on the printer.
+    // TODO: Implement this function
+
+    // Print gcode from printer storage.
+    // TODO: Implement this function
+
+    /// Returns the current nozzle temperature of the printer.
+    ///
+    /// If auto refresh is enabled, the function will refresh the printer information if
+    /// the specified time on `last_refresh` has passed, otherwise it will use the cached information.
+    ///
+    /// If auto refresh is disabled, the function will only refresh the printer information if
+    /// there is no cached information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an Err.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```should_panic
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .auto_refresh(Duration::from_secs(5))
+    ///     .build();
+    ///
+    /// let nozzle_temp = printer.get_nozzle_temp().await.unwrap(); // Errors since this is not a valid address
+    /// # })
+    /// ```
+    pub async fn get_nozzle_temp(&mut self) -> Result<Celsius, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_nozzle_temp())
+    }
+
+    /// Returns the current bed temperature of the printer.
+    ///
+    /// If `auto_refresh` is Some, the function will refresh the printer information if
+    /// the specified time on `last_refresh` has passed, otherwise it will use the cached information.
+    ///
+    /// If `auto_refresh` is None, the function will only refresh the printer information if
+    /// there is no cached information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```should_panic
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::time::Duration;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .auto_refresh(Duration::from_secs(5))
+    ///     .build();
+    ///
+    /// let nozzle_temp = printer.get_bed_temp().await.unwrap(); // Errors since this is not a valid address
+    /// # })
+    /// ```
+    pub async fn get_bed_temp(&mut self) -> Result<Celsius, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_bed_temp())
+    }
+
+    /// Returns the temperature inside the enclosure, or None if the printer has no chamber sensor,
+    /// like printers without an enclosure.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn get_chamber_temp(&mut self) -> Result<Option<Celsius>, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_chamber_temp())
+    }
+
+    /// Returns the material loaded in the printer, or None if nothing is loaded
+    /// or the printer doesn't report it, like the MINI.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use prusa_link_rs::material::Material;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if printer.loaded_material().await.unwrap() != Some(Material::Petg) {
+    ///     println!("Load PETG before starting this job");
+    /// }
+    /// # })
+    /// ```
+    pub async fn loaded_material(&mut self) -> Result<Option<Material>, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        Ok(self.printer.as_ref().unwrap().get_material())
+    }
+
+    /// Returns the current temperature of the tool with the given index,
+    /// or None if the printer doesn't have that many tools.
+    ///
+    /// Tool 0 is the only tool on single tool printers, the XL has up to 5 tools.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn get_tool_temp(&mut self, index: usize) -> Result<Option<Celsius>, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_tool_temp(index))
+    }
+
+    /// Returns the actual and target temperature of the nozzle from the same refresh,
+    /// following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let nozzle = printer.nozzle().await.unwrap();
+    /// if nozzle.is_heating(2.0) {
+    ///     println!("Heating to {}, currently {}", nozzle.target, nozzle.actual);
+    /// }
+    /// # })
+    /// ```
+    pub async fn nozzle(&mut self) -> Result<Temperatures, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer
+            .temperatures()
+            .nozzle
+            .resolve(TemperatureSource::Telemetry))
+    }
+
+    /// Returns the actual and target temperature of the bed from the same refresh,
+    /// following the same auto refresh rules as `get_bed_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn bed(&mut self) -> Result<Temperatures, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer
+            .temperatures()
+            .bed
+            .resolve(TemperatureSource::Telemetry))
+    }
+
+    /// Returns every temperature reported by the printer with both the reading of the
+    /// temperature block and of the telemetry, which can be a few degrees apart while heating.
+    /// `nozzle()` and `bed()` resolve them with `TemperatureSource::Telemetry`.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use prusa_link_rs::units::TemperatureSource;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let temperatures = printer.temperatures().await.unwrap();
+    /// if let Some(degrees) = temperatures.nozzle.disagreement() {
+    ///     println!("The nozzle readings are {degrees} degrees apart");
+    /// }
+    /// println!("{}", temperatures.nozzle.resolve(TemperatureSource::Block).actual);
+    /// # })
+    /// ```
+    pub async fn temperatures(&mut self) -> Result<TemperatureView, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        Ok(self.printer.as_ref().unwrap().temperatures())
+    }
+
+    /// Returns the free space in bytes of the storage with the given name or path, like `local` or `/usb`,
+    /// or None if the printer doesn't report it.
+    ///
+    /// Uses the storage block of the cached printer information, following the same auto refresh rules
+    /// as `get_nozzle_temp()`. Storages the block doesn't cover are looked up with `get_storage_info()`,
+    /// which always sends a request.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if let Some(free) = printer.free_space("usb").await.unwrap() {
+    ///     println!("{} MB left on the usb stick", free / 1_000_000);
+    /// }
+    /// # })
+    /// ```
+    pub async fn free_space(
+        &mut self,
+        storage: impl Into<Storage>,
+    ) -> Result<Option<u64>, Box<dyn Error>> {
+        Ok(self
+            .storage_space(&storage.into())
+            .await?
+            .and_then(|info| info.free_space))
+    }
+
+    /// Returns the total space in bytes of the storage with the given name or path,
+    /// or None if the printer doesn't report it, see `free_space()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn total_space(
+        &mut self,
+        storage: impl Into<Storage>,
+    ) -> Result<Option<u64>, Box<dyn Error>> {
+        Ok(self
+            .storage_space(&storage.into())
+            .await?
+            .and_then(|info| info.total_space))
+    }
+
+    /// Returns the speeds of the hotend fan and the print fan in RPM,
+    /// each None if the printer doesn't report it.
+    ///
+    /// Uses the cached status information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if printer.hotend_fan_stalled().await.unwrap() {
+    ///     println!("The hotend fan stopped!");
+    /// }
+    /// # })
+    /// ```
+    pub async fn fan_speeds(&mut self) -> Result<(Option<u32>, Option<u32>), Box<dyn Error>> {
+        self.refresh_status_if_necessary().await?;
+
+        let status = self.status.as_ref().unwrap();
+
+        Ok((status.get_fan_hotend(), status.get_fan_print()))
+    }
+
+    /// Returns true if the nozzle is hot but the hotend fan isn't spinning.
+    /// Printers that don't report the fan speed are never considered stalled.
+    ///
+    /// Uses the cached status information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn hotend_fan_stalled(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_status_if_necessary().await?;
+
+        let status = self.status.as_ref().unwrap();
+
+        Ok(status.hotend_fan_stalled().unwrap_or(false))
+    }
+
+    /// Returns why the printer stopped or asks for attention, with the error code if the firmware
+    /// reports one, or None if the printer is fine. See `RawStatus::error_info()`.
+    ///
+    /// Uses the cached status information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if let Some(error) = printer.current_error().await.unwrap() {
+    ///     println!("Printer stopped: {} (code {:?})", error.text, error.code);
+    /// }
+    /// # })
+    /// ```
+    pub async fn current_error(&mut self) -> Result<Option<PrinterErrorInfo>, Box<dyn Error>> {
+        self.refresh_status_if_necessary().await?;
+
+        let status = self.status.as_ref().unwrap();
+
+        Ok(status.error_info())
+    }
+
+    /// Returns the speed and flow overrides in percent, each None if the printer doesn't report it.
+    ///
+    /// Uses the cached status information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn speed_and_flow(&mut self) -> Result<(Option<u32>, Option<u32>), Box<dyn Error>> {
+        self.refresh_status_if_necessary().await?;
+
+        let status = self.status.as_ref().unwrap();
+
+        Ok((status.get_speed_factor(), status.get_flow_factor()))
+    }
+
+    /// Returns true if a multi material unit is connected to the printer.
+    ///
+    /// The printer information this is based on is only fetched once, since it only changes
+    /// when the printer is reconfigured. Firmwares that don't report it are treated as having no unit.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn has_mmu(&mut self) -> Result<bool, Box<dyn Error>> {
+        if self.info.is_none() {
+            self.info = Some(self.get_info().await?);
+        }
+
+        let info = self.info.as_ref().unwrap();
+
+        Ok(info.mmu.unwrap_or(false))
+    }
+
+    /// Returns the slot of the multi material unit that is currently in use, counting from 1,
+    /// or None if the printer has no unit or doesn't report it.
+    ///
+    /// Uses the cached status information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn active_slot(&mut self) -> Result<Option<u32>, Box<dyn Error>> {
+        self.refresh_status_if_necessary().await?;
+
+        let status = self.status.as_ref().unwrap();
+
+        Ok(status.get_active_slot())
+    }
+
+    /// Returns true if the filament sensor detects filament,
+    /// or None if the printer doesn't report its filament sensor.
+    ///
+    /// Uses the cached status information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if printer.filament_detected().await.unwrap() == Some(false) {
+    ///     panic!("Load some filament first");
+    /// }
+    /// # })
+    /// ```
+    pub async fn filament_detected(&mut self) -> Result<Option<bool>, Box<dyn Error>> {
+        self.refresh_status_if_necessary().await?;
+
+        let status = self.status.as_ref().unwrap();
+
+        Ok(status.get_filament_detected())
+    }
+
+    /// Returns true if the printer is currently printing.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```should_panic
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if printer.is_printing().await.unwrap() { // Errors since this is not a valid address
+    ///     println!("{:?}% done", printer.progress_percent().await.unwrap());
+    /// }
+    /// # })
+    /// ```
+    pub async fn is_printing(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_printing())
+    }
+
+    /// Returns true if the printer is not doing anything and has no errors,
+    /// meaning it is not printing, pausing, paused or cancelling.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_idle(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.is_idle())
+    }
+
+    /// Returns true if the printer reports an error.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn has_error(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.has_error())
+    }
+
+    /// Returns the link state of the printer, like `LinkState::Printing`.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, raw_printer::LinkState};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// if printer.state().await.unwrap() == LinkState::Attention {
+    ///     println!("The printer needs attention: {}", printer.state_text().await.unwrap());
+    /// }
+    /// # })
+    /// ```
+    pub async fn state(&mut self) -> Result<LinkState, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.link_state())
+    }
+
+    /// Returns the state as text, as shown on the display of the printer.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn state_text(&mut self) -> Result<String, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_state_text().to_string())
+    }
+
+    /// Returns every state flag of the printer at once.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn flags(&mut self) -> Result<PrinterFlags, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.flags().clone())
+    }
+
+    /// Returns true if the printer is connected and can take commands.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_operational(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_operational())
+    }
+
+    /// Returns true if the printer is ready to start a print.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_ready(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_ready())
+    }
+
+    /// Returns true if the current print is paused.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_paused(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_paused())
+    }
+
+    /// Returns true if the current print is being paused.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_pausing(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_pausing())
+    }
+
+    /// Returns true if the current print is being stopped.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_cancelling(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_cancelling())
+    }
+
+    /// Returns true if the last print has finished.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_finished(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_finished())
+    }
+
+    /// Returns true if a print has been prepared to start.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_prepared(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_prepared())
+    }
+
+    /// Returns true if the storage of the printer is ready.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_sd_ready(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_sd_ready())
+    }
+
+    /// Returns true if the printer is disconnected or in an error state.
+    ///
+    /// Uses the cached printer information, following the same auto refresh rules as `get_nozzle_temp()`.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an empty response, the function will return an `Err`.
+    /// This can happen if the server is not running or if the api key is incorrect.
+    pub async fn is_closed_or_error(&mut self) -> Result<bool, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+
+        Ok(printer.get_closed_or_error())
+    }
+
+    /// Returns the progress of the current job in percent, from 0 to 100,
+    /// or None if there is no job.
+    ///
+    /// Uses the cached job information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn progress_percent(&mut self) -> Result<Option<f32>, Box<dyn Error>> {
+        self.refresh_job_if_necessary().await?;
+
+        Ok(self.job.as_ref().map(|job| job.get_progress()))
+    }
+
+    /// Returns the estimated time remaining of the current job,
+    /// or None if there is no job or the printer has no estimate yet.
+    ///
+    /// Uses the cached job information, which is refreshed following the same auto refresh rules
+    /// as the printer information.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    pub async fn time_remaining(&mut self) -> Result<Option<Duration>, Box<dyn Error>> {
+        self.refresh_job_if_necessary().await?;
+
+        Ok(self.job.as_ref().and_then(|job| job.get_time_remaining()))
+    }
+
+    /// Returns how far the current print is, or None if there is no job.
+    ///
+    /// The progress is put together from the job, the status and the telemetry, which are refreshed
+    /// following the same auto refresh rules as the printer information. Printers without the status endpoint
+    /// only report what the job and the telemetry contain.
+    ///
+    /// # Errors
+    ///
+    /// If the server returns an error status or a response that can't be parsed,
+    /// the function will return an Err.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("192.168.1.50", "api_key").build();
+    ///
+    /// if let Some(progress) = printer.progress().await.unwrap() {
+    ///     println!("{:.0}% done, at {:?}mm", progress.percent, progress.current_layer_height);
+    /// }
+    /// # })
+    /// ```
+    pub async fn progress(&mut self) -> Result<Option<PrintProgress>, Box<dyn Error>> {
+        self.refresh_job_if_necessary().await?;
+
+        if self.job.is_none() {
+            return Ok(None);
+        }
+
+        match self.refresh_status_if_necessary().await {
+            Err(err) if matches!(err.downcast_ref(), Some(PrinterError::Unsupported(_))) => {}
+            result => result?,
+        }
+        // The telemetry only adds the layer height, so a failed refresh doesn't fail the progress
+        let _ = self.refresh_if_necessary().await;
+
+        Ok(self
+            .job
+            .as_ref()
+            .map(|job| PrintProgress::new(job, self.status.as_ref(), self.printer.as_ref())))
+    }
+}
+
+// impl block for minor helper functions
+impl Printer {
+    /// Returns a reference to the address string
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns the api key.
+    ///
+    /// The key is kept out of the `Debug` output of `Printer`, so only use this if you really need it,
+    /// for example to store it in a configuration file.
+    pub fn expose_api_key(&self) -> &str {
+        self.api_key.expose_secret()
+    }
+
+    /// Returns the recorded telemetry history,
+    /// or None if it wasn't enabled with `PrinterBuilder::telemetry_history()`
+    pub fn telemetry_history(&self) -> Option<&TelemetryHistory> {
+        self.history.as_ref()
+    }
+
+    /// Returns the recorded jobs,
+    /// or None if it wasn't enabled with `PrinterBuilder::job_history()`
+    pub fn job_history(&self) -> Option<&JobHistory> {
+        self.job_history.as_ref()
+    }
+
+    /// Returns the cached printer information without refreshing it,
+    /// or None if the printer hasn't been refreshed yet
+    pub fn cached(&self) -> Option<&RawPrinter> {
+        self.printer.as_ref()
+    }
+
+    /// Returns how the requests to the printer went recently
+    pub fn health(&self) -> Health {
+        self.sender.health.lock().unwrap().clone()
+    }
+
+    /// Returns the number of requests sent to every endpoint of the printer,
+    /// how many of them failed and how long they took
+    pub fn stats(&self) -> RequestStats {
+        self.sender.stats.lock().unwrap().clone()
+    }
+
+    /// Starts counting the requests of `stats()` from zero again
+    pub fn reset_stats(&self) {
+        self.sender.stats.lock().unwrap().clear();
+    }
+
+    /// Returns true if the cached getters are returning information from before an outage,
+    /// see `PrinterBuilder::serve_stale()`
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Changes the APIs url, in any format `PrinterAddress` accepts.
+    ///
+    /// The scheme, port and path prefix are taken from the address, the default port of the scheme
+    /// is used if it doesn't specify one. The cached information is dropped and the capabilities
+    /// are detected again afterwards, since it might be a different printer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BuildError::InvalidAddress` if the address can't be parsed, the printer is left unchanged then.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// let mut printer = PrinterBuilder::new("192.168.1.50", "api_key").build();
+    ///
+    /// printer
+    ///     .change_address("https://farm.local:8443/printers/mk4a")
+    ///     .unwrap();
+    /// assert!(printer.change_address("ftp://192.168.1.51").is_err());
+    /// ```
+    pub fn change_address(&mut self, address: &str) -> Result<(), BuildError> {
+        let address = address.parse::<PrinterAddress>()?;
+
+        self.scheme = address.scheme;
+        self.port = address.port_or_default().into();
+        self.base_path = address.path;
+        self.address = address.host;
+
+        self.printer = None;
+        self.last_refresh = None;
+        self.job = None;
+        self.last_job_refresh = None;
+        self.status = None;
+        self.last_status_refresh = None;
+        self.info = None;
+        self.revalidation = None;
+        self.stale = false;
+        self.capabilities = tokio::sync::OnceCell::new();
+        self.identity = tokio::sync::OnceCell::new();
+
+        Ok(())
+    }
+
+    /// changes the APIs api key
+    pub fn change_api_key(&mut self, api_key: String) {
+        self.api_key = SecretString::new(api_key);
+    }
+
+    /// Replaces the api key, for example after it was regenerated in the printer settings.
+    ///
+    /// Unlike `change_api_key()`, this also forgets the capabilities and the identity of the printer,
+    /// which might have been detected while the old key was rejected.
+    /// With `verify` the new key is checked with `check_connection()` first,
+    /// and the old key is kept if the check fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `ConnectionError` of the check if `verify` is true and the new key can't be used.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("192.168.1.50", "old_key").build();
+    ///
+    /// if let Err(err) = printer.rotate_api_key("new_key", true).await {
+    ///     println!("Still using the old key: {}", err);
+    /// }
+    /// # })
+    /// ```
+    pub async fn rotate_api_key(
+        &mut self,
+        api_key: impl Into<String>,
+        verify: bool,
+    ) -> Result<(), ConnectionError> {
+        let previous = std::mem::replace(&mut self.api_key, SecretString::new(api_key.into()));
+
+        if verify {
+            if let Err(err) = self.check_connection().await {
+                self.api_key = previous;
+                return Err(err);
+            }
+        }
+
+        self.capabilities = tokio::sync::OnceCell::new();
+        self.identity = tokio::sync::OnceCell::new();
+
+        Ok(())
+    }
+
+    /// Starts a request to the given path on the printer, with the api key and the headers of the builder already set
+    fn request(&self, method: http::Method, path: &str) -> http::request::Builder {
+        let mut request = http::Request::builder().method(method).uri(self.url(path));
+
+        if let Some(headers) = request.headers_mut() {
+            headers.extend(self.headers.clone());
+        }
+
+        request.header("X-Api-Key", self.expose_api_key())
+    }
+
+    /// Sends a request started with `request()` without a body
+    async fn send(
+        &self,
+        request: http::request::Builder,
+    ) -> Result<http::Response<bytes::Bytes>, TransportError> {
+        self.send_body(request, TransportBody::Empty).await
+    }
+
+    /// Sends a request started with `request()` with the given body, see `Sender::send()`
+    async fn send_body(
+        &self,
+        request: http::request::Builder,
+        body: TransportBody,
+    ) -> Result<http::Response<bytes::Bytes>, TransportError> {
+        self.sender.send(request.body(body)?).await
+    }
+
+    /// Returns the url of the given path on the printer, so the scheme, port and base path are applied everywhere
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}://{}:{}{}/{}",
+            self.scheme,
+            self.address,
+            self.port,
+            self.base_path,
+            encode_path(path.trim_start_matches('/'))
+        )
+    }
+
+    /// Sends a command to `/api/v1/job/{id}{action}`, `verb` is used in the error message
+    async fn job_command(
+        &self,
+        method: http::Method,
+        id: JobId,
+        action: &str,
+        verb: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.require(|capabilities| capabilities.v1_api, "the job endpoint")
+            .await?;
+
+        let _lock = self.lock_commands(&format!("{} job {}", verb, id)).await?;
+        let res = self
+            .send(self.request(method, &format!("/api/v1/job/{}{}", id, action)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to {} job {}, server responded with {}",
+                verb,
+                id,
+                res.status()
+            )
+            .into());
+        }
+
+        self.invalidate_cache();
+        Ok(())
+    }
+
+    /// Waits for or rejects `command` according to the command lock of the builder,
+    /// the command may run while the returned guard is kept
+    async fn lock_commands(
+        &self,
+        command: &str,
+    ) -> Result<Option<tokio::sync::MutexGuard<'_, ()>>, PrinterError> {
+        match &self.command_locker {
+            Some(locker) => Ok(Some(locker.acquire(command).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sends `/api/system/commands/core/{action}` if it was confirmed
+    async fn system_command(&self, action: &str, confirm: bool) -> Result<(), Box<dyn Error>> {
+        if !confirm {
+            return Err(format!("The {} wasn't confirmed", action).into());
+        }
+
+        let _lock = self.lock_commands(action).await?;
+        let res = self
+            .send(self.request(
+                http::Method::POST,
+                &format!("/api/system/commands/core/{}", action),
+            ))
+            .await?;
+
+        if matches!(
+            res.status(),
+            http::StatusCode::NOT_FOUND | http::StatusCode::NOT_IMPLEMENTED
+        ) {
+            return Err(PrinterError::Unsupported(format!("the {} command", action)).into());
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to send the {} command, server responded with {}",
+                action,
+                res.status()
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns a `PrinterError::Unsupported` naming `feature` if the firmware lacks it
+    async fn require<F>(&self, supported: F, feature: &str) -> Result<(), Box<dyn Error>>
+    where
+        F: Fn(&Capabilities) -> bool,
+    {
+        if supported(self.capabilities().await?) {
+            Ok(())
+        } else {
+            Err(PrinterError::Unsupported(feature.to_string()).into())
+        }
+    }
+
+    /// Refreshed the printer information if auto_refresh is enabled and the specified time has passed
+    /// since the last refresh.
+    ///
+    /// If auto_refresh is disabled, the function will refresh the printer information if
+    /// there is no cached information.
+    async fn refresh_if_necessary(&mut self) -> Result<(), Box<dyn Error>> {
+        self.apply_invalidation();
+        self.finish_revalidation();
+
+        if self.is_outdated("printer", self.last_refresh) && !self.revalidate() {
+            let result = self.refresh().await;
+            self.serve_stale_on_outage(result, self.last_refresh)?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts refreshing the printer information in the background if the cache policy allows answering
+    /// from the cache meanwhile, returns false if the information has to be refreshed right away
+    fn revalidate(&mut self) -> bool {
+        let CachePolicy::StaleWhileRevalidate { max_stale } = self.cache_policy else {
+            return false;
+        };
+        let Some(last_refresh) = self.last_refresh else {
+            return false;
+        };
+        if self.elapsed_since(last_refresh) > max_stale {
+            return false;
+        }
+        if self.revalidation.is_some() {
+            return true;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let sender = self.sender.clone();
+            let clock = self.clock.clone();
+            let compatibility = self.compatibility;
+            let request = self
+                .request(http::Method::GET, "/api/printer")
+                .body(TransportBody::Empty);
+
+            runtime.spawn(async move {
+                let result = match request {
+                    Ok(request) => sender.send(request).await.map_err(|err| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                let printer = result.and_then(|res| {
+                    parse_printer_info(compatibility, res.body())
+                        .map(|printer| (printer, clock.now()))
+                        .map_err(|err| err.to_string())
+                });
+
+                let _ = tx.send(printer);
+            });
+
+            self.revalidation = Some(rx);
+            return true;
+        }
+
+        false
+    }
+
+    /// Takes the result of a finished background refresh, a failed one is only visible in `health()`
+    fn finish_revalidation(&mut self) {
+        let Some(revalidation) = self.revalidation.as_mut() else {
+            return;
+        };
+
+        match revalidation.try_recv() {
+            Ok(Ok((printer, fetched_at))) => {
+                self.revalidation = None;
+
+                // A refresh that finished in the meantime has newer information
+                if self.last_refresh.is_none_or(|time| time < fetched_at) {
+                    self.store_printer(printer, fetched_at);
+                }
+            }
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => {}
+            _ => self.revalidation = None,
+        }
+    }
+
+    /// Marks the cached information as outdated after a command changed the printer
+    fn invalidate_cache(&self) {
+        self.invalidated
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Forgets when the cached information was refreshed if a command was sent since,
+    /// so it is refreshed before it is used again
+    fn apply_invalidation(&mut self) {
+        if !std::mem::take(self.invalidated.get_mut()) {
+            return;
+        }
+
+        self.last_refresh = None;
+        self.last_job_refresh = None;
+        self.last_status_refresh = None;
+        // A background refresh started before the command would bring back the old state
+        self.revalidation = None;
+    }
+
+    /// Caches freshly fetched printer information
+    fn store_printer(&mut self, printer: RawPrinter, fetched_at: Instant) {
+        if let Some(history) = self.history.as_mut() {
+            history.record(&printer);
+        }
+
+        self.printer = Some(printer);
+        self.last_refresh = Some(fetched_at);
+    }
+
+    /// Same as `refresh_if_necessary` but for the job information
+    async fn refresh_job_if_necessary(&mut self) -> Result<(), Box<dyn Error>> {
+        self.apply_invalidation();
+        if self.is_outdated("job", self.last_job_refresh) {
+            let result = self.refresh_job().await;
+            self.serve_stale_on_outage(result, self.last_job_refresh)?;
+        }
+
+        Ok(())
+    }
+
+    /// Same as `refresh_if_necessary` but for the status information
+    async fn refresh_status_if_necessary(&mut self) -> Result<(), Box<dyn Error>> {
+        self.apply_invalidation();
+        if self.is_outdated("status", self.last_status_refresh) {
+            let result = self.refresh_status().await;
+            self.serve_stale_on_outage(result, self.last_status_refresh)?;
+        }
+
+        Ok(())
+    }
+
+    /// Refreshes the printer every `poll_interval` until `condition` holds, returning the link state at that point
+    async fn poll_until<F>(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+        condition: F,
+    ) -> Result<LinkState, Box<dyn Error>>
+    where
+        F: Fn(&RawPrinter) -> bool,
+    {
+        let poll = async {
+            let mut ticker = Ticker::new(poll_interval);
+
+            loop {
+                ticker.tick().await;
+                self.refresh().await?;
+
+                let printer = self.printer.as_ref().unwrap();
+                if condition(printer) {
+                    return Ok(printer.link_state());
+                }
+            }
+        };
+
+        match time::timeout(timeout, poll).await {
+            Some(result) => result,
+            None => Err(PrinterError::Timeout(timeout).into()),
+        }
+    }
+
+    /// Finds the storage in the cached printer information, or in `/api/v1/storage` if the cache doesn't have it
+    async fn storage_space(
+        &mut self,
+        storage: &Storage,
+    ) -> Result<Option<StorageInfo>, Box<dyn Error>> {
+        self.refresh_if_necessary().await?;
+
+        let printer = self.printer.as_ref().unwrap();
+        let cached = StorageInfo::from_legacy(printer.storage())
+            .into_iter()
+            .find(|info| info.matches(storage));
+
+        if cached.is_some() || !self.capabilities().await?.v1_api {
+            return Ok(cached);
+        }
+
+        Ok(self
+            .get_storage_info()
+            .await?
+            .into_iter()
+            .find(|info| info.matches(storage)))
+    }
+
+    async fn wait_for_temp(
+        &mut self,
+        heater: Heater,
+        target: Celsius,
+        tolerance: f32,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        let reached = |printer: &RawPrinter| {
+            let actual = match heater {
+                Heater::Nozzle => printer.get_nozzle_temp(),
+                Heater::Bed => printer.get_bed_temp(),
+            };
+
+            (actual.value() - target.value()).abs() <= tolerance
+        };
+
+        self.apply_invalidation();
+        if self.printer.as_ref().is_some_and(reached)
+            && !self.is_outdated("printer", self.last_refresh)
+        {
+            return Ok(());
+        }
+
+        let poll_interval = self.auto_refresh.unwrap_or(config::DEFAULT_REFRESH);
+        self.poll_until(poll_interval, timeout, reached).await?;
+
+        Ok(())
+    }
+
+    /// Keeps the cached information if the refresh failed because the printer couldn't be reached,
+    /// and the cache isn't older than the limit set with `PrinterBuilder::serve_stale()`
+    fn serve_stale_on_outage(
+        &mut self,
+        result: Result<(), Box<dyn Error>>,
+        last_refresh: Option<Instant>,
+    ) -> Result<(), Box<dyn Error>> {
+        let err = match result {
+            Ok(()) => {
+                self.stale = false;
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+
+        let recent = match (last_refresh, self.serve_stale) {
+            (Some(time), Some(max_age)) => self.elapsed_since(time) <= max_age,
+            _ => false,
+        };
+
+        if recent && err.is::<TransportError>() {
+            self.stale = true;
+            return Ok(());
+        }
+
+        Err(err)
+    }
+
+    /// Returns how long ago `time` was according to the clock of the printer
+    fn elapsed_since(&self, time: Instant) -> Duration {
+        self.clock.now().saturating_duration_since(time)
+    }
+
+    /// Checks if information refreshed at `last_refresh` should be refreshed again
+    ///
+    /// `cache` names the information in the trace events of the `tracing` feature.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn is_outdated(&self, cache: &str, last_refresh: Option<Instant>) -> bool {
+        let outdated = match (last_refresh, self.auto_refresh) {
+            _ if self.cache_policy == CachePolicy::NoCache => true,
+            (Some(time), Some(duration)) if self.elapsed_since(time) > duration => true,
+            (None, _) => true,
+            _ => false,
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            cache,
+            decision = match (last_refresh, outdated) {
+                (None, _) => "empty",
+                (Some(_), true) => "stale",
+                (Some(_), false) => "hit",
+            },
+            "cache lookup"
+        );
+
+        outdated
+    }
+}
+
+/// Fills in the children of the folders below `entry` from the listings of `Printer::file_tree()`
+fn fill_folders(
+    path: &str,
+    entry: &mut FileEntry,
+    folders: &mut std::collections::HashMap<String, FileEntry>,
+) {
+    for child in entry.children.iter_mut().filter(|child| child.is_folder()) {
+        let child_path = files::join(path, child.display_name());
+
+        if let Some(listing) = folders.remove(&child_path) {
+            child.children = listing.children;
+            fill_folders(&child_path, child, folders);
+        }
+    }
+}
+
+/// Parses the body of `/api/printer`, filling in the state flags of OctoPrint style
+/// and SLA printer responses first
+fn parse_printer_info(
+    compatibility: Compatibility,
+    body: &[u8],
+) -> Result<RawPrinter, Box<dyn Error>> {
+    let raw_printer_text = String::from_utf8_lossy(body);
+
+    if raw_printer_text.trim().is_empty() {
+        return Err("Received an empty response from the server".into());
+    }
+
+    let raw_printer = serde_json::from_str(&raw_printer_text)?;
+
+    match (compatibility, printer_kind(&raw_printer)) {
+        (Compatibility::PrusaLink, PrinterKind::Fdm) => {
+            Ok(serde_json::from_value::<RawPrinter>(raw_printer)?)
+        }
+        (Compatibility::OctoPrint, _) | (_, PrinterKind::Sla) => {
+            Ok(serde_json::from_value::<RawPrinter>(
+                normalize_octoprint_printer(raw_printer),
+            )?)
+        }
+    }
+}
+
+/// Everything sending a request needs, kept apart from `Printer` so a spawned task can send requests as well
+#[derive(Debug, Clone)]
+struct Sender {
+    transport: std::sync::Arc<dyn HttpTransport>,
+    hooks: Hooks,
+    rate_limiter: Option<std::sync::Arc<RateLimiter>>,
+    health: std::sync::Arc<std::sync::Mutex<Health>>,
+    stats: std::sync::Arc<std::sync::Mutex<RequestStats>>,
+    circuit_breaker: Option<CircuitBreaker>,
+}
+
+impl Sender {
+    /// Sends a request, every request of the crate goes through this.
+    ///
+    /// The hooks of the builder are called here, every request is counted in the `RequestStats`,
+    /// and with the `tracing` feature every request is logged with its method, endpoint, status and latency.
+    async fn send(
+        &self,
+        mut request: http::Request<TransportBody>,
+    ) -> Result<http::Response<bytes::Bytes>, TransportError> {
+        self.hooks.request(&mut request);
+
+        #[cfg(feature = "tracing")]
+        let (span, started) = (
+            tracing::debug_span!(
+                "prusa_link_request",
+                method = %request.method(),
+                endpoint = %request.uri().path(),
+            ),
+            Instant::now(),
+        );
+
+        if let Some(circuit_breaker) = &self.circuit_breaker {
+            circuit_breaker
+                .check(&mut self.health.lock().unwrap())
+                .map_err(|err| TransportError::new(TransportErrorKind::CircuitOpen, err))?;
+        }
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+
+        let (method, path) = (request.method().clone(), request.uri().path().to_string());
+        let sent_at = Instant::now();
+
+        let result = self.transport.send(request).await;
+
+        self.stats.lock().unwrap().record(
+            &method,
+            &path,
+            sent_at.elapsed(),
+            result.as_ref().ok().map(|res| res.status()),
+        );
+
+        match &result {
+            Ok(res) => {
+                self.health.lock().unwrap().record_success();
+                self.hooks.response(res);
+            }
+            Err(err) => self.health.lock().unwrap().record_failure(err.to_string()),
+        }
+
+        #[cfg(feature = "tracing")]
+        span.in_scope(|| {
+            let latency_ms = started.elapsed().as_millis() as u64;
+
+            match &result {
+                Ok(res) => tracing::debug!(status = res.status().as_u16(), latency_ms, "response"),
+                Err(err) => tracing::warn!(error = %err, latency_ms, "request failed"),
+            }
+        });
+
+        result
+    }
+}
+
+/// Percent-encodes the characters a uri can't contain, like the spaces in file names.
+/// Already encoded paths are left as they are, since `%` is kept.
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => encoded.push(byte as char),
+            b'-' | b'.' | b'_' | b'~' | b'/' | b'%' | b'?' | b'=' | b'&' | b':' | b'@' | b'+'
+            | b',' | b';' | b'!' | b'$' | b'\'' | b'(' | b')' | b'*' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
 }
@@ -0,0 +1,97 @@
+//! Reading the log files of PrusaLink through `/api/logs`, to find out remotely why a print failed.
+//!
+//! Only PrusaLink on a Raspberry Pi keeps log files, the firmware of the MK4, XL and MINI
+//! doesn't have the endpoint.
+
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::PrinterError, path::PrinterPath, Printer};
+
+/// A log file as listed by `/api/logs`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogFile {
+    /// The name of the file, pass it to `Printer::get_log()` to read it
+    pub name: String,
+
+    pub size: Option<u64>,
+
+    /// Modification time as a unix timestamp
+    pub date: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RawLogs {
+    #[serde(default)]
+    files: Vec<LogFile>,
+}
+
+impl Printer {
+    /// Returns the log files PrusaLink keeps, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::Unsupported` if the printer doesn't have `/api/logs`,
+    /// and an Err if it responds with another error status or a response that can't be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// for log in printer.get_logs().await.unwrap() {
+    ///     let text = printer.get_log(&log.name).await.unwrap();
+    ///     println!("{}: {} lines", log.name, text.lines().count());
+    /// }
+    /// # })
+    /// ```
+    pub async fn get_logs(&self) -> Result<Vec<LogFile>, Box<dyn Error>> {
+        let body = self.fetch_logs("/api/logs", "the log list").await?;
+        let mut files = serde_json::from_slice::<RawLogs>(&body)?.files;
+        files.sort_by_key(|file| std::cmp::Reverse(file.date));
+
+        Ok(files)
+    }
+
+    /// Returns the content of a log file listed by `get_logs()`, invalid UTF-8 is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::InvalidPath` if `name` isn't a valid file name,
+    /// a `PrinterError::Unsupported` if the printer doesn't have `/api/logs`,
+    /// and an Err if it responds with another error status.
+    pub async fn get_log(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        let path = PrinterPath::new(name)?;
+        let body = self
+            .fetch_logs(&format!("/api/logs/{}", path.url_encoded()), name)
+            .await?;
+
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Requests `url` below `/api/logs`, `what` is used in the error message
+    async fn fetch_logs(&self, url: &str, what: &str) -> Result<bytes::Bytes, Box<dyn Error>> {
+        let res = self.send(self.request(http::Method::GET, url)).await?;
+
+        // Without logs support the whole endpoint is missing, a missing file is only a missing file
+        if res.status() == http::StatusCode::NOT_FOUND && url == "/api/logs" {
+            return Err(PrinterError::Unsupported("log files".to_string()).into());
+        }
+
+        if !res.status().is_success() {
+            return Err(format!(
+                "Failed to get {}, server responded with {}",
+                what,
+                res.status()
+            )
+            .into());
+        }
+
+        Ok(res.into_body())
+    }
+}
@@ -0,0 +1,106 @@
+//! The filament loaded in the printer, see `Printer::loaded_material()`.
+//!
+//! The printer reports the material as the name selected on its display, like `PLA` or `PETG`,
+//! and ` - ` or `---` if nothing is loaded. The MINI doesn't report it at all.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A filament type, as selected on the display of the printer or set in the slicer
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::material::Material;
+///
+/// assert_eq!(Material::parse("PETG"), Some(Material::Petg));
+/// assert_eq!(Material::parse(" - "), None);
+/// assert_eq!(Material::parse("PA11CF"), Some(Material::Other("PA11CF".to_string())));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Material {
+    Pla,
+    Petg,
+    Asa,
+    Abs,
+    Pc,
+    Pa,
+    Pvb,
+    Hips,
+    Pp,
+    Flex,
+
+    /// A material the printer knows but this crate doesn't, with the name as reported
+    Other(String),
+}
+
+impl Material {
+    /// Parses the material reported by the printer, None if nothing is loaded
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+
+        if name.chars().all(|c| c == '-') {
+            return None;
+        }
+
+        Some(Self::from(name.to_string()))
+    }
+
+    /// Returns the name of the material the way the printer reports it, like `PETG`
+    pub fn name(&self) -> &str {
+        match self {
+            Material::Pla => "PLA",
+            Material::Petg => "PETG",
+            Material::Asa => "ASA",
+            Material::Abs => "ABS",
+            Material::Pc => "PC",
+            Material::Pa => "PA",
+            Material::Pvb => "PVB",
+            Material::Hips => "HIPS",
+            Material::Pp => "PP",
+            Material::Flex => "FLEX",
+            Material::Other(name) => name,
+        }
+    }
+}
+
+impl From<String> for Material {
+    /// Matches the name case insensitively, unknown names become `Material::Other`
+    fn from(name: String) -> Self {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "PLA" => Material::Pla,
+            "PETG" | "PET" => Material::Petg,
+            "ASA" => Material::Asa,
+            "ABS" => Material::Abs,
+            "PC" => Material::Pc,
+            "PA" | "NYLON" => Material::Pa,
+            "PVB" => Material::Pvb,
+            "HIPS" => Material::Hips,
+            "PP" => Material::Pp,
+            "FLEX" | "TPU" => Material::Flex,
+            _ => Material::Other(name.trim().to_string()),
+        }
+    }
+}
+
+impl From<Material> for String {
+    fn from(material: Material) -> Self {
+        material.name().to_string()
+    }
+}
+
+impl FromStr for Material {
+    type Err = std::convert::Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(name.to_string()))
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
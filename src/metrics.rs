@@ -0,0 +1,218 @@
+//! Prometheus metrics of printers, enabled with the `metrics` feature.
+//!
+//! The state of a `Farm` is written in the Prometheus text exposition format,
+//! with the name of every printer as the `printer` label:
+//!
+//! ```text
+//! prusalink_up{printer="mk4"} 1
+//! prusalink_printer_state{printer="mk4",state="PRINTING"} 1
+//! prusalink_tool_temperature_celsius{printer="mk4",tool="0"} 215.2
+//! prusalink_job_progress_percent{printer="mk4"} 42
+//! ```
+//!
+//! The metrics come from the last refresh, so refresh the farm before or between scrapes.
+
+use std::fmt::Write;
+
+use crate::farm::{Farm, PrinterSnapshot};
+
+/// The content type of the text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// A metric with every sample of it, so the help and type lines are only written once
+struct Gauge {
+    name: &'static str,
+    help: &'static str,
+    samples: Vec<(String, f64)>,
+}
+
+impl Gauge {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            samples: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, labels: &[(&str, &str)], value: f64) {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape_label(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        self.samples.push((labels, value));
+    }
+
+    fn write(&self, out: &mut String) {
+        if self.samples.is_empty() {
+            return;
+        }
+
+        // Writing to a String can't fail
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} gauge", self.name);
+
+        for (labels, value) in &self.samples {
+            let _ = writeln!(out, "{}{{{}}} {}", self.name, labels, value);
+        }
+    }
+}
+
+/// The metrics read from the snapshots of the printers
+struct SnapshotGauges {
+    state: Gauge,
+    tool_temp: Gauge,
+    tool_target: Gauge,
+    bed_temp: Gauge,
+    bed_target: Gauge,
+    progress: Gauge,
+    time_remaining: Gauge,
+    time_printing: Gauge,
+}
+
+impl SnapshotGauges {
+    fn new() -> Self {
+        Self {
+            state: Gauge::new(
+                "prusalink_printer_state",
+                "The state of the printer, 1 for the current state",
+            ),
+            tool_temp: Gauge::new(
+                "prusalink_tool_temperature_celsius",
+                "The temperature of the nozzle of the tool",
+            ),
+            tool_target: Gauge::new(
+                "prusalink_tool_target_temperature_celsius",
+                "The target temperature of the nozzle of the tool",
+            ),
+            bed_temp: Gauge::new(
+                "prusalink_bed_temperature_celsius",
+                "The temperature of the bed",
+            ),
+            bed_target: Gauge::new(
+                "prusalink_bed_target_temperature_celsius",
+                "The target temperature of the bed",
+            ),
+            progress: Gauge::new(
+                "prusalink_job_progress_percent",
+                "The progress of the current job",
+            ),
+            time_remaining: Gauge::new(
+                "prusalink_job_time_remaining_seconds",
+                "The estimated time until the current job ends",
+            ),
+            time_printing: Gauge::new(
+                "prusalink_job_time_printing_seconds",
+                "The time the current job has been printing",
+            ),
+        }
+    }
+
+    fn add(&mut self, name: &str, snapshot: &PrinterSnapshot) {
+        let printer = &snapshot.printer;
+        let labels = [("printer", name)];
+
+        self.state.add(
+            &[("printer", name), ("state", printer.link_state().as_str())],
+            1.0,
+        );
+
+        for (index, tool) in printer.temperature().tools.iter().enumerate() {
+            let index = index.to_string();
+            let labels = [("printer", name), ("tool", index.as_str())];
+
+            self.tool_temp.add(&labels, tool.actual.into());
+            self.tool_target.add(&labels, tool.target.into());
+        }
+
+        self.bed_temp.add(&labels, printer.get_bed_temp().into());
+        self.bed_target
+            .add(&labels, printer.get_target_bed_temp().into());
+
+        if let Some(job) = &snapshot.job {
+            self.progress.add(&labels, job.get_progress().into());
+            self.time_printing
+                .add(&labels, job.get_time_printing().as_secs_f64());
+
+            if let Some(remaining) = job.get_time_remaining() {
+                self.time_remaining.add(&labels, remaining.as_secs_f64());
+            }
+        }
+    }
+
+    fn write(&self, out: &mut String) {
+        for gauge in [
+            &self.state,
+            &self.tool_temp,
+            &self.tool_target,
+            &self.bed_temp,
+            &self.bed_target,
+            &self.progress,
+            &self.time_remaining,
+            &self.time_printing,
+        ] {
+            gauge.write(out);
+        }
+    }
+}
+
+/// Writes the metrics of the given snapshots, keyed by the name used as the `printer` label
+pub fn encode_snapshots<'a, I>(snapshots: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, &'a PrinterSnapshot)>,
+{
+    let mut gauges = SnapshotGauges::new();
+    for (name, snapshot) in snapshots {
+        gauges.add(name, snapshot);
+    }
+
+    let mut out = String::new();
+    gauges.write(&mut out);
+
+    out
+}
+
+/// Writes the metrics of every printer of the farm.
+///
+/// Besides the metrics of the snapshots, `prusalink_up` is 1 for every printer that answered its last request,
+/// and 0 for the others.
+pub fn encode_farm(farm: &Farm) -> String {
+    let mut up = Gauge::new(
+        "prusalink_up",
+        "Whether the printer answered its last request",
+    );
+    for (name, printer) in farm.iter() {
+        let healthy = printer.health().is_healthy();
+
+        up.add(&[("printer", name)], if healthy { 1.0 } else { 0.0 });
+    }
+
+    let mut out = String::new();
+    up.write(&mut out);
+
+    let snapshots = farm.snapshots();
+    out.push_str(&encode_snapshots(
+        snapshots.iter().map(|(name, snapshot)| (*name, snapshot)),
+    ));
+
+    out
+}
+
+/// Returns the metrics of the farm as a response for a scrape,
+/// which servers built on the `http` crate can send as it is
+pub fn farm_response(farm: &Farm) -> http::Response<String> {
+    http::Response::builder()
+        .header(http::header::CONTENT_TYPE, CONTENT_TYPE)
+        .body(encode_farm(farm))
+        .expect("the response is always valid")
+}
+
+/// Escapes a label value as the exposition format requires
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
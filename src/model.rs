@@ -0,0 +1,183 @@
+//! Detecting the model and firmware of a printer, see `Printer::identity()`.
+//!
+//! PrusaLink doesn't report the model directly, so it is guessed from the names the printer reports:
+//! the `original` field of the Raspberry Pi version of PrusaLink, the hostname, which defaults to
+//! something like `prusa-mk4`, and the name given to the printer.
+
+use std::fmt;
+
+/// The version type of `PrinterIdentity`, re-exported from the `semver` crate
+pub use semver::Version;
+
+use crate::{capabilities::RawVersion, raw_info::RawInfo};
+
+/// How a printer prints, which decides what it reports: nozzles and a bed for `Fdm`,
+/// exposure times and resin for `Sla`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrinterKind {
+    /// A printer extruding filament, every model except the SL1
+    Fdm,
+
+    /// A resin printer, the SL1 and SL1S
+    Sla,
+}
+
+/// The kinds of printers PrusaLink runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrinterModel {
+    /// A MK3, MK3S or MK3S+ with PrusaLink running on a Raspberry Pi
+    Mk3RaspberryPi,
+    Mk35,
+    Mk39,
+    Mk4,
+    Mini,
+    Xl,
+    CoreOne,
+    Sl1,
+
+    /// The printer didn't report anything the model could be detected from
+    Unknown,
+}
+
+impl PrinterModel {
+    /// Guesses the model from the names in the version and the info of the printer
+    pub fn detect(version: Option<&RawVersion>, info: Option<&RawInfo>) -> Self {
+        let names = [
+            version.and_then(|version| version.original.as_deref()),
+            version.and_then(|version| version.text.as_deref()),
+            version.and_then(|version| version.hostname.as_deref()),
+            info.and_then(|info| info.hostname.as_deref()),
+            info.and_then(|info| info.name.as_deref()),
+        ];
+
+        names
+            .into_iter()
+            .flatten()
+            .map(Self::from_name)
+            .find(|model| *model != PrinterModel::Unknown)
+            .unwrap_or(PrinterModel::Unknown)
+    }
+
+    /// Guesses the model from a single name like `prusa-mk3.9` or `PrusaLink I3MK3S`
+    pub(crate) fn from_name(name: &str) -> Self {
+        let name = name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        // The longer names go first, `mk39` also contains `mk3`
+        [
+            ("coreone", PrinterModel::CoreOne),
+            ("mk39", PrinterModel::Mk39),
+            ("mk35", PrinterModel::Mk35),
+            ("mk4", PrinterModel::Mk4),
+            ("mk3", PrinterModel::Mk3RaspberryPi),
+            ("mini", PrinterModel::Mini),
+            ("sl1", PrinterModel::Sl1),
+            ("xl", PrinterModel::Xl),
+        ]
+        .into_iter()
+        .find(|(pattern, _)| name.contains(pattern))
+        .map_or(PrinterModel::Unknown, |(_, model)| model)
+    }
+
+    /// Returns how the printer prints, printers that weren't detected are treated as `PrinterKind::Fdm`
+    pub fn kind(self) -> PrinterKind {
+        match self {
+            PrinterModel::Sl1 => PrinterKind::Sla,
+            _ => PrinterKind::Fdm,
+        }
+    }
+
+    /// Returns true if the printer can have more than one tool
+    pub fn is_multi_tool(self) -> bool {
+        self == PrinterModel::Xl
+    }
+
+    /// Returns true if the printer can print binary g-code (`.bgcode`) files
+    pub fn supports_bgcode(self) -> bool {
+        matches!(
+            self,
+            PrinterModel::Mk35
+                | PrinterModel::Mk39
+                | PrinterModel::Mk4
+                | PrinterModel::Mini
+                | PrinterModel::Xl
+                | PrinterModel::CoreOne
+        )
+    }
+}
+
+impl fmt::Display for PrinterModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PrinterModel::Mk3RaspberryPi => "MK3",
+            PrinterModel::Mk35 => "MK3.5",
+            PrinterModel::Mk39 => "MK3.9",
+            PrinterModel::Mk4 => "MK4",
+            PrinterModel::Mini => "MINI",
+            PrinterModel::Xl => "XL",
+            PrinterModel::CoreOne => "CORE One",
+            PrinterModel::Sl1 => "SL1",
+            PrinterModel::Unknown => "unknown",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The model of a printer and the versions of its software
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrinterIdentity {
+    pub model: PrinterModel,
+
+    /// Version of the printer firmware, None if the printer didn't report it
+    pub firmware: Option<Version>,
+
+    /// Version of PrusaLink, None if the printer didn't report it
+    pub prusa_link: Option<Version>,
+}
+
+impl PrinterIdentity {
+    pub fn detect(version: Option<&RawVersion>, info: Option<&RawInfo>) -> Self {
+        Self {
+            model: PrinterModel::detect(version, info),
+            firmware: version
+                .and_then(|version| version.firmware.as_deref())
+                .and_then(parse_version),
+            prusa_link: version
+                .and_then(|version| version.server.as_deref())
+                .and_then(parse_version),
+        }
+    }
+}
+
+/// Parses the numeric part of a version like `6.1.3+8447` or `3.11.0-4955`, a missing minor or patch
+/// version counts as 0.
+///
+/// Firmware puts build numbers where semver expects pre-release versions,
+/// so anything after the numbers is left out to keep comparisons meaningful.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::model::{parse_version, Version};
+///
+/// assert_eq!(parse_version("3.11.0-4955"), Some(Version::new(3, 11, 0)));
+/// assert_eq!(parse_version("2.1"), Some(Version::new(2, 1, 0)));
+/// assert_eq!(parse_version("unknown"), None);
+/// ```
+pub fn parse_version(text: &str) -> Option<Version> {
+    let numeric = text
+        .trim()
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .next()?;
+
+    let mut parts = numeric.split('.').map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().transpose().ok()?.unwrap_or(0);
+    let patch = parts.next().transpose().ok()?.unwrap_or(0);
+
+    Some(Version::new(major, minor, patch))
+}
@@ -0,0 +1,395 @@
+//! Publishing the state of a printer to an MQTT broker, enabled with the `mqtt` feature.
+//!
+//! A `MqttBridge` refreshes the printer on an interval and publishes under `<prefix>/<printer>`:
+//!
+//! - `availability`, `online` or `offline` depending on whether the last refresh worked,
+//!   also sent by the broker as the last will when the bridge disconnects
+//! - `state`, the link state of the printer like `PRINTING`
+//! - `telemetry`, a JSON object with the temperatures and the progress of the current job
+//! - `events`, a JSON object for every `PrinterEvent`, like `{"event": "print_started"}`
+//!
+//! Everything but the events is retained, so new subscribers get the last state right away.
+//! The bridge listens on `command` as well, where `pause`, `resume` or `stop` act on the current job.
+//! Commands that fail are reported on `events` as `{"event": "command_failed", ...}`.
+//!
+//! With `home_assistant()` the bridge also publishes the discovery payloads of `home_assistant::Discovery`,
+//! so the printer shows up in Home Assistant without any configuration.
+
+use std::{error::Error, time::Duration};
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::json;
+
+use crate::{
+    events::{diff_events, Heater, PrinterEvent},
+    farm::{refresh_printer, PrinterSnapshot},
+    home_assistant::{object_id_of, Discovery, EntityTopics},
+    raw_info::RawInfo,
+    time::Ticker,
+    Printer,
+};
+
+/// The topic prefix used by default
+pub const DEFAULT_TOPIC_PREFIX: &str = "prusalink";
+
+/// How long to wait before polling the connection again after it failed
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// A message to publish, as produced by the bridge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: String,
+    pub retain: bool,
+}
+
+/// Publishes the state of one printer to an MQTT broker.
+///
+/// # Example
+///
+/// ```no_run
+/// # use prusa_link_rs::{mqtt::MqttBridge, PrinterBuilder};
+/// # use rumqttc::MqttOptions;
+/// # use std::time::Duration;
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// let mut printer = PrinterBuilder::new("192.168.1.50", "api_key").build();
+///
+/// let bridge = MqttBridge::new("mk4").home_assistant("homeassistant");
+/// let options = MqttOptions::new("prusalink-mk4", "broker.local", 1883);
+///
+/// bridge
+///     .run(&mut printer, options, Duration::from_secs(5))
+///     .await
+///     .unwrap();
+/// # })
+/// ```
+#[derive(Debug, Clone)]
+pub struct MqttBridge {
+    name: String,
+    topic_prefix: String,
+    discovery_prefix: Option<String>,
+    qos: QoS,
+}
+
+impl MqttBridge {
+    /// Creates a bridge for the printer with the given name, which is used in the topics
+    /// with everything but letters, digits, `-` and `_` replaced by `_`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            topic_prefix: DEFAULT_TOPIC_PREFIX.to_string(),
+            discovery_prefix: None,
+            qos: QoS::AtLeastOnce,
+        }
+    }
+
+    /// Use this function to publish under a different prefix than `DEFAULT_TOPIC_PREFIX`
+    pub fn topic_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.topic_prefix = prefix.into();
+        self
+    }
+
+    /// Publishes Home Assistant discovery payloads under the given discovery prefix,
+    /// usually `home_assistant::DEFAULT_DISCOVERY_PREFIX`
+    pub fn home_assistant(mut self, discovery_prefix: impl Into<String>) -> Self {
+        self.discovery_prefix = Some(discovery_prefix.into());
+        self
+    }
+
+    /// Use this function to publish with a different QoS than `AtLeastOnce`
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Returns the topic every other topic of the printer is under
+    pub fn base_topic(&self) -> String {
+        format!("{}/{}", self.topic_prefix, object_id_of(&self.name))
+    }
+
+    pub fn availability_topic(&self) -> String {
+        format!("{}/availability", self.base_topic())
+    }
+
+    pub fn state_topic(&self) -> String {
+        format!("{}/state", self.base_topic())
+    }
+
+    pub fn telemetry_topic(&self) -> String {
+        format!("{}/telemetry", self.base_topic())
+    }
+
+    pub fn events_topic(&self) -> String {
+        format!("{}/events", self.base_topic())
+    }
+
+    pub fn command_topic(&self) -> String {
+        format!("{}/command", self.base_topic())
+    }
+
+    /// Returns the last will marking the printer as offline, `run()` sets it on the options
+    pub fn last_will(&self) -> LastWill {
+        LastWill::new(self.availability_topic(), "offline", self.qos, true)
+    }
+
+    /// Returns the messages with the availability, state and telemetry of the snapshot
+    pub fn status_messages(&self, snapshot: &PrinterSnapshot) -> Vec<MqttMessage> {
+        let printer = &snapshot.printer;
+        let job = snapshot.job.as_ref();
+
+        let telemetry = json!({
+            "nozzle_temp": printer.get_nozzle_temp(),
+            "target_nozzle_temp": printer.get_target_nozzle_temp(),
+            "bed_temp": printer.get_bed_temp(),
+            "target_bed_temp": printer.get_target_bed_temp(),
+            "progress": job.map(|job| job.get_progress()),
+            "time_remaining": job.and_then(|job| job.get_time_remaining()).map(|time| time.as_secs()),
+            "time_printing": job.map(|job| job.get_time_printing().as_secs()),
+        });
+
+        vec![
+            self.availability_message(true),
+            retained(
+                self.state_topic(),
+                printer.link_state().as_str().to_string(),
+            ),
+            retained(self.telemetry_topic(), telemetry.to_string()),
+        ]
+    }
+
+    /// Returns the message reporting the printer as online or offline
+    pub fn availability_message(&self, online: bool) -> MqttMessage {
+        let payload = if online { "online" } else { "offline" };
+
+        retained(self.availability_topic(), payload.to_string())
+    }
+
+    /// Returns the message for an event, published on the events topic without being retained
+    pub fn event_message(&self, event: &PrinterEvent) -> MqttMessage {
+        let payload = match event {
+            PrinterEvent::StateChanged { from, to } => json!({
+                "event": "state_changed",
+                "from": from.as_str(),
+                "to": to.as_str(),
+            }),
+            PrinterEvent::PrintStarted => json!({ "event": "print_started" }),
+            PrinterEvent::PrintFinished => json!({ "event": "print_finished" }),
+            PrinterEvent::PrintFailed => json!({ "event": "print_failed" }),
+            PrinterEvent::TemperatureReached {
+                heater,
+                temperature,
+            } => json!({
+                "event": "temperature_reached",
+                "heater": match heater {
+                    Heater::Nozzle => "nozzle",
+                    Heater::Bed => "bed",
+                },
+                "temperature": temperature,
+            }),
+            PrinterEvent::RefreshFailed(error) => json!({
+                "event": "refresh_failed",
+                "error": error,
+            }),
+        };
+
+        MqttMessage {
+            topic: self.events_topic(),
+            payload: payload.to_string(),
+            retain: false,
+        }
+    }
+
+    /// Returns the Home Assistant discovery messages of the printer, named after the info if it is given,
+    /// empty unless `home_assistant()` was used
+    pub fn discovery_messages(&self, info: Option<&RawInfo>) -> Vec<MqttMessage> {
+        let Some(discovery_prefix) = &self.discovery_prefix else {
+            return Vec::new();
+        };
+
+        let topics = EntityTopics::under(&self.base_topic());
+        let discovery = match info {
+            Some(info) => Discovery::from_info(&self.name, info, topics),
+            None => Discovery::new(&self.name, topics).name(&self.name),
+        };
+
+        discovery
+            .discovery_prefix(discovery_prefix)
+            .entities()
+            .into_iter()
+            .map(|entity| retained(entity.topic, entity.config.to_string()))
+            .collect()
+    }
+
+    /// Publishes the messages with the QoS of the bridge
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event loop of the client is gone.
+    pub async fn publish(
+        &self,
+        client: &AsyncClient,
+        messages: impl IntoIterator<Item = MqttMessage>,
+    ) -> Result<(), rumqttc::ClientError> {
+        for message in messages {
+            client
+                .publish(message.topic, self.qos, message.retain, message.payload)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Connects to the broker and publishes the state of the printer every `interval`, until publishing fails.
+    ///
+    /// The printer and its job are refreshed every interval, the state and the telemetry are published
+    /// after every successful refresh along with the events since the previous one. A failed refresh marks
+    /// the printer as offline. The connection to the broker is made again whenever it drops.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a message can't be handed to the client, errors of the printer are published instead.
+    pub async fn run(
+        &self,
+        printer: &mut Printer,
+        mut options: MqttOptions,
+        interval: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        options.set_last_will(self.last_will());
+
+        let (client, mut eventloop) = AsyncClient::new(options, 32);
+        let (commands_tx, mut commands) = tokio::sync::mpsc::unbounded_channel();
+
+        let subscriber = client.clone();
+        let (command_topic, qos) = (self.command_topic(), self.qos);
+
+        let connection = tokio::spawn(async move {
+            loop {
+                match eventloop.poll().await {
+                    // The session starts clean, so the subscription is made again on every connection
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        let _ = subscriber.try_subscribe(&command_topic, qos);
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish)))
+                        if publish.topic == command_topic =>
+                    {
+                        let _ = commands_tx
+                            .send(String::from_utf8_lossy(&publish.payload).into_owned());
+                    }
+                    Ok(_) => {}
+                    Err(_err) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(error = %_err, "mqtt connection failed");
+
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        });
+
+        let result = self
+            .publish_loop(&client, printer, interval, &mut commands)
+            .await;
+        connection.abort();
+
+        result
+    }
+
+    async fn publish_loop(
+        &self,
+        client: &AsyncClient,
+        printer: &mut Printer,
+        interval: Duration,
+        commands: &mut tokio::sync::mpsc::UnboundedReceiver<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.discovery_prefix.is_some() {
+            let info = printer.get_info().await.ok();
+
+            self.publish(client, self.discovery_messages(info.as_ref()))
+                .await?;
+        }
+
+        let mut ticker = Ticker::new(interval);
+        let mut previous = None::<PrinterSnapshot>;
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                Some(command) = commands.recv() => {
+                    if let Err(err) = run_command(printer, command.trim()).await {
+                        self.publish(client, [self.command_failed_message(command.trim(), &*err)])
+                            .await?;
+                    }
+
+                    continue;
+                }
+            }
+
+            let current = match refresh_printer(printer).await {
+                Ok(()) => printer.printer.clone().map(|raw| PrinterSnapshot {
+                    printer: raw,
+                    job: printer.job.clone(),
+                }),
+                Err(err) => {
+                    let failed = PrinterEvent::RefreshFailed(err.to_string());
+                    let messages = [
+                        self.availability_message(false),
+                        self.event_message(&failed),
+                    ];
+
+                    self.publish(client, messages).await?;
+                    continue;
+                }
+            };
+
+            let Some(current) = current else {
+                continue;
+            };
+
+            let mut messages = self.status_messages(&current);
+            if let Some(previous) = &previous {
+                let events = diff_events(&previous.printer, &current.printer);
+                messages.extend(events.iter().map(|event| self.event_message(event)));
+            }
+
+            self.publish(client, messages).await?;
+            previous = Some(current);
+        }
+    }
+
+    fn command_failed_message(&self, command: &str, err: &dyn Error) -> MqttMessage {
+        let payload = json!({
+            "event": "command_failed",
+            "command": command,
+            "error": err.to_string(),
+        });
+
+        MqttMessage {
+            topic: self.events_topic(),
+            payload: payload.to_string(),
+            retain: false,
+        }
+    }
+}
+
+/// Runs a command received on the command topic on the current job
+async fn run_command(printer: &Printer, command: &str) -> Result<(), Box<dyn Error>> {
+    let Some(job) = printer.get_job_info().await? else {
+        return Err("there is no job".into());
+    };
+
+    match command {
+        "pause" => printer.pause_job(job.get_id()).await,
+        "resume" => printer.resume_job(job.get_id()).await,
+        "stop" => printer.stop_job(job.get_id()).await,
+        _ => Err(format!("unknown command {}", command).into()),
+    }
+}
+
+fn retained(topic: String, payload: String) -> MqttMessage {
+    MqttMessage {
+        topic,
+        payload,
+        retain: true,
+    }
+}
@@ -0,0 +1,145 @@
+//! Paths of files on a storage of the printer, checked before they are sent.
+//!
+//! The storages are FAT formatted, and the firmware answers paths with characters FAT doesn't allow
+//! with a bare `400 Bad Request` or `404 Not Found`. `PrinterPath` rejects them with a message
+//! naming the character instead, and percent-encodes the rest so names with spaces or
+//! unicode characters reach the printer as they are.
+
+use std::{fmt, str::FromStr};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::{error::PrinterError, storage::Storage};
+
+/// Characters that are encoded in a segment of the url, everything but the unreserved characters
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Characters FAT doesn't allow in names, control characters are rejected as well
+const FORBIDDEN: &[char] = &['"', '*', ':', '<', '>', '?', '|'];
+
+/// A validated path of a file or folder on a storage, relative to the root of the storage.
+///
+/// Both `/` and `\` separate folders, and leading, trailing and repeated separators are dropped,
+/// so `\projects\\benchy.gcode` and `projects/benchy.gcode` are the same path.
+/// An empty path is the root of the storage.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::path::PrinterPath;
+///
+/// let path = PrinterPath::new(r"\projects\My Benchy ü.gcode").unwrap();
+///
+/// assert_eq!(path.as_str(), "projects/My Benchy ü.gcode");
+/// assert_eq!(path.url_encoded(), "projects/My%20Benchy%20%C3%BC.gcode");
+/// assert!(PrinterPath::new("benchy?.gcode").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PrinterPath {
+    path: String,
+}
+
+impl PrinterPath {
+    /// Normalizes and validates a path.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::InvalidPath` if a name contains a character FAT doesn't allow,
+    /// or if a name is `.` or `..`.
+    pub fn new(path: &str) -> Result<Self, PrinterError> {
+        let mut segments = Vec::new();
+
+        for segment in path
+            .split(['/', '\\'])
+            .filter(|segment| !segment.is_empty())
+        {
+            if segment == "." || segment == ".." {
+                return Err(PrinterError::InvalidPath(format!(
+                    "{} can't contain {}",
+                    path, segment
+                )));
+            }
+
+            if let Some(char) = segment
+                .chars()
+                .find(|char| char.is_control() || FORBIDDEN.contains(char))
+            {
+                return Err(PrinterError::InvalidPath(format!(
+                    "{} contains {:?}, which the printer doesn't accept",
+                    path, char
+                )));
+            }
+
+            segments.push(segment);
+        }
+
+        Ok(Self {
+            path: segments.join("/"),
+        })
+    }
+
+    /// Returns the path with `/` between the names, without a leading slash
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns true for the root of the storage
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Returns the last name of the path, None for the root
+    pub fn file_name(&self) -> Option<&str> {
+        self.path.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+
+    /// Returns the path with every name percent-encoded, ready to be put into a url
+    pub fn url_encoded(&self) -> String {
+        self.path
+            .split('/')
+            .map(|segment| utf8_percent_encode(segment, SEGMENT).to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl fmt::Display for PrinterPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)
+    }
+}
+
+impl FromStr for PrinterPath {
+    type Err = PrinterError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::new(path)
+    }
+}
+
+impl TryFrom<&str> for PrinterPath {
+    type Error = PrinterError;
+
+    fn try_from(path: &str) -> Result<Self, Self::Error> {
+        Self::new(path)
+    }
+}
+
+impl AsRef<str> for PrinterPath {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+/// Returns the url of a file or folder on a storage, `/api/v1/files/{storage}/{path}`
+pub(crate) fn files_url(storage: &Storage, path: &str) -> Result<String, PrinterError> {
+    Ok(format!(
+        "/api/v1/files/{}/{}",
+        storage,
+        PrinterPath::new(path)?.url_encoded()
+    ))
+}
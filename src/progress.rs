@@ -0,0 +1,60 @@
+//! The progress of a print, put together from the job, the status and the telemetry of the printer.
+
+use std::time::Duration;
+
+use crate::{raw_job::RawJob, raw_printer::RawPrinter, raw_status::RawStatus, time::SystemTime};
+
+/// How far the current print is, as returned by `Printer::progress()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintProgress {
+    /// Progress in percent, from 0 to 100
+    pub percent: f32,
+
+    /// The height of the nozzle in millimeters, which is the height of the layer being printed.
+    /// None if neither the status nor the telemetry report it.
+    pub current_layer_height: Option<f32>,
+
+    pub time_elapsed: Duration,
+
+    /// None while the printer has no estimate yet
+    pub time_remaining: Option<Duration>,
+
+    /// When the print is estimated to end, from the time remaining as of the last refresh
+    pub eta: Option<SystemTime>,
+}
+
+impl PrintProgress {
+    /// Puts the progress together, preferring the status over the job since it is refreshed more often
+    /// while printing
+    pub(crate) fn new(
+        job: &RawJob,
+        status: Option<&RawStatus>,
+        printer: Option<&RawPrinter>,
+    ) -> Self {
+        let status_job = status
+            .and_then(RawStatus::job)
+            .filter(|status_job| status_job.id == job.get_id().0);
+
+        let percent = status_job
+            .and_then(|status_job| status_job.progress)
+            .unwrap_or(job.get_progress());
+        let time_elapsed = status_job
+            .and_then(|status_job| status_job.time_printing)
+            .unwrap_or(job.get_time_printing());
+        let time_remaining = status_job
+            .and_then(|status_job| status_job.time_remaining)
+            .or(job.get_time_remaining());
+
+        let current_layer_height = status
+            .and_then(|status| status.printer().axis_z)
+            .or(printer.and_then(RawPrinter::get_z_height_telemetry));
+
+        Self {
+            percent,
+            current_layer_height,
+            time_elapsed,
+            time_remaining,
+            eta: time_remaining.map(|remaining| SystemTime::now() + remaining),
+        }
+    }
+}
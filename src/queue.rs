@@ -0,0 +1,227 @@
+//! Printing a list of local files one after another, on a printer or across a farm. Not available on wasm
+
+use std::{
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::{farm::Farm, raw_job::JobId, raw_printer::LinkState, storage::Storage, time, Printer};
+
+/// A file waiting to be printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedFile {
+    /// The file on the local filesystem
+    pub local_path: PathBuf,
+
+    /// The storage the file is uploaded to, for example `usb`
+    pub storage: Storage,
+
+    /// The path the file is uploaded to on the storage
+    pub path: String,
+}
+
+/// What happened to a file of the queue
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOutcome {
+    pub file: QueuedFile,
+
+    /// The name of the printer in the farm, or its address when printing on a single printer
+    pub printer: String,
+
+    /// The job of the print, None if the upload failed
+    pub job: Option<JobId>,
+
+    /// The state the print ended in, or why it couldn't be printed
+    pub result: Result<LinkState, String>,
+}
+
+impl PrintOutcome {
+    /// Returns true if the print finished
+    pub fn succeeded(&self) -> bool {
+        self.result == Ok(LinkState::Finished)
+    }
+}
+
+/// Uploads and prints local files one at a time, waiting for each print to end before starting the next.
+///
+/// A file that can't be uploaded or whose print doesn't finish is recorded as failed,
+/// and the queue continues with the next file.
+///
+/// # Example
+///
+/// ```no_run
+/// # use prusa_link_rs::{queue::PrintQueue, PrinterBuilder};
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// let mut printer = PrinterBuilder::new("192.168.1.50", "api_key").build();
+///
+/// let mut queue = PrintQueue::new("usb");
+/// queue.push("plates/plate_1.bgcode");
+/// queue.push("plates/plate_2.bgcode");
+///
+/// queue.run(&mut printer).await;
+///
+/// for outcome in queue.outcomes() {
+///     println!("{}: {:?}", outcome.file.path, outcome.result);
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct PrintQueue {
+    storage: Storage,
+    pending: VecDeque<QueuedFile>,
+    outcomes: Vec<PrintOutcome>,
+    poll_interval: Duration,
+    print_timeout: Duration,
+}
+
+impl PrintQueue {
+    /// Creates an empty queue uploading to the given storage
+    pub fn new(storage: impl Into<Storage>) -> Self {
+        Self {
+            storage: storage.into(),
+            pending: VecDeque::new(),
+            outcomes: Vec::new(),
+            poll_interval: Duration::from_secs(10),
+            print_timeout: Duration::MAX,
+        }
+    }
+
+    /// Use this function to change how often the printer is checked while printing, by default every 10 seconds.
+    /// It is checked at most every 100 ms, a shorter or zero interval is raised to that.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval.max(time::MIN_PERIOD);
+        self
+    }
+
+    /// Use this function to give up on prints taking longer than `print_timeout`, by default it waits forever
+    pub fn print_timeout(mut self, print_timeout: Duration) -> Self {
+        self.print_timeout = print_timeout;
+        self
+    }
+
+    /// Adds a file to the end of the queue, uploaded under its file name
+    pub fn push(&mut self, local_path: impl AsRef<Path>) {
+        let local_path = local_path.as_ref();
+        let path = local_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        self.push_as(local_path, path);
+    }
+
+    /// Adds a file to the end of the queue, uploaded to `path` on the storage
+    pub fn push_as(&mut self, local_path: impl AsRef<Path>, path: impl Into<String>) {
+        self.pending.push_back(QueuedFile {
+            local_path: local_path.as_ref().to_path_buf(),
+            storage: self.storage.clone(),
+            path: path.into(),
+        });
+    }
+
+    /// Returns the files that haven't been printed yet, in the order they will be printed
+    pub fn pending(&self) -> impl Iterator<Item = &QueuedFile> {
+        self.pending.iter()
+    }
+
+    /// Returns what happened to the files printed so far, in the order their prints ended
+    pub fn outcomes(&self) -> &[PrintOutcome] {
+        &self.outcomes
+    }
+
+    /// Returns true if no files are waiting
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Prints the next file on the printer, returns None if the queue is empty
+    pub async fn run_next(&mut self, printer: &mut Printer) -> Option<&PrintOutcome> {
+        let file = self.pending.pop_front()?;
+        let name = printer.address().to_string();
+
+        let outcome = self.print(printer, name, file).await;
+        self.outcomes.push(outcome);
+
+        self.outcomes.last()
+    }
+
+    /// Prints every file of the queue on the printer, one after another
+    pub async fn run(&mut self, printer: &mut Printer) {
+        while self.run_next(printer).await.is_some() {}
+    }
+
+    /// Prints the files of the queue across the idle printers of the farm.
+    ///
+    /// Every idle printer prints one file at a time, and takes the next file when its print ends.
+    /// Printers that are busy when this is called are left alone. If no printer is idle,
+    /// the files stay in the queue.
+    pub async fn run_on_farm(&mut self, farm: &mut Farm) {
+        farm.refresh_all().await;
+
+        let idle = farm
+            .idle_printers()
+            .into_iter()
+            .map(String::from)
+            .collect::<HashSet<_>>();
+
+        let pending = Mutex::new(std::mem::take(&mut self.pending));
+        let outcomes = Mutex::new(Vec::new());
+
+        let workers = farm
+            .iter_mut()
+            .filter(|(name, _)| idle.contains(*name))
+            .map(|(name, printer)| {
+                let (this, pending, outcomes) = (&*self, &pending, &outcomes);
+
+                async move {
+                    // The lock is released before the print starts, so the other printers can take files
+                    loop {
+                        let Some(file) = pending.lock().unwrap().pop_front() else {
+                            break;
+                        };
+
+                        let outcome = this.print(printer, name.to_string(), file).await;
+                        outcomes.lock().unwrap().push(outcome);
+                    }
+                }
+            });
+
+        futures::future::join_all(workers).await;
+
+        self.pending = pending.into_inner().unwrap();
+        self.outcomes.extend(outcomes.into_inner().unwrap());
+    }
+
+    /// Uploads and prints the file, and waits for the print to end
+    async fn print(&self, printer: &mut Printer, name: String, file: QueuedFile) -> PrintOutcome {
+        let job = match printer
+            .upload_and_print(&file.storage, &file.path, file.local_path.as_path())
+            .await
+        {
+            Ok(job) => job.get_id(),
+            Err(err) => {
+                return PrintOutcome {
+                    file,
+                    printer: name,
+                    job: None,
+                    result: Err(err.to_string()),
+                }
+            }
+        };
+
+        let result = printer
+            .wait_until_finished(self.poll_interval, self.print_timeout)
+            .await
+            .map_err(|err| err.to_string());
+
+        PrintOutcome {
+            file,
+            printer: name,
+            job: Some(job),
+            result,
+        }
+    }
+}
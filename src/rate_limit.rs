@@ -0,0 +1,87 @@
+//! Limiting how often requests are sent to a printer, set with `PrinterBuilder::rate_limit()`.
+//!
+//! The web server on the Buddy board only handles a few requests at once and slows down the
+//! printer when it is flooded. With a rate limit, requests that would go over it wait for their turn,
+//! in the order they were made.
+
+use std::time::Duration;
+
+use crate::time::{self, Instant};
+
+/// How often requests may be sent to a printer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimit {
+    /// Waits at least the given duration between the start of two requests
+    MinInterval(Duration),
+
+    /// Lets up to `burst` requests through at once, and one more every `refill` after that.
+    /// A `burst` of 0 is treated as 1.
+    TokenBucket { burst: u32, refill: Duration },
+}
+
+impl RateLimit {
+    /// Returns the capacity of the bucket and the time it takes to refill one request
+    fn bucket(&self) -> (f64, Duration) {
+        match *self {
+            RateLimit::MinInterval(interval) => (1.0, interval),
+            RateLimit::TokenBucket { burst, refill } => (burst.max(1).into(), refill),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, capacity: f64, refill: Duration) {
+        let now = Instant::now();
+        let refilled =
+            now.saturating_duration_since(self.updated).as_secs_f64() / refill.as_secs_f64();
+
+        self.tokens = (self.tokens + refilled).min(capacity);
+        self.updated = now;
+    }
+}
+
+/// Holds back requests going over a `RateLimit`, shared by every request of a printer
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    // The tokio mutex is fair, so waiting requests go through in the order they were made
+    bucket: tokio::sync::Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let (capacity, _) = limit.bucket();
+
+        Self {
+            limit,
+            bucket: tokio::sync::Mutex::new(Bucket {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Waits until a request can be sent under the limit
+    pub(crate) async fn acquire(&self) {
+        let (capacity, refill) = self.limit.bucket();
+        if refill.is_zero() {
+            return;
+        }
+
+        let mut bucket = self.bucket.lock().await;
+        bucket.refill(capacity, refill);
+
+        if bucket.tokens < 1.0 {
+            time::sleep(refill.mul_f64(1.0 - bucket.tokens)).await;
+            bucket.refill(capacity, refill);
+        }
+
+        bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Information about the printer as returned by `/api/v1/info`.
+///
+/// This only changes when the printer is reconfigured, so `Printer` fetches it once and keeps it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RawInfo {
+    /// The name given to the printer in its settings
+    pub name: Option<String>,
+
+    pub location: Option<String>,
+
+    pub hostname: Option<String>,
+
+    pub serial: Option<String>,
+
+    /// Nozzle diameter in millimeters
+    pub nozzle_diameter: Option<f32>,
+
+    /// Minimum temperature the printer allows extruding at, in degrees celsius
+    pub min_extrusion_temp: Option<f32>,
+
+    /// True if a multi material unit is connected, not reported by every firmware
+    pub mmu: Option<bool>,
+
+    pub sd_ready: Option<bool>,
+
+    pub farm_mode: Option<bool>,
+
+    /// Fields this version of the crate doesn't know about, kept so newer firmware doesn't break parsing
+    #[serde(flatten, default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
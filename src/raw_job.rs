@@ -0,0 +1,206 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    storage::Storage,
+    time::{self, SystemTime},
+};
+
+/// The id of a job, used to address it in `/api/v1/job/{id}`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct JobId(pub u32);
+
+impl From<u32> for JobId {
+    fn from(id: u32) -> Self {
+        JobId(id)
+    }
+}
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Links to resources belonging to a file on the printer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FileRefs {
+    pub download: Option<String>,
+    pub icon: Option<String>,
+    pub thumbnail: Option<String>,
+}
+
+/// The thumbnail variants the printer provides for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailSize {
+    /// The small icon shown in the file list
+    Small,
+
+    /// The large preview shown before printing
+    Large,
+}
+
+impl FileRefs {
+    /// Returns the path of the thumbnail of the given size, if the file has one
+    pub fn thumbnail_ref(&self, size: ThumbnailSize) -> Option<&str> {
+        match size {
+            ThumbnailSize::Small => self.icon.as_deref(),
+            ThumbnailSize::Large => self.thumbnail.as_deref(),
+        }
+    }
+}
+
+/// The file that is being printed by a job
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct JobFile {
+    /// The short (8.3) name of the file
+    pub name: String,
+
+    /// The long name of the file, as it is shown on the printer
+    pub display_name: Option<String>,
+
+    /// The directory the file is in, including the storage, for example `/usb`
+    pub path: String,
+
+    pub size: Option<u64>,
+
+    /// Modification time as a unix timestamp
+    pub m_timestamp: Option<u64>,
+
+    pub refs: Option<FileRefs>,
+}
+
+impl JobFile {
+    /// Returns true if this is the file at `path` on `storage`, compared by the short
+    /// and the long name since the printer can report either
+    pub fn is_at(&self, storage: &Storage, path: &str) -> bool {
+        let folder = self
+            .path
+            .trim_matches('/')
+            .strip_prefix(storage.as_str())
+            .unwrap_or(&self.path)
+            .trim_matches('/');
+        let path = path.trim_matches('/');
+        let names = [Some(self.name.as_str()), self.display_name.as_deref()];
+
+        names
+            .into_iter()
+            .flatten()
+            .any(|name| crate::files::join(folder, name).eq_ignore_ascii_case(path))
+    }
+}
+
+/// The current job as returned by `/api/v1/job`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RawJob {
+    id: JobId,
+
+    state: String,
+
+    /// Progress of the job in percent
+    progress: f32,
+
+    /// Estimated time remaining, in seconds in the response
+    #[serde(default, with = "time::option_secs")]
+    time_remaining: Option<Duration>,
+
+    /// Time spent printing, in seconds in the response
+    #[serde(with = "time::secs")]
+    time_printing: Duration,
+
+    file: Option<JobFile>,
+
+    /// Exposure time of a layer, in milliseconds in the response, only reported by SLA printers
+    #[serde(
+        default,
+        with = "time::option_millis",
+        skip_serializing_if = "Option::is_none"
+    )]
+    exposure_time: Option<Duration>,
+
+    /// Exposure time of the first layers, in milliseconds in the response, only reported by SLA printers
+    #[serde(
+        default,
+        with = "time::option_millis",
+        skip_serializing_if = "Option::is_none"
+    )]
+    exposure_time_first: Option<Duration>,
+
+    /// Layer height in millimeters, only reported by SLA printers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    layer_height: Option<f32>,
+
+    /// Fields this version of the crate doesn't know about, kept so newer firmware doesn't break parsing
+    #[serde(flatten, default, skip_serializing_if = "HashMap::is_empty")]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+impl RawJob {
+    pub fn get_id(&self) -> JobId {
+        self.id
+    }
+
+    pub fn get_state(&self) -> &str {
+        &self.state
+    }
+
+    /// Returns true if the job hasn't ended yet, meaning it is not `FINISHED`, `STOPPED` or `ERROR`
+    pub fn is_active(&self) -> bool {
+        !matches!(self.state.as_str(), "FINISHED" | "STOPPED" | "ERROR")
+    }
+
+    /// Returns the progress of the job in percent, from 0 to 100
+    pub fn get_progress(&self) -> f32 {
+        self.progress
+    }
+
+    pub fn get_time_remaining(&self) -> Option<Duration> {
+        self.time_remaining
+    }
+
+    pub fn get_time_printing(&self) -> Duration {
+        self.time_printing
+    }
+
+    /// Returns when the job is estimated to end, from the time remaining as of now.
+    ///
+    /// The estimate is only as fresh as the job, so call this right after fetching it.
+    pub fn get_eta(&self) -> Option<SystemTime> {
+        self.time_remaining
+            .map(|remaining| SystemTime::now() + remaining)
+    }
+
+    /// Returns when the job is estimated to end as a `chrono` timestamp, see `get_eta()`
+    #[cfg(feature = "chrono")]
+    pub fn get_eta_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.time_remaining
+            .and_then(|remaining| chrono::Duration::from_std(remaining).ok())
+            .map(|remaining| chrono::Utc::now() + remaining)
+    }
+
+    pub fn get_file(&self) -> Option<&JobFile> {
+        self.file.as_ref()
+    }
+
+    /// Returns how long each layer is exposed, only reported by SLA printers
+    pub fn get_exposure_time(&self) -> Option<Duration> {
+        self.exposure_time
+    }
+
+    /// Returns how long the first layers are exposed to stick to the platform,
+    /// only reported by SLA printers
+    pub fn get_exposure_time_first(&self) -> Option<Duration> {
+        self.exposure_time_first
+    }
+
+    pub fn get_layer_height(&self) -> Option<f32> {
+        self.layer_height
+    }
+
+    /// Returns the fields of the response this version of the crate doesn't know about
+    pub fn extra(&self) -> &HashMap<String, serde_json::Value> {
+        &self.extra
+    }
+}
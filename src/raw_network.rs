@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The network settings of the printer as returned by `/api/v1/network`,
+/// only reported by newer PrusaLink versions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetworkInfo {
+    pub hostname: Option<String>,
+
+    /// The network interfaces of the printer, usually one for ethernet and one for Wi-Fi
+    #[serde(default)]
+    pub interfaces: Vec<NetworkInterface>,
+
+    /// Fields this version of the crate doesn't know about, kept so newer firmware doesn't break parsing
+    #[serde(flatten, default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl NetworkInfo {
+    /// Returns the Wi-Fi interface, if the printer has one
+    pub fn wifi(&self) -> Option<&NetworkInterface> {
+        self.interfaces.iter().find(|interface| interface.is_wifi())
+    }
+
+    /// Returns the interface the printer is connected with, preferring ethernet over Wi-Fi
+    pub fn active(&self) -> Option<&NetworkInterface> {
+        let mut connected = self
+            .interfaces
+            .iter()
+            .filter(|interface| interface.is_connected());
+
+        connected
+            .clone()
+            .find(|interface| !interface.is_wifi())
+            .or_else(|| connected.next())
+    }
+}
+
+/// A network interface of the printer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetworkInterface {
+    /// The name of the interface, for example `wlan0`
+    pub name: String,
+
+    /// `wifi` or `ethernet`
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+
+    pub mac: Option<String>,
+
+    /// The IPv4 address, None if the interface has no address
+    pub ipv4: Option<String>,
+
+    pub ipv6: Option<String>,
+
+    pub netmask: Option<String>,
+
+    pub gateway: Option<String>,
+
+    #[serde(default)]
+    pub dns: Vec<String>,
+
+    /// True if the address was assigned with DHCP, false if it is static
+    pub dhcp: Option<bool>,
+
+    /// The name of the Wi-Fi network, for Wi-Fi interfaces
+    pub ssid: Option<String>,
+
+    /// Signal strength of the Wi-Fi in dBm, for example `-60`
+    pub rssi: Option<i32>,
+
+    /// Fields this version of the crate doesn't know about, kept so newer firmware doesn't break parsing
+    #[serde(flatten, default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl NetworkInterface {
+    pub fn is_wifi(&self) -> bool {
+        self.kind
+            .as_deref()
+            .is_some_and(|kind| kind.eq_ignore_ascii_case("wifi"))
+            || self.ssid.is_some()
+    }
+
+    /// Returns true if the interface has an address
+    pub fn is_connected(&self) -> bool {
+        self.ipv4.is_some() || self.ipv6.is_some()
+    }
+
+    /// Returns the signal strength in percent, from 0 at -100 dBm to 100 at -50 dBm and above,
+    /// or None if the interface doesn't report it
+    pub fn signal_quality(&self) -> Option<u8> {
+        self.rssi
+            .map(|rssi| (2 * (rssi.clamp(-100, -50) + 100)) as u8)
+    }
+}
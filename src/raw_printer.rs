@@ -1,104 +1,408 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
-struct PrinterTemperature {
-    #[serde(rename = "tool0")]
-    nozzle: Temp,
+use crate::material::Material;
+use crate::units::{Celsius, HeaterReading, Temperatures};
+
+/// The state of the printer as reported by PrusaLink in the `link_state` flag
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LinkState {
+    Idle,
+    Busy,
+    Printing,
+    Paused,
+    Finished,
+    Stopped,
+    Error,
+    Attention,
+    Ready,
+
+    /// A state this version of the crate doesn't know about
+    Unknown(String),
+}
+
+impl LinkState {
+    /// Returns the state as it is sent by the printer
+    pub fn as_str(&self) -> &str {
+        match self {
+            LinkState::Idle => "IDLE",
+            LinkState::Busy => "BUSY",
+            LinkState::Printing => "PRINTING",
+            LinkState::Paused => "PAUSED",
+            LinkState::Finished => "FINISHED",
+            LinkState::Stopped => "STOPPED",
+            LinkState::Error => "ERROR",
+            LinkState::Attention => "ATTENTION",
+            LinkState::Ready => "READY",
+            LinkState::Unknown(state) => state,
+        }
+    }
+}
+
+impl From<&str> for LinkState {
+    fn from(state: &str) -> Self {
+        match state {
+            "IDLE" => LinkState::Idle,
+            "BUSY" => LinkState::Busy,
+            "PRINTING" => LinkState::Printing,
+            "PAUSED" => LinkState::Paused,
+            "FINISHED" => LinkState::Finished,
+            "STOPPED" => LinkState::Stopped,
+            "ERROR" => LinkState::Error,
+            "ATTENTION" => LinkState::Attention,
+            "READY" => LinkState::Ready,
+            other => LinkState::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for LinkState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Temperature block of `/api/printer`.
+///
+/// Every tool is reported as `toolN`, most printers only have `tool0`
+/// while the XL reports up to `tool4`. SLA printers have neither tools nor a bed,
+/// they are read with no tools and a bed at 0°C, their temperatures are in the telemetry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(try_from = "WireTemperature", into = "WireTemperature")]
+pub struct PrinterTemperature {
+    /// The temperatures of the tools, ordered by their index
+    pub tools: Vec<Temp>,
+
+    pub bed: Temp,
+
+    /// The temperature inside the enclosure, only reported by enclosed printers like the CORE One
+    pub chamber: Option<Temp>,
+}
+
+impl PrinterTemperature {
+    /// Returns the temperature of the tool with the given index
+    pub fn tool(&self, index: usize) -> Option<&Temp> {
+        self.tools.get(index)
+    }
 
-    bed: Temp,
+    /// Returns the temperature of the first tool, which is the only one on single tool printers
+    pub fn nozzle(&self) -> Option<&Temp> {
+        self.tool(0)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct Temp {
+/// The temperature block as it is sent by the printer, with the tools as separate keys
+#[derive(Serialize, Deserialize)]
+struct WireTemperature {
+    bed: Option<Temp>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chamber: Option<Temp>,
+
+    #[serde(flatten)]
+    others: BTreeMap<String, serde_json::Value>,
+}
+
+impl TryFrom<WireTemperature> for PrinterTemperature {
+    type Error = String;
+
+    fn try_from(wire: WireTemperature) -> Result<Self, Self::Error> {
+        let mut tools = Vec::new();
+
+        while let Some(tool) = wire.others.get(&format!("tool{}", tools.len())) {
+            tools.push(Temp::deserialize(tool).map_err(|err| err.to_string())?);
+        }
+
+        match wire.bed {
+            Some(_) if tools.is_empty() => Err("missing field `tool0`".to_string()),
+            Some(bed) => Ok(Self {
+                tools,
+                bed,
+                chamber: wire.chamber,
+            }),
+            None if tools.is_empty() => Ok(Self {
+                tools,
+                bed: Temp::default(),
+                chamber: wire.chamber,
+            }),
+            None => Err("missing field `bed`".to_string()),
+        }
+    }
+}
+
+impl From<PrinterTemperature> for WireTemperature {
+    fn from(temperature: PrinterTemperature) -> Self {
+        let others: BTreeMap<_, _> = temperature
+            .tools
+            .into_iter()
+            .enumerate()
+            .map(|(index, tool)| {
+                (
+                    format!("tool{}", index),
+                    serde_json::to_value(tool).unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        // SLA printers have no tools and no bed
+        Self {
+            bed: (!others.is_empty()).then_some(temperature.bed),
+            chamber: temperature.chamber,
+            others,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Temp {
     /// The actual tmperature of the printer
-    actual: f32,
+    pub actual: f32,
 
     /// The target temperature of the printer
-    target: f32,
+    pub target: f32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct PrinterSd {
-    ready: bool,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct PrinterSd {
+    pub ready: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct PrinterState {
-    text: String,
-    flags: PrinterFlags,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrinterState {
+    pub text: String,
+    pub flags: PrinterFlags,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct PrinterFlags {
-    operational: bool,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrinterFlags {
+    pub operational: bool,
 
-    paused: bool,
+    pub paused: bool,
 
-    printing: bool,
+    pub printing: bool,
 
-    cancelling: bool,
+    pub cancelling: bool,
 
-    pausing: bool,
+    pub pausing: bool,
 
     #[serde(rename = "sdReady")]
-    sd_ready: bool,
+    pub sd_ready: bool,
 
-    error: bool,
+    pub error: bool,
 
-    ready: bool,
+    pub ready: bool,
 
     #[serde(rename = "closedOrError")]
-    closed_or_error: bool,
+    pub closed_or_error: bool,
 
-    finished: bool,
+    pub finished: bool,
 
-    prepared: bool,
+    pub prepared: bool,
 
-    link_state: String,
+    pub link_state: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct PrinterTelemetry {
+impl PrinterFlags {
+    /// Returns the flags PrusaLink reports in the given state
+    pub fn for_state(link_state: &LinkState) -> Self {
+        Self {
+            operational: *link_state != LinkState::Error,
+            paused: *link_state == LinkState::Paused,
+            printing: *link_state == LinkState::Printing,
+            cancelling: false,
+            pausing: false,
+            sd_ready: false,
+            error: *link_state == LinkState::Error,
+            ready: matches!(
+                link_state,
+                LinkState::Idle | LinkState::Ready | LinkState::Finished | LinkState::Stopped
+            ),
+            closed_or_error: false,
+            finished: *link_state == LinkState::Finished,
+            prepared: false,
+            link_state: link_state.as_str().to_string(),
+        }
+    }
+}
+
+/// Telemetry block of `/api/printer`.
+///
+/// Which fields are reported depends on the printer and firmware, the MINI for example
+/// does not report the loaded material and only the MK3 reports axis positions,
+/// so every field is optional. The getters on `RawPrinter` fall back to the temperature block
+/// for the temperatures the telemetry doesn't report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PrinterTelemetry {
     #[serde(rename = "temp-bed")]
-    bed_temp: f32,
+    pub bed_temp: Option<f32>,
+
     #[serde(rename = "temp-nozzle")]
-    nozzle_temp: f32,
-    material: String,
+    pub nozzle_temp: Option<f32>,
+
+    /// Temperature inside the enclosure, only reported by enclosed printers
+    #[serde(rename = "temp-chamber")]
+    pub chamber_temp: Option<f32>,
+
+    pub material: Option<String>,
+
     #[serde(rename = "z-height")]
-    z_height: f32,
+    pub z_height: Option<f32>,
+
     #[serde(rename = "print-speed")]
-    print_speed: f32,
-    axis_x: Option<f32>,
-    axis_y: Option<f32>,
-    axis_z: Option<f32>,
+    pub print_speed: Option<f32>,
+
+    pub axis_x: Option<f32>,
+
+    pub axis_y: Option<f32>,
+
+    pub axis_z: Option<f32>,
+
+    /// Temperature of the UV LED panel, only reported by SLA printers
+    #[serde(rename = "temp-uv-led")]
+    pub uv_led_temp: Option<f32>,
+
+    /// Temperature of the air around the printer, only reported by SLA printers
+    #[serde(rename = "temp-amb")]
+    pub ambient_temp: Option<f32>,
+
+    /// Temperature of the processor, only reported by SLA printers
+    #[serde(rename = "temp-cpu")]
+    pub cpu_temp: Option<f32>,
+
+    /// Speed of the UV LED fan in RPM, only reported by SLA printers
+    #[serde(rename = "fan-uv-led")]
+    pub uv_led_fan: Option<u32>,
+
+    /// Speed of the blower fan in RPM, only reported by SLA printers
+    #[serde(rename = "fan-blower")]
+    pub blower_fan: Option<u32>,
+
+    /// Speed of the rear fan in RPM, only reported by SLA printers
+    #[serde(rename = "fan-rear")]
+    pub rear_fan: Option<u32>,
+
+    /// True if the cover is closed, SLA printers don't print with the cover open
+    #[serde(rename = "cover-closed")]
+    pub cover_closed: Option<bool>,
+
+    /// Resin left in the tank in milliliters, only reported by SLA printers
+    #[serde(rename = "resin-remaining")]
+    pub resin_remaining: Option<f32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+/// Every temperature of `/api/printer` with both of its readings, see `RawPrinter::temperatures()`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TemperatureView {
+    /// The first tool, which is the only one on single tool printers
+    pub nozzle: HeaterReading,
+
+    pub bed: HeaterReading,
+
+    /// The enclosure, None if the printer has no chamber sensor
+    pub chamber: Option<HeaterReading>,
+
+    /// Every tool from the temperature block, the telemetry only reports the first one
+    pub tools: Vec<Temperatures>,
+}
+
+impl TemperatureView {
+    /// Returns the largest disagreement between the blocks over all heaters, in degrees
+    pub fn max_disagreement(&self) -> Option<f32> {
+        [Some(&self.nozzle), Some(&self.bed), self.chamber.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(HeaterReading::disagreement)
+            .reduce(f32::max)
+    }
+}
+
+/// Position of the print head in millimeters
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct AxisPosition {
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub z: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PrinterStorageInfo {
     pub free_space: u64,
     pub total_space: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-struct PrinterStorage {
-    local: Option<PrinterStorageInfo>,
-    sd_card: Option<PrinterStorageInfo>,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct PrinterStorage {
+    pub local: Option<PrinterStorageInfo>,
+    pub sd_card: Option<PrinterStorageInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// The response of `/api/printer`
+///
+/// Firmware that doesn't send the `temperature`, `sd`, `telemetry` or `storage` blocks is accepted,
+/// the missing blocks are read as empty, so their values are None.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RawPrinter {
+    #[serde(default)]
     temperature: PrinterTemperature,
 
+    #[serde(default)]
     sd: PrinterSd,
 
     state: PrinterState,
 
+    #[serde(default)]
     telemetry: PrinterTelemetry,
 
+    #[serde(default)]
     storage: PrinterStorage,
+
+    /// Fields this version of the crate doesn't know about, kept so newer firmware doesn't break parsing
+    #[serde(flatten, default, skip_serializing_if = "HashMap::is_empty")]
+    extra: HashMap<String, serde_json::Value>,
 }
 
 impl RawPrinter {
+    /// Returns a builder for a `RawPrinter`, for test fixtures
+    pub fn builder() -> RawPrinterBuilder {
+        RawPrinterBuilder::new()
+    }
+
+    /// Returns the temperature block, with the actual and target temperatures of every heater
+    pub fn temperature(&self) -> &PrinterTemperature {
+        &self.temperature
+    }
+
+    /// Returns the sd card block
+    pub fn sd(&self) -> &PrinterSd {
+        &self.sd
+    }
+
+    /// Returns the state block, containing the state text and flags
+    pub fn state(&self) -> &PrinterState {
+        &self.state
+    }
+
+    /// Returns the state flags
+    pub fn flags(&self) -> &PrinterFlags {
+        &self.state.flags
+    }
+
+    /// Returns the telemetry block
+    pub fn telemetry(&self) -> &PrinterTelemetry {
+        &self.telemetry
+    }
+
+    /// Returns the storage block
+    pub fn storage(&self) -> &PrinterStorage {
+        &self.storage
+    }
+
+    /// Returns the fields of the response this version of the crate doesn't know about
+    pub fn extra(&self) -> &HashMap<String, serde_json::Value> {
+        &self.extra
+    }
+
     pub fn get_paused(&self) -> bool {
         self.state.flags.paused
     }
@@ -147,44 +451,166 @@ impl RawPrinter {
         &self.state.flags.link_state
     }
 
+    /// Returns true if the printer is not doing anything and has no errors,
+    /// meaning it is not printing, pausing, paused or cancelling
+    pub fn is_idle(&self) -> bool {
+        !(self.get_printing()
+            || self.get_pausing()
+            || self.get_paused()
+            || self.get_cancelling()
+            || self.has_error())
+    }
+
+    /// Returns true if the printer reports an error
+    pub fn has_error(&self) -> bool {
+        self.get_error() || self.get_closed_or_error()
+    }
+
+    /// Returns the link state parsed into a `LinkState`
+    pub fn link_state(&self) -> LinkState {
+        LinkState::from(self.state.flags.link_state.as_str())
+    }
+
+    /// Returns true if the printer reports no tools, which is the case for SLA printers
+    pub fn is_sla(&self) -> bool {
+        self.temperature.tools.is_empty()
+    }
+
     pub fn get_state_text(&self) -> &str {
         &self.state.text
     }
 
-    pub fn get_bed_temp(&self) -> f32 {
-        self.telemetry.bed_temp
+    /// Returns the temperatures of every heater from both the temperature block and the telemetry,
+    /// so disagreements between them can be seen and resolved explicitly.
+    /// The getters like `get_nozzle_temp()` resolve them with `TemperatureSource::Telemetry`.
+    pub fn temperatures(&self) -> TemperatureView {
+        let block = |temp: &Temp| Temperatures {
+            actual: Celsius(temp.actual),
+            target: Celsius(temp.target),
+        };
+
+        let chamber_block = self.temperature.chamber.as_ref().map(block);
+        let chamber_telemetry = self.telemetry.chamber_temp.map(Celsius);
+
+        TemperatureView {
+            nozzle: HeaterReading {
+                block: self.temperature.nozzle().map(block).unwrap_or_default(),
+                telemetry: self.telemetry.nozzle_temp.map(Celsius),
+            },
+            bed: HeaterReading {
+                block: block(&self.temperature.bed),
+                telemetry: self.telemetry.bed_temp.map(Celsius),
+            },
+            // Without a chamber in the temperature block, the telemetry is the only reading
+            chamber: match (chamber_block, chamber_telemetry) {
+                (None, None) => None,
+                (block, telemetry) => Some(HeaterReading {
+                    block: block.unwrap_or(Temperatures {
+                        actual: telemetry.unwrap_or_default(),
+                        target: Celsius::default(),
+                    }),
+                    telemetry,
+                }),
+            },
+            tools: self.temperature.tools.iter().map(block).collect(),
+        }
     }
 
-    pub fn get_target_bed_temp(&self) -> f32 {
-        self.temperature.bed.target
+    /// Returns the bed temperature from the telemetry,
+    /// falling back to the temperature block if the telemetry doesn't report it
+    pub fn get_bed_temp(&self) -> Celsius {
+        Celsius(
+            self.telemetry
+                .bed_temp
+                .unwrap_or(self.temperature.bed.actual),
+        )
     }
 
-    pub fn get_nozzle_temp(&self) -> f32 {
-        self.telemetry.nozzle_temp
+    pub fn get_target_bed_temp(&self) -> Celsius {
+        Celsius(self.temperature.bed.target)
     }
 
-    pub fn get_target_nozzle_temp(&self) -> f32 {
-        self.temperature.nozzle.target
+    /// Returns the temperature inside the enclosure from the telemetry, falling back to
+    /// the temperature block, or None if the printer has no chamber sensor
+    pub fn get_chamber_temp(&self) -> Option<Celsius> {
+        self.telemetry
+            .chamber_temp
+            .or(self
+                .temperature
+                .chamber
+                .as_ref()
+                .map(|chamber| chamber.actual))
+            .map(Celsius)
     }
 
-    pub fn get_material_telemetry(&self) -> &str {
-        &self.telemetry.material
+    /// Returns the target temperature of the enclosure, None if the printer doesn't heat or report it
+    pub fn get_target_chamber_temp(&self) -> Option<Celsius> {
+        self.temperature
+            .chamber
+            .as_ref()
+            .map(|chamber| Celsius(chamber.target))
     }
 
-    pub fn get_z_height_telemetry(&self) -> f32 {
-        self.telemetry.z_height
+    /// Returns the nozzle temperature of the first tool from the telemetry,
+    /// falling back to the temperature block if the telemetry doesn't report it
+    pub fn get_nozzle_temp(&self) -> Celsius {
+        self.telemetry
+            .nozzle_temp
+            .map(Celsius)
+            .or(self.get_tool_temp(0))
+            .unwrap_or_default()
     }
 
-    pub fn get_print_speed_telemetry(&self) -> f32 {
-        self.telemetry.print_speed
+    pub fn get_target_nozzle_temp(&self) -> Celsius {
+        self.get_target_tool_temp(0).unwrap_or_default()
+    }
+
+    /// Returns the number of tools the printer reports temperatures for
+    pub fn tool_count(&self) -> usize {
+        self.temperature.tools.len()
+    }
+
+    /// Returns the actual temperature of the tool with the given index
+    pub fn get_tool_temp(&self, index: usize) -> Option<Celsius> {
+        self.temperature
+            .tool(index)
+            .map(|tool| Celsius(tool.actual))
+    }
+
+    /// Returns the target temperature of the tool with the given index
+    pub fn get_target_tool_temp(&self, index: usize) -> Option<Celsius> {
+        self.temperature
+            .tool(index)
+            .map(|tool| Celsius(tool.target))
+    }
+
+    pub fn get_material_telemetry(&self) -> Option<&str> {
+        self.telemetry.material.as_deref()
+    }
+
+    /// Returns the loaded material, None if nothing is loaded or the printer doesn't report it
+    pub fn get_material(&self) -> Option<Material> {
+        self.telemetry.material.as_deref().and_then(Material::parse)
+    }
+
+    pub fn get_z_height_telemetry(&self) -> Option<f32> {
+        self.telemetry.z_height
     }
 
-    pub fn get_axis_x_telemetry(&self) -> Option<f32> {
-        self.telemetry.axis_x
+    pub fn get_print_speed_telemetry(&self) -> Option<f32> {
+        self.telemetry.print_speed
     }
 
-    pub fn get_axis_y_telemetry(&self) -> Option<f32> {
-        self.telemetry.axis_y
+    /// Returns the position of the print head, each axis is None if the printer doesn't report it.
+    ///
+    /// Only the MK3 reports its axis positions, other printers only report the z height,
+    /// which is available from `get_z_height_telemetry()`.
+    pub fn position(&self) -> AxisPosition {
+        AxisPosition {
+            x: self.telemetry.axis_x,
+            y: self.telemetry.axis_y,
+            z: self.telemetry.axis_z,
+        }
     }
 
     pub fn get_local_storage_space(&self) -> Option<&PrinterStorageInfo> {
@@ -195,3 +621,127 @@ impl RawPrinter {
         self.storage.sd_card.as_ref()
     }
 }
+
+/// Builds a `RawPrinter` without a response from a printer, for example as a test fixture.
+///
+/// By default the printer is idle with one tool, and the heaters are off at room temperature.
+/// The telemetry is left empty, so the temperature getters read the temperature block.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::{
+///     raw_printer::{LinkState, RawPrinter},
+///     units::Celsius,
+/// };
+///
+/// let printer = RawPrinter::builder()
+///     .link_state(LinkState::Printing)
+///     .nozzle_temp(214.8, 215.0)
+///     .bed_temp(60.1, 60.0)
+///     .build();
+///
+/// assert!(printer.get_printing());
+/// assert_eq!(printer.get_nozzle_temp(), Celsius(214.8));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawPrinterBuilder {
+    printer: RawPrinter,
+}
+
+impl Default for RawPrinterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RawPrinterBuilder {
+    pub fn new() -> Self {
+        let room_temp = Temp {
+            actual: 23.0,
+            target: 0.0,
+        };
+
+        Self {
+            printer: RawPrinter {
+                temperature: PrinterTemperature {
+                    tools: vec![room_temp.clone()],
+                    bed: room_temp,
+                    chamber: None,
+                },
+                sd: PrinterSd::default(),
+                state: PrinterState {
+                    text: "Operational".to_string(),
+                    flags: PrinterFlags::for_state(&LinkState::Idle),
+                },
+                telemetry: PrinterTelemetry::default(),
+                storage: PrinterStorage::default(),
+                extra: HashMap::new(),
+            },
+        }
+    }
+
+    /// Sets the state text and the flags that go with the state
+    pub fn link_state(mut self, link_state: LinkState) -> Self {
+        self.printer.state = PrinterState {
+            text: link_state.as_str().to_string(),
+            flags: PrinterFlags::for_state(&link_state),
+        };
+        self
+    }
+
+    /// Sets the flags, for states that `link_state()` doesn't cover, like cancelling
+    pub fn flags(mut self, flags: PrinterFlags) -> Self {
+        self.printer.state.flags = flags;
+        self
+    }
+
+    /// Sets the temperature of the first tool
+    pub fn nozzle_temp(mut self, actual: f32, target: f32) -> Self {
+        self.printer.temperature.tools[0] = Temp { actual, target };
+        self
+    }
+
+    pub fn bed_temp(mut self, actual: f32, target: f32) -> Self {
+        self.printer.temperature.bed = Temp { actual, target };
+        self
+    }
+
+    /// Sets the temperature of the enclosure, which printers without a chamber sensor don't have
+    pub fn chamber_temp(mut self, actual: f32, target: f32) -> Self {
+        self.printer.temperature.chamber = Some(Temp { actual, target });
+        self
+    }
+
+    /// Sets the temperatures of every tool, for multi tool printers like the XL.
+    /// At least one tool has to be given, since every printer reports `tool0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tools` is empty.
+    pub fn tools(mut self, tools: Vec<Temp>) -> Self {
+        assert!(!tools.is_empty(), "a printer has at least one tool");
+
+        self.printer.temperature.tools = tools;
+        self
+    }
+
+    pub fn sd(mut self, sd: PrinterSd) -> Self {
+        self.printer.sd = sd;
+        self
+    }
+
+    pub fn telemetry(mut self, telemetry: PrinterTelemetry) -> Self {
+        self.printer.telemetry = telemetry;
+        self
+    }
+
+    pub fn storage(mut self, storage: PrinterStorage) -> Self {
+        self.printer.storage = storage;
+        self
+    }
+
+    pub fn build(self) -> RawPrinter {
+        self.printer
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{raw_printer::LinkState, time};
+
+/// Nozzle temperature in degrees celsius above which the hotend fan has to be spinning
+pub const HOTEND_FAN_TEMP_THRESHOLD: f32 = 50.0;
+
+/// The job part of `/api/v1/status`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusJob {
+    pub id: u32,
+
+    /// Progress of the job in percent
+    pub progress: Option<f32>,
+
+    /// Estimated time remaining, in seconds in the response
+    #[serde(default, with = "time::option_secs")]
+    pub time_remaining: Option<Duration>,
+
+    /// Time spent printing, in seconds in the response
+    #[serde(default, with = "time::option_secs")]
+    pub time_printing: Option<Duration>,
+}
+
+/// The storage part of `/api/v1/status`, the storage the current job is printed from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusStorage {
+    pub path: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// A filament slot of a multi material unit or a tool of a multi tool printer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SlotInfo {
+    /// The material loaded in the slot, for example `PLA`
+    pub material: Option<String>,
+
+    /// Nozzle temperature of the slot, on multi tool printers
+    pub temp: Option<f32>,
+}
+
+/// The slots of a printer with a multi material unit or multiple tools
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusSlots {
+    /// The slot that is currently in use, counting from 1
+    pub active: Option<u32>,
+
+    /// The slots, keyed by their number
+    #[serde(default)]
+    pub slots: BTreeMap<String, SlotInfo>,
+}
+
+/// A health report in the printer part of `/api/v1/status`, like `status_printer`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    /// False if something needs attention
+    pub ok: bool,
+
+    /// What needs attention, for example `Filament runout`
+    pub message: Option<String>,
+
+    /// The Prusa error code, like 17505, only reported by some firmwares
+    pub code: Option<u32>,
+}
+
+/// Why the printer stopped or asks for attention, see `Printer::current_error()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrinterErrorInfo {
+    /// The state of the printer when the error was reported
+    pub state: LinkState,
+
+    /// The Prusa error code, None if the firmware doesn't report one
+    pub code: Option<u32>,
+
+    /// The message of the printer, or the state if there is no message
+    pub text: String,
+}
+
+/// The printer part of `/api/v1/status`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusPrinter {
+    pub state: String,
+
+    pub temp_nozzle: Option<f32>,
+    pub target_nozzle: Option<f32>,
+    pub temp_bed: Option<f32>,
+    pub target_bed: Option<f32>,
+
+    /// Temperature inside the enclosure, only reported by enclosed printers
+    pub temp_chamber: Option<f32>,
+    pub target_chamber: Option<f32>,
+
+    pub axis_x: Option<f32>,
+    pub axis_y: Option<f32>,
+    pub axis_z: Option<f32>,
+
+    /// Flow override in percent, 100 is the flow from the g-code
+    pub flow: Option<u32>,
+
+    /// Speed override in percent, 100 is the speed from the g-code
+    pub speed: Option<u32>,
+
+    /// Speed of the hotend fan in RPM
+    pub fan_hotend: Option<u32>,
+
+    /// Speed of the print (part cooling) fan in RPM
+    pub fan_print: Option<u32>,
+
+    /// True if the filament sensor detects filament, not reported by every firmware
+    pub filament_sensor: Option<bool>,
+
+    /// The multi material slots, only reported by printers that have them
+    pub slot: Option<StatusSlots>,
+
+    /// The health of the printer, with a message when it is not ok
+    pub status_printer: Option<StatusMessage>,
+
+    /// The health of the connection to Prusa Connect, with a message when it is not ok
+    pub status_connect: Option<StatusMessage>,
+}
+
+/// The status of the printer as returned by `/api/v1/status`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RawStatus {
+    job: Option<StatusJob>,
+
+    storage: Option<StatusStorage>,
+
+    printer: StatusPrinter,
+
+    /// Fields this version of the crate doesn't know about, kept so newer firmware doesn't break parsing
+    #[serde(flatten, default, skip_serializing_if = "HashMap::is_empty")]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+impl RawStatus {
+    /// Returns the job part, or None if there is no job
+    pub fn job(&self) -> Option<&StatusJob> {
+        self.job.as_ref()
+    }
+
+    /// Returns the storage the current job is printed from
+    pub fn storage(&self) -> Option<&StatusStorage> {
+        self.storage.as_ref()
+    }
+
+    /// Returns the printer part
+    pub fn printer(&self) -> &StatusPrinter {
+        &self.printer
+    }
+
+    /// Returns the fields of the response this version of the crate doesn't know about
+    pub fn extra(&self) -> &HashMap<String, serde_json::Value> {
+        &self.extra
+    }
+
+    /// Returns the state of the printer parsed into a `LinkState`
+    pub fn state(&self) -> LinkState {
+        LinkState::from(self.printer.state.as_str())
+    }
+
+    /// Returns the speed of the hotend fan in RPM
+    pub fn get_fan_hotend(&self) -> Option<u32> {
+        self.printer.fan_hotend
+    }
+
+    /// Returns the speed of the print fan in RPM
+    pub fn get_fan_print(&self) -> Option<u32> {
+        self.printer.fan_print
+    }
+
+    /// Returns the speed override in percent
+    pub fn get_speed_factor(&self) -> Option<u32> {
+        self.printer.speed
+    }
+
+    /// Returns the flow override in percent
+    pub fn get_flow_factor(&self) -> Option<u32> {
+        self.printer.flow
+    }
+
+    /// Returns true if the nozzle is hot but the hotend fan isn't spinning,
+    /// which will lead to heat creep and clogs if it isn't fixed.
+    ///
+    /// Returns None if the printer doesn't report the fan speed or nozzle temperature.
+    pub fn hotend_fan_stalled(&self) -> Option<bool> {
+        let fan = self.printer.fan_hotend?;
+        let temp = self.printer.temp_nozzle?;
+
+        Some(temp > HOTEND_FAN_TEMP_THRESHOLD && fan == 0)
+    }
+
+    /// Returns the slot of the multi material unit that is currently in use
+    pub fn get_active_slot(&self) -> Option<u32> {
+        self.printer.slot.as_ref().and_then(|slot| slot.active)
+    }
+
+    /// Returns why the printer stopped or asks for attention, None if it is fine.
+    ///
+    /// A `status_printer` that isn't ok is reported with its message and code. Printers in the
+    /// `ERROR` or `ATTENTION` state that don't send a message are reported with the state as the text.
+    pub fn error_info(&self) -> Option<PrinterErrorInfo> {
+        let state = self.state();
+
+        if let Some(status) = self
+            .printer
+            .status_printer
+            .as_ref()
+            .filter(|status| !status.ok)
+        {
+            return Some(PrinterErrorInfo {
+                text: status
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| state.as_str().to_string()),
+                code: status.code,
+                state,
+            });
+        }
+
+        matches!(state, LinkState::Error | LinkState::Attention).then(|| PrinterErrorInfo {
+            code: None,
+            text: state.as_str().to_string(),
+            state,
+        })
+    }
+
+    /// Returns true if the filament sensor detects filament,
+    /// or None if the printer doesn't report its filament sensor
+    pub fn get_filament_detected(&self) -> Option<bool> {
+        self.printer.filament_sensor
+    }
+}
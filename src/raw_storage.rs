@@ -0,0 +1,185 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gcode_meta::GcodeMetadata,
+    raw_job::FileRefs,
+    raw_printer::{PrinterStorage, PrinterStorageInfo},
+    storage::Storage,
+};
+
+/// A storage of the printer as returned by `/api/v1/storage`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageInfo {
+    /// The name used in file paths, for example `usb` or `local`
+    pub name: String,
+
+    /// The kind of storage, for example `USB` or `LOCAL`
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// The path of the storage, for example `/usb`
+    pub path: String,
+
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Free space in bytes, not reported by every firmware
+    pub free_space: Option<u64>,
+
+    /// Total space in bytes, not reported by every firmware
+    pub total_space: Option<u64>,
+
+    /// False if the storage is known but not present, for example when no usb stick is plugged in
+    #[serde(default = "available_default")]
+    pub available: bool,
+}
+
+fn available_default() -> bool {
+    true
+}
+
+impl StorageInfo {
+    /// Returns true if `storage` refers to this storage, either by name or by path
+    pub fn matches(&self, storage: impl Into<Storage>) -> bool {
+        let storage = storage.into();
+
+        [self.name.as_str(), self.path.as_str()]
+            .into_iter()
+            .any(|name| Storage::from(name) == storage)
+    }
+
+    /// Returns the storage to pass to the file and upload functions
+    pub fn storage(&self) -> Storage {
+        Storage::from(self.name.as_str())
+    }
+
+    /// Converts the storage block of `/api/printer`, for firmware without `/api/v1/storage`
+    pub fn from_legacy(storage: &PrinterStorage) -> Vec<StorageInfo> {
+        let convert = |name: &str, kind: &str, info: &PrinterStorageInfo| StorageInfo {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            path: format!("/{}", name),
+            read_only: false,
+            free_space: Some(info.free_space),
+            total_space: Some(info.total_space),
+            available: true,
+        };
+
+        let local = storage
+            .local
+            .as_ref()
+            .map(|info| convert("local", "LOCAL", info));
+        let sd_card = storage
+            .sd_card
+            .as_ref()
+            .map(|info| convert("sdcard", "SDCARD", info));
+
+        local.into_iter().chain(sd_card).collect()
+    }
+}
+
+/// The response of `/api/v1/storage`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RawStorageList {
+    pub storage_list: Vec<StorageInfo>,
+}
+
+/// A file or folder on a storage of the printer as returned by `/api/v1/files`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    /// The short (8.3) name of the file
+    pub name: String,
+
+    /// The long name of the file, as it is shown on the printer
+    pub display_name: Option<String>,
+
+    /// The kind of entry, `FOLDER`, `PRINT_FILE` for g-code or `FILE` for anything else
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    pub size: Option<u64>,
+
+    /// Modification time as a unix timestamp
+    pub m_timestamp: Option<u64>,
+
+    #[serde(default, rename = "ro")]
+    pub read_only: bool,
+
+    pub refs: Option<FileRefs>,
+
+    /// The entries of a folder, only filled in for the folder that was listed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<FileEntry>,
+}
+
+impl FileEntry {
+    pub fn is_folder(&self) -> bool {
+        self.kind == "FOLDER"
+    }
+
+    /// Returns the long name if the printer knows it, the short name otherwise
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A single file as returned by `/api/v1/files/{storage}/{path}`, see `Printer::get_file_info()`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FileDetail {
+    /// The short (8.3) name of the file
+    pub name: String,
+
+    /// The long name of the file, as it is shown on the printer
+    pub display_name: Option<String>,
+
+    /// The kind of entry, `PRINT_FILE` for g-code or `FILE` for anything else
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    pub size: Option<u64>,
+
+    /// Modification time as a unix timestamp
+    pub m_timestamp: Option<u64>,
+
+    #[serde(default, rename = "ro")]
+    pub read_only: bool,
+
+    /// Where to download the file and its thumbnails from
+    pub refs: Option<FileRefs>,
+
+    /// The metadata the slicer wrote into the file, keyed like in the g-code,
+    /// for example `estimated printing time (normal mode)`
+    #[serde(default)]
+    pub meta: HashMap<String, serde_json::Value>,
+}
+
+impl FileDetail {
+    /// Returns the long name if the printer knows it, the short name otherwise
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Returns the estimated print time in normal mode, None if the slicer didn't write one
+    pub fn estimated_print_time(&self) -> Option<Duration> {
+        self.metadata().estimated_time
+    }
+
+    /// Returns the metadata parsed the same way as the metadata of a local file, see `gcode_meta`
+    pub fn metadata(&self) -> GcodeMetadata {
+        let entries = self
+            .meta
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_json::Value::String(text) => text.clone(),
+                    value => value.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+
+        GcodeMetadata::from_entries(entries)
+    }
+}
@@ -0,0 +1,169 @@
+//! Checking that the printer can take a new print, see `Printer::ensure_ready()`.
+
+use std::{error::Error, fmt};
+
+use crate::{
+    error::PrinterError,
+    raw_printer::{LinkState, RawPrinter},
+    units::Celsius,
+    Printer,
+};
+
+/// What `Printer::ensure_ready()` checks besides the state of the printer.
+///
+/// Without limits only the state is checked. Set temperature limits when a person or a robot
+/// has to reach into the printer before the print, to clear the bed for example.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::{raw_printer::RawPrinter, ready::{NotReadyReason, ReadyCheck}, units::Celsius};
+///
+/// let check = ReadyCheck::new().max_bed_temp(40.0);
+/// let printer = RawPrinter::builder().bed_temp(60.0, 60.0).build();
+///
+/// assert_eq!(
+///     check.check(&printer),
+///     Err(NotReadyReason::BedTooHot { actual: Celsius(60.0), limit: Celsius(40.0) })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReadyCheck {
+    max_nozzle_temp: Option<Celsius>,
+    max_bed_temp: Option<Celsius>,
+}
+
+impl ReadyCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The printer isn't ready while the nozzle is hotter than `max_nozzle_temp`
+    pub fn max_nozzle_temp(mut self, max_nozzle_temp: impl Into<Celsius>) -> Self {
+        self.max_nozzle_temp = Some(max_nozzle_temp.into());
+        self
+    }
+
+    /// The printer isn't ready while the bed is hotter than `max_bed_temp`
+    pub fn max_bed_temp(mut self, max_bed_temp: impl Into<Celsius>) -> Self {
+        self.max_bed_temp = Some(max_bed_temp.into());
+        self
+    }
+
+    /// Returns why the printer isn't ready, checking the error state first,
+    /// then the operational and ready flags and the temperatures last
+    pub fn check(&self, printer: &RawPrinter) -> Result<(), NotReadyReason> {
+        if printer.has_error() || printer.link_state() == LinkState::Error {
+            return Err(NotReadyReason::Error(printer.get_state_text().to_string()));
+        }
+
+        if !printer.is_idle()
+            || matches!(
+                printer.link_state(),
+                LinkState::Busy | LinkState::Printing | LinkState::Paused | LinkState::Attention
+            )
+        {
+            return Err(NotReadyReason::Busy(printer.link_state()));
+        }
+
+        if !printer.get_operational() || !printer.get_ready() {
+            return Err(NotReadyReason::NotOperational(printer.link_state()));
+        }
+
+        let nozzle = printer.get_nozzle_temp();
+        if let Some(limit) = self.max_nozzle_temp.filter(|&limit| nozzle > limit) {
+            return Err(NotReadyReason::NozzleTooHot {
+                actual: nozzle,
+                limit,
+            });
+        }
+
+        let bed = printer.get_bed_temp();
+        if let Some(limit) = self.max_bed_temp.filter(|&limit| bed > limit) {
+            return Err(NotReadyReason::BedTooHot { actual: bed, limit });
+        }
+
+        Ok(())
+    }
+}
+
+/// Why the printer can't take a new print, see `PrinterError::NotReady`
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotReadyReason {
+    /// The printer reports an error, with the state text of the printer
+    Error(String),
+
+    /// The printer is printing, paused or otherwise busy
+    Busy(LinkState),
+
+    /// The printer doesn't report itself operational and ready
+    NotOperational(LinkState),
+
+    /// The nozzle is hotter than `ReadyCheck::max_nozzle_temp()`
+    NozzleTooHot { actual: Celsius, limit: Celsius },
+
+    /// The bed is hotter than `ReadyCheck::max_bed_temp()`
+    BedTooHot { actual: Celsius, limit: Celsius },
+}
+
+impl fmt::Display for NotReadyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotReadyReason::Error(state) => write!(f, "the printer reports an error: {}", state),
+            NotReadyReason::Busy(state) => {
+                write!(f, "the printer is busy, its state is {}", state.as_str())
+            }
+            NotReadyReason::NotOperational(state) => {
+                write!(
+                    f,
+                    "the printer isn't ready, its state is {}",
+                    state.as_str()
+                )
+            }
+            NotReadyReason::NozzleTooHot { actual, limit } => write!(
+                f,
+                "the nozzle is at {}, above the limit of {}",
+                actual, limit
+            ),
+            NotReadyReason::BedTooHot { actual, limit } => {
+                write!(f, "the bed is at {}, above the limit of {}", actual, limit)
+            }
+        }
+    }
+}
+
+impl Printer {
+    /// Checks that the printer can take a new print, as a guard before submitting a print
+    /// without a person watching. See `ReadyCheck` for what is checked.
+    ///
+    /// This always refreshes the printer information first, so the check never looks at a cached state.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PrinterError::NotReady` with the reason if the printer isn't ready,
+    /// or an Err if the printer information can't be read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::{PrinterBuilder, ready::ReadyCheck};
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// printer
+    ///     .ensure_ready(ReadyCheck::new().max_bed_temp(35.0))
+    ///     .await
+    ///     .unwrap();
+    /// printer.start_print("usb", "benchy.bgcode").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn ensure_ready(&mut self, check: ReadyCheck) -> Result<(), Box<dyn Error>> {
+        self.refresh().await?;
+
+        check
+            .check(self.printer.as_ref().unwrap())
+            .map_err(|reason| PrinterError::NotReady(reason).into())
+    }
+}
@@ -0,0 +1,84 @@
+//! Retrying uploads that failed during the transfer, set with `PrinterBuilder::retry_uploads()`.
+
+use std::{error::Error, time::Duration};
+
+use crate::{
+    error::PrinterError,
+    transport::{TransportError, TransportErrorKind},
+};
+
+/// How often a failed upload is tried again, and how long to wait in between.
+///
+/// The wait doubles after every attempt, starting at `initial_backoff` and never longer than `max_backoff`.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new(4).backoff(Duration::from_secs(2), Duration::from_secs(10));
+///
+/// assert_eq!(policy.delay(1), Duration::from_secs(2));
+/// assert_eq!(policy.delay(2), Duration::from_secs(4));
+/// assert_eq!(policy.delay(3), Duration::from_secs(8));
+/// assert_eq!(policy.delay(4), Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of attempts including the first one, values below 1 are treated as 1
+    pub max_attempts: u32,
+
+    /// The wait after the first failed attempt
+    pub initial_backoff: Duration,
+
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a policy with `max_attempts` attempts, waiting 1 second after the first failure
+    /// and at most 30 seconds
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Use this function to change how long to wait between the attempts
+    pub fn backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Returns how long to wait after the given failed attempt, counting from 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, see `new()`
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Returns true if the upload may succeed when it is tried again: the connection failed,
+/// or the file arrived incomplete. Files the printer refused are not retried
+pub(crate) fn is_retryable(err: &(dyn Error + 'static)) -> bool {
+    if let Some(err) = err.downcast_ref::<TransportError>() {
+        return err.kind() != TransportErrorKind::CircuitOpen;
+    }
+
+    matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::UploadVerificationFailed { .. })
+    )
+}
@@ -0,0 +1,187 @@
+//! Counting the requests sent to a printer, read with `Printer::stats()`.
+//!
+//! Every request is counted under its endpoint, the method and the path with the parts that
+//! change between calls replaced, like `PUT /api/v1/job/{id}/pause` or `PUT /api/v1/files/usb/{path}`.
+
+use std::{collections::BTreeMap, time::Duration};
+
+/// The upper bounds of the buckets of `EndpointStats::latency_histogram`,
+/// the last bucket of the histogram counts the requests slower than all of them
+pub const LATENCY_BUCKETS: [Duration; 6] = [
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+];
+
+/// The requests sent to one endpoint, or to all of them for `RequestStats::total()`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    /// The number of requests sent, including the failed ones
+    pub requests: u64,
+
+    /// The requests that didn't get a response, because the printer couldn't be reached for example
+    pub failures: u64,
+
+    /// The requests the printer answered with an error status
+    pub error_responses: u64,
+
+    /// The time spent waiting for all the requests together
+    pub total_latency: Duration,
+
+    pub max_latency: Duration,
+
+    /// The number of requests that took at most the duration of the bucket in `LATENCY_BUCKETS`
+    /// with the same index, and longer than the bucket before
+    pub latency_histogram: [u64; LATENCY_BUCKETS.len() + 1],
+}
+
+impl EndpointStats {
+    /// Returns the average time a request took, or None if no request was sent
+    pub fn average_latency(&self) -> Option<Duration> {
+        let requests = u32::try_from(self.requests).ok().filter(|&n| n > 0)?;
+
+        Some(self.total_latency / requests)
+    }
+
+    fn record(&mut self, latency: Duration, status: Option<http::StatusCode>) {
+        self.requests += 1;
+
+        match status {
+            None => self.failures += 1,
+            Some(status) if status.is_client_error() || status.is_server_error() => {
+                self.error_responses += 1
+            }
+            Some(_) => {}
+        }
+
+        self.total_latency += latency;
+        self.max_latency = self.max_latency.max(latency);
+
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| latency <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.latency_histogram[bucket] += 1;
+    }
+
+    fn merge(&mut self, other: &EndpointStats) {
+        self.requests += other.requests;
+        self.failures += other.failures;
+        self.error_responses += other.error_responses;
+        self.total_latency += other.total_latency;
+        self.max_latency = self.max_latency.max(other.max_latency);
+
+        for (count, other) in self
+            .latency_histogram
+            .iter_mut()
+            .zip(other.latency_histogram)
+        {
+            *count += other;
+        }
+    }
+}
+
+/// The requests sent to a printer since it was built or `Printer::reset_stats()` was called
+///
+/// # Example
+///
+/// ```no_run
+/// # use prusa_link_rs::PrinterBuilder;
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// let mut printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+///     .build();
+///
+/// printer.refresh().await.unwrap();
+///
+/// for (endpoint, stats) in printer.stats().endpoints() {
+///     println!("{}: {} requests, {:?} on average", endpoint, stats.requests, stats.average_latency());
+/// }
+/// # })
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    endpoints: BTreeMap<String, EndpointStats>,
+}
+
+impl RequestStats {
+    /// Returns the stats of every endpoint a request was sent to, sorted by endpoint
+    pub fn endpoints(&self) -> impl Iterator<Item = (&str, &EndpointStats)> {
+        self.endpoints
+            .iter()
+            .map(|(endpoint, stats)| (endpoint.as_str(), stats))
+    }
+
+    /// Returns the stats of an endpoint like `GET /api/printer`, or None if no request was sent to it
+    pub fn endpoint(&self, endpoint: &str) -> Option<&EndpointStats> {
+        self.endpoints.get(endpoint)
+    }
+
+    /// Returns the stats of all endpoints together
+    pub fn total(&self) -> EndpointStats {
+        let mut total = EndpointStats::default();
+
+        for stats in self.endpoints.values() {
+            total.merge(stats);
+        }
+
+        total
+    }
+
+    /// Counts a request, `status` is None if no response was received
+    pub(crate) fn record(
+        &mut self,
+        method: &http::Method,
+        path: &str,
+        latency: Duration,
+        status: Option<http::StatusCode>,
+    ) {
+        self.endpoints
+            .entry(format!("{} {}", method, endpoint(path)))
+            .or_default()
+            .record(latency, status);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.endpoints.clear();
+    }
+}
+
+/// Returns the path of the endpoint without the base path, with ids replaced by `{id}`
+/// and file paths by `{path}`. Downloads are counted as `/{storage}/{path}`
+fn endpoint(path: &str) -> String {
+    let Some(start) = ["/api/", "/thumb/"]
+        .iter()
+        .filter_map(|prefix| path.find(prefix))
+        .min()
+    else {
+        // Anything else is a download, like `/usb/BENCHY~1.GCO`
+        let storage = path
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        return format!("/{}/{{path}}", storage);
+    };
+
+    let mut segments = Vec::new();
+    for segment in path[start..].split('/') {
+        // The storage and the size of a thumbnail are kept, the path after them is not
+        let after = segments.len().checked_sub(2).map(|index| segments[index]);
+        if matches!(after, Some("files" | "thumb")) {
+            segments.push("{path}");
+            break;
+        }
+
+        if !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit()) {
+            segments.push("{id}");
+        } else {
+            segments.push(segment);
+        }
+    }
+
+    segments.join("/")
+}
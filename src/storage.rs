@@ -0,0 +1,99 @@
+//! The storages of a printer, used by the file, upload and storage functions of `Printer`.
+
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A storage of the printer, named in requests by its wire name, like `usb`.
+///
+/// The functions taking a storage accept anything that converts into one, so `"usb"` works as well
+/// as `Storage::Usb`. Names are matched ignoring case and surrounding slashes, `/USB` is `Storage::Usb`,
+/// and names that aren't known end up in `Storage::Custom`.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::storage::Storage;
+///
+/// assert_eq!(Storage::from("/usb"), Storage::Usb);
+/// assert_eq!(Storage::SdCard.as_str(), "sdcard");
+/// assert_eq!(Storage::from("flash"), Storage::Custom("flash".to_string()));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Storage {
+    /// The internal storage of printers with PrusaLink on a Raspberry Pi, `local`
+    Local,
+
+    /// The usb stick of the MK4, XL and MINI, `usb`
+    Usb,
+
+    /// The sd card of the MK3, `sdcard`
+    SdCard,
+
+    /// Any other storage, by the name the printer uses for it
+    Custom(String),
+}
+
+impl Storage {
+    /// Returns the name used for the storage in requests
+    pub fn as_str(&self) -> &str {
+        match self {
+            Storage::Local => "local",
+            Storage::Usb => "usb",
+            Storage::SdCard => "sdcard",
+            Storage::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Storage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<&str> for Storage {
+    fn from(name: &str) -> Self {
+        let name = name.trim_matches('/');
+
+        match name.to_ascii_lowercase().as_str() {
+            "local" => Storage::Local,
+            "usb" => Storage::Usb,
+            "sdcard" => Storage::SdCard,
+            _ => Storage::Custom(name.to_string()),
+        }
+    }
+}
+
+impl From<&String> for Storage {
+    fn from(name: &String) -> Self {
+        Storage::from(name.as_str())
+    }
+}
+
+impl From<String> for Storage {
+    fn from(name: String) -> Self {
+        Storage::from(name.as_str())
+    }
+}
+
+impl From<&Storage> for Storage {
+    fn from(storage: &Storage) -> Self {
+        storage.clone()
+    }
+}
+
+impl From<Storage> for String {
+    fn from(storage: Storage) -> Self {
+        storage.as_str().to_string()
+    }
+}
+
+impl FromStr for Storage {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(Storage::from(name))
+    }
+}
@@ -0,0 +1,439 @@
+//! A fake printer for testing applications built on this crate, enabled with the `test-utils` feature.
+//!
+//! `FakePrinter` implements `PrinterApi` without any network, and its state is changed by the test:
+//!
+//! ```rust
+//! use prusa_link_rs::{api::PrinterApi, test_utils::FakePrinter};
+//! # use tokio_test::block_on;
+//! # block_on(async {
+//! let mut printer = FakePrinter::new();
+//! let script = printer.clone();
+//!
+//! script.start_print("benchy.gcode");
+//! script.advance(40.0);
+//! assert_eq!(printer.progress_percent().await.unwrap(), Some(40.0));
+//!
+//! script.fail_next("Connection reset");
+//! assert!(printer.get_status().await.is_err());
+//!
+//! script.advance(60.0);
+//! assert!(printer.is_idle().await.unwrap());
+//! # })
+//! ```
+//!
+//! `MockClock` is a `Clock` for testing when a `Printer` refreshes its cached information,
+//! see `clock` for an example.
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    api::PrinterApi,
+    clock::{Clock, Instant},
+    error::ConnectionError,
+    raw_info::RawInfo,
+    raw_job::{FileRefs, JobId, RawJob, ThumbnailSize},
+    raw_printer::{LinkState, RawPrinter},
+    raw_status::{RawStatus, HOTEND_FAN_TEMP_THRESHOLD},
+    raw_storage::StorageInfo,
+    storage::Storage,
+};
+
+/// The estimated duration of every print started with `FakePrinter::start_print()`, in seconds
+pub const FAKE_PRINT_DURATION: u64 = 3600;
+
+/// A file uploaded to a `FakePrinter`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upload {
+    pub storage: Storage,
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct FakeJob {
+    id: JobId,
+    file: String,
+    progress: f32,
+}
+
+#[derive(Debug)]
+struct FakeState {
+    state: LinkState,
+    nozzle: (f32, f32),
+    bed: (f32, f32),
+    job: Option<FakeJob>,
+    next_job_id: u32,
+    failures: VecDeque<Box<dyn Error + Send + Sync>>,
+    connected: bool,
+    uploads: Vec<Upload>,
+}
+
+/// A printer that answers every request from memory, for tests.
+///
+/// Clones share their state, so a test can keep a clone to script the printer
+/// while the code under test holds the other one.
+/// A new printer is idle at room temperature, with a `usb` storage.
+#[derive(Debug, Clone)]
+pub struct FakePrinter {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl Default for FakePrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakePrinter {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FakeState {
+                state: LinkState::Idle,
+                nozzle: (23.0, 0.0),
+                bed: (23.0, 0.0),
+                job: None,
+                next_job_id: 1,
+                failures: VecDeque::new(),
+                connected: true,
+                uploads: Vec::new(),
+            })),
+        }
+    }
+
+    /// Starts printing `file`, heating up to PLA temperatures, and returns the id of the new job
+    pub fn start_print(&self, file: &str) -> JobId {
+        let mut state = self.state.lock().unwrap();
+
+        let id = JobId(state.next_job_id);
+        state.next_job_id += 1;
+        state.job = Some(FakeJob {
+            id,
+            file: file.to_string(),
+            progress: 0.0,
+        });
+        state.state = LinkState::Printing;
+        state.nozzle = (215.0, 215.0);
+        state.bed = (60.0, 60.0);
+
+        id
+    }
+
+    /// Advances the progress of the current job by `percent`,
+    /// the job finishes and the heaters turn off when it reaches 100%
+    pub fn advance(&self, percent: f32) {
+        let mut state = self.state.lock().unwrap();
+
+        let Some(job) = state.job.as_mut() else {
+            return;
+        };
+
+        job.progress = (job.progress + percent).min(100.0);
+
+        if job.progress >= 100.0 {
+            state.state = LinkState::Finished;
+            state.nozzle.1 = 0.0;
+            state.bed.1 = 0.0;
+        }
+    }
+
+    /// Sets the state of the printer, for example `LinkState::Error` or `LinkState::Attention`
+    pub fn set_state(&self, link_state: LinkState) {
+        self.state.lock().unwrap().state = link_state;
+    }
+
+    /// Sets the actual nozzle and bed temperatures
+    pub fn set_temperatures(&self, nozzle: f32, bed: f32) {
+        let mut state = self.state.lock().unwrap();
+
+        state.nozzle.0 = nozzle;
+        state.bed.0 = bed;
+    }
+
+    /// Makes the next request fail with the given error, errors are used in the order they were added.
+    ///
+    /// A `PrinterError` can be given to test how it is handled after downcasting.
+    pub fn fail_next(&self, err: impl Into<Box<dyn Error + Send + Sync>>) {
+        self.state.lock().unwrap().failures.push_back(err.into());
+    }
+
+    /// Makes every request fail as if the printer was unreachable, until `reconnect()` is called
+    pub fn disconnect(&self) {
+        self.state.lock().unwrap().connected = false;
+    }
+
+    pub fn reconnect(&self) {
+        self.state.lock().unwrap().connected = true;
+    }
+
+    /// Returns the files uploaded so far
+    pub fn uploads(&self) -> Vec<Upload> {
+        self.state.lock().unwrap().uploads.clone()
+    }
+
+    /// Returns the next injected error, or an error if the printer is disconnected
+    fn check_failure(&self) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.connected {
+            return Err("Connection refused".into());
+        }
+
+        match state.failures.pop_front() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Changes the state of the job with the given id, if the printer is in one of the `from` states
+    fn transition(
+        &self,
+        id: JobId,
+        from: &[LinkState],
+        to: LinkState,
+        verb: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        self.check_failure()?;
+
+        let mut state = self.state.lock().unwrap();
+
+        let matches = state.job.as_ref().is_some_and(|job| job.id == id);
+        if !matches || !from.contains(&state.state) {
+            return Err(format!(
+                "Failed to {} job {}, server responded with 409 Conflict",
+                verb, id
+            )
+            .into());
+        }
+
+        state.state = to;
+        Ok(())
+    }
+
+    fn job_json(state: &FakeState, job: &FakeJob) -> Value {
+        let time_printing = (FAKE_PRINT_DURATION as f32 * job.progress / 100.0) as u64;
+
+        json!({
+            "id": job.id,
+            "state": state.state.as_str(),
+            "progress": job.progress,
+            "time_remaining": FAKE_PRINT_DURATION - time_printing,
+            "time_printing": time_printing,
+            "file": {
+                "name": job.file,
+                "display_name": job.file,
+                "path": "/usb",
+                "size": null,
+                "m_timestamp": null,
+                "refs": null
+            }
+        })
+    }
+}
+
+impl PrinterApi for FakePrinter {
+    async fn get_version(&self) -> Result<String, Box<dyn Error>> {
+        self.check_failure()?;
+
+        Ok(json!({ "api": "2.0.0", "server": "2.1.2", "text": "PrusaLink" }).to_string())
+    }
+
+    async fn check_connection(&self) -> Result<(), ConnectionError> {
+        self.check_failure()
+            .map_err(|err| ConnectionError::Unreachable(err.to_string()))
+    }
+
+    async fn get_printer_info(&mut self) -> Result<RawPrinter, Box<dyn Error>> {
+        self.check_failure()?;
+
+        let state = self.state.lock().unwrap();
+
+        Ok(RawPrinter::builder()
+            .link_state(state.state.clone())
+            .nozzle_temp(state.nozzle.0, state.nozzle.1)
+            .bed_temp(state.bed.0, state.bed.1)
+            .build())
+    }
+
+    async fn get_job_info(&self) -> Result<Option<RawJob>, Box<dyn Error>> {
+        self.check_failure()?;
+
+        let state = self.state.lock().unwrap();
+
+        match &state.job {
+            Some(job) => Ok(Some(serde_json::from_value(Self::job_json(&state, job))?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_job(&self, id: JobId) -> Result<Option<RawJob>, Box<dyn Error>> {
+        let job = self.get_job_info().await?;
+
+        Ok(job.filter(|job| job.get_id() == id))
+    }
+
+    async fn pause_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.transition(id, &[LinkState::Printing], LinkState::Paused, "pause")
+    }
+
+    async fn resume_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.transition(id, &[LinkState::Paused], LinkState::Printing, "resume")
+    }
+
+    async fn continue_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.transition(id, &[LinkState::Attention], LinkState::Printing, "continue")
+    }
+
+    async fn stop_job(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        self.transition(
+            id,
+            &[LinkState::Printing, LinkState::Paused, LinkState::Attention],
+            LinkState::Stopped,
+            "stop",
+        )
+    }
+
+    async fn get_status(&self) -> Result<RawStatus, Box<dyn Error>> {
+        self.check_failure()?;
+
+        let state = self.state.lock().unwrap();
+
+        let job = state.job.as_ref().map(|job| {
+            let mut job = Self::job_json(&state, job);
+            job.as_object_mut().unwrap().remove("file");
+            job
+        });
+        let fan_hotend = if state.nozzle.0 > HOTEND_FAN_TEMP_THRESHOLD {
+            5000
+        } else {
+            0
+        };
+
+        let status = json!({
+            "job": job,
+            "printer": {
+                "state": state.state.as_str(),
+                "temp_nozzle": state.nozzle.0,
+                "target_nozzle": state.nozzle.1,
+                "temp_bed": state.bed.0,
+                "target_bed": state.bed.1,
+                "speed": 100,
+                "flow": 100,
+                "fan_hotend": fan_hotend,
+                "fan_print": 0
+            }
+        });
+
+        Ok(serde_json::from_value(status)?)
+    }
+
+    async fn get_info(&self) -> Result<RawInfo, Box<dyn Error>> {
+        self.check_failure()?;
+
+        Ok(serde_json::from_value(json!({
+            "name": "Fake printer",
+            "hostname": "fake-printer.local",
+            "nozzle_diameter": 0.4,
+            "mmu": false
+        }))?)
+    }
+
+    async fn get_storage_info(&self) -> Result<Vec<StorageInfo>, Box<dyn Error>> {
+        self.check_failure()?;
+
+        Ok(vec![StorageInfo {
+            name: "usb".to_string(),
+            kind: "USB".to_string(),
+            path: "/usb".to_string(),
+            read_only: false,
+            free_space: Some(8_000_000_000),
+            total_space: Some(16_000_000_000),
+            available: true,
+        }])
+    }
+
+    async fn get_thumbnail(
+        &self,
+        _refs: &FileRefs,
+        _size: ThumbnailSize,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        self.check_failure()?;
+
+        Ok(None)
+    }
+
+    async fn upload_file(
+        &self,
+        storage: &Storage,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.check_failure()?;
+
+        self.state.lock().unwrap().uploads.push(Upload {
+            storage: storage.clone(),
+            path: path.to_string(),
+            data,
+        });
+
+        Ok(())
+    }
+}
+
+/// A clock that only moves when `advance()` is called, clones share the same time.
+///
+/// # Example
+///
+/// ```no_run
+/// use prusa_link_rs::{test_utils::MockClock, PrinterBuilder};
+/// use std::time::Duration;
+/// # use tokio_test::block_on;
+/// # block_on(async {
+/// let clock = MockClock::new();
+/// let mut printer = PrinterBuilder::new("192.168.1.50", "api_key")
+///     .auto_refresh(Duration::from_secs(5))
+///     .clock(clock.clone())
+///     .build();
+///
+/// printer.get_nozzle_temp().await.unwrap(); // Sends a request
+/// clock.advance(Duration::from_secs(6));
+/// printer.get_nozzle_temp().await.unwrap(); // Sends another one, without waiting 6 seconds
+/// # })
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+}
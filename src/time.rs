@@ -0,0 +1,182 @@
+//! Clocks and timers that work both natively and in the browser,
+//! where `std::time::Instant` panics and there is no tokio runtime.
+
+use std::{future::Future, time::Duration};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::{Instant, SystemTime};
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime};
+
+/// Waits for the given duration
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+
+    #[cfg(target_arch = "wasm32")]
+    futures_timer::Delay::new(duration).await;
+}
+
+/// Runs `future` for at most `duration`, returning None if it didn't finish in time.
+/// `Duration::MAX` waits forever.
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    if duration == Duration::MAX {
+        return Some(future.await);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    return tokio::time::timeout(duration, future).await.ok();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let future = std::pin::pin!(future);
+
+        match futures::future::select(future, futures_timer::Delay::new(duration)).await {
+            futures::future::Either::Left((output, _)) => Some(output),
+            futures::future::Either::Right(_) => None,
+        }
+    }
+}
+
+/// The shortest period of a `Ticker`, shorter ones would poll the printer in a busy loop
+pub(crate) const MIN_PERIOD: Duration = Duration::from_millis(100);
+
+/// Ticks every period, the first tick completes right away.
+///
+/// A tick that is late delays the following ones instead of firing them in a burst.
+/// Periods shorter than `MIN_PERIOD`, including zero, are raised to it.
+pub(crate) struct Ticker {
+    #[cfg(not(target_arch = "wasm32"))]
+    interval: tokio::time::Interval,
+
+    #[cfg(target_arch = "wasm32")]
+    period: Duration,
+
+    #[cfg(target_arch = "wasm32")]
+    next: Option<Instant>,
+}
+
+impl Ticker {
+    pub(crate) fn new(period: Duration) -> Self {
+        let period = period.max(MIN_PERIOD);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            Self { interval }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        Self { period, next: None }
+    }
+
+    pub(crate) async fn tick(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.interval.tick().await;
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if let Some(next) = self.next {
+                sleep(next.saturating_duration_since(Instant::now())).await;
+            }
+
+            self.next = Some(Instant::now() + self.period);
+        }
+    }
+}
+
+/// Reads and writes a duration as whole seconds, the way the printer reports durations
+pub(crate) mod secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_secs())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Duration, D::Error> {
+        Ok(Duration::from_secs(u64::deserialize(deserializer)?))
+    }
+}
+
+/// Like `secs`, for durations the printer doesn't always report
+pub(crate) mod option_secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&duration.as_secs()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
+    }
+}
+
+/// Like `option_secs`, for durations the printer reports in milliseconds
+pub(crate) mod option_millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&(duration.as_millis() as u64)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+    }
+}
+
+/// Reads and writes a point in time as whole seconds since the unix epoch
+pub(crate) mod option_timestamp {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::SystemTime;
+
+    pub(crate) fn serialize<S: Serializer>(
+        time: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let secs = time.and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok());
+
+        match secs {
+            Some(secs) => serializer.serialize_some(&secs.as_secs()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)))
+    }
+}
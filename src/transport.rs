@@ -0,0 +1,195 @@
+//! The HTTP client the printer sends its requests with.
+//!
+//! By default requests are sent with reqwest. Any other client, or a test double,
+//! can be used by implementing `HttpTransport` and passing it to `PrinterBuilder::transport()`.
+//! Without the default `reqwest` feature a transport has to be given.
+
+use std::{error::Error, fmt, future::Future, pin::Pin};
+
+use bytes::Bytes;
+#[cfg(not(target_arch = "wasm32"))]
+use futures::Stream;
+
+/// A streamed request body, used for uploads so files don't have to be read into memory
+#[cfg(not(target_arch = "wasm32"))]
+pub type BodyStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send + Sync>>;
+
+/// The body of a request
+pub enum TransportBody {
+    Empty,
+
+    Bytes(Bytes),
+
+    /// A body of unknown size that is sent as it is read, not available on wasm
+    #[cfg(not(target_arch = "wasm32"))]
+    Stream(BodyStream),
+}
+
+impl fmt::Debug for TransportBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportBody::Empty => write!(f, "Empty"),
+            TransportBody::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+            #[cfg(not(target_arch = "wasm32"))]
+            TransportBody::Stream(_) => write!(f, "Stream"),
+        }
+    }
+}
+
+/// Why a request couldn't be completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportErrorKind {
+    /// No connection to the printer could be made
+    Connect,
+
+    /// The printer didn't answer in time
+    Timeout,
+
+    /// The request wasn't sent because the printer failed too many requests in a row,
+    /// the source is a `PrinterError::CircuitOpen`
+    CircuitOpen,
+
+    /// Anything else, for example an invalid response
+    Other,
+}
+
+/// An error of a `HttpTransport`, the kind tells `Printer::check_connection()` what went wrong
+#[derive(Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl TransportError {
+    pub fn new(kind: TransportErrorKind, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Error for TransportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl From<http::Error> for TransportError {
+    fn from(err: http::Error) -> Self {
+        TransportError::new(TransportErrorKind::Other, err)
+    }
+}
+
+/// The future returned by `HttpTransport::send()`
+#[cfg(not(target_arch = "wasm32"))]
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<http::Response<Bytes>, TransportError>> + Send + 'a>>;
+
+/// The future returned by `HttpTransport::send()`, futures in the browser aren't Send
+#[cfg(target_arch = "wasm32")]
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<http::Response<Bytes>, TransportError>> + 'a>>;
+
+/// Sends the requests of a `Printer`.
+///
+/// The response body is read completely before the future finishes.
+///
+/// # Example
+///
+/// A transport that answers every request itself, for example in tests:
+///
+/// ```rust
+/// use prusa_link_rs::{transport::*, PrinterBuilder};
+///
+/// #[derive(Debug)]
+/// struct Offline;
+///
+/// impl HttpTransport for Offline {
+///     fn send(&self, _request: http::Request<TransportBody>) -> TransportFuture<'_> {
+///         Box::pin(async { Err(TransportError::new(TransportErrorKind::Connect, "offline")) })
+///     }
+/// }
+///
+/// let printer = PrinterBuilder::new("192.168.1.50", "api_key")
+///     .transport(Offline)
+///     .build();
+/// ```
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+    fn send(&self, request: http::Request<TransportBody>) -> TransportFuture<'_>;
+}
+
+/// The default transport, sending requests with a `reqwest::Client`
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses the given client, for example one with custom timeouts or certificates
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: http::Request<TransportBody>) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let request = request.map(|body| match body {
+                TransportBody::Empty => reqwest::Body::from(Bytes::new()),
+                TransportBody::Bytes(bytes) => reqwest::Body::from(bytes),
+                #[cfg(not(target_arch = "wasm32"))]
+                TransportBody::Stream(stream) => reqwest::Body::wrap_stream(stream),
+            });
+            let request = reqwest::Request::try_from(request).map_err(reqwest_error)?;
+
+            let res = self.client.execute(request).await.map_err(reqwest_error)?;
+
+            let mut response = http::Response::builder().status(res.status());
+            if let Some(headers) = response.headers_mut() {
+                *headers = res.headers().clone();
+            }
+
+            let body = res.bytes().await.map_err(reqwest_error)?;
+
+            Ok(response.body(body)?)
+        })
+    }
+}
+
+#[cfg(feature = "reqwest")]
+fn reqwest_error(err: reqwest::Error) -> TransportError {
+    // Fetch doesn't tell why a request failed, so in the browser every failed request
+    // is taken as an unreachable host
+    #[cfg(not(target_arch = "wasm32"))]
+    let connect = err.is_connect();
+    #[cfg(target_arch = "wasm32")]
+    let connect = err.is_request();
+
+    let kind = if connect {
+        TransportErrorKind::Connect
+    } else if err.is_timeout() {
+        TransportErrorKind::Timeout
+    } else {
+        TransportErrorKind::Other
+    };
+
+    TransportError::new(kind, err)
+}
@@ -0,0 +1,152 @@
+//! Units of the values reported by the printer, so they can't be mixed up with each other.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A temperature in degrees celsius, the unit printers report every temperature in.
+///
+/// Displayed with one decimal unless a precision is given, like `215.3°C`.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::units::Celsius;
+///
+/// let nozzle = Celsius(215.0);
+///
+/// assert_eq!(nozzle.fahrenheit(), 419.0);
+/// assert_eq!(nozzle.to_string(), "215.0°C");
+/// assert_eq!(format!("{:.0}", nozzle), "215°C");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[serde(transparent)]
+pub struct Celsius(pub f32);
+
+impl Celsius {
+    pub fn from_fahrenheit(fahrenheit: f32) -> Self {
+        Self((fahrenheit - 32.0) * 5.0 / 9.0)
+    }
+
+    pub fn from_kelvin(kelvin: f32) -> Self {
+        Self(kelvin - 273.15)
+    }
+
+    /// Returns the temperature in degrees celsius as a bare number
+    pub fn value(self) -> f32 {
+        self.0
+    }
+
+    pub fn fahrenheit(self) -> f32 {
+        self.0 * 9.0 / 5.0 + 32.0
+    }
+
+    pub fn kelvin(self) -> f32 {
+        self.0 + 273.15
+    }
+}
+
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(1);
+
+        write!(f, "{:.*}°C", precision, self.0)
+    }
+}
+
+impl From<f32> for Celsius {
+    fn from(celsius: f32) -> Self {
+        Self(celsius)
+    }
+}
+
+impl From<Celsius> for f32 {
+    fn from(celsius: Celsius) -> Self {
+        celsius.0
+    }
+}
+
+impl From<Celsius> for f64 {
+    fn from(celsius: Celsius) -> Self {
+        celsius.0.into()
+    }
+}
+
+/// The actual and target temperature of a heater, read at the same time
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Temperatures {
+    pub actual: Celsius,
+
+    /// Zero when the heater is off
+    pub target: Celsius,
+}
+
+impl Temperatures {
+    /// Returns true if the heater is on and the actual temperature is more than
+    /// `tolerance` degrees away from the target
+    pub fn is_heating(&self, tolerance: f32) -> bool {
+        self.target.value() > 0.0 && (self.actual.value() - self.target.value()).abs() > tolerance
+    }
+}
+
+/// Where the actual temperature of a heater is read from, `/api/printer` reports it twice
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureSource {
+    /// The `telemetry` block, falling back to the `temperature` block if the telemetry
+    /// doesn't report the heater. This is what the getters like `RawPrinter::get_nozzle_temp()` return
+    #[default]
+    Telemetry,
+
+    /// The `temperature` block
+    Block,
+}
+
+/// A heater as reported by both blocks of `/api/printer`.
+///
+/// The firmware fills the blocks at different times, so while a heater warms up they can be
+/// a few degrees apart. The target is only in the temperature block.
+///
+/// # Example
+///
+/// ```rust
+/// use prusa_link_rs::units::{Celsius, HeaterReading, TemperatureSource, Temperatures};
+///
+/// let nozzle = HeaterReading {
+///     block: Temperatures { actual: Celsius(180.0), target: Celsius(215.0) },
+///     telemetry: Some(Celsius(183.5)),
+/// };
+///
+/// assert_eq!(nozzle.resolve(TemperatureSource::Telemetry).actual, Celsius(183.5));
+/// assert_eq!(nozzle.resolve(TemperatureSource::Block).actual, Celsius(180.0));
+/// assert_eq!(nozzle.disagreement(), Some(3.5));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct HeaterReading {
+    /// The actual and target temperature from the `temperature` block
+    pub block: Temperatures,
+
+    /// The actual temperature from the `telemetry` block, None if it doesn't report the heater
+    pub telemetry: Option<Celsius>,
+}
+
+impl HeaterReading {
+    /// Returns the actual temperature from the given source and the target from the temperature block
+    pub fn resolve(&self, source: TemperatureSource) -> Temperatures {
+        let actual = match source {
+            TemperatureSource::Telemetry => self.telemetry.unwrap_or(self.block.actual),
+            TemperatureSource::Block => self.block.actual,
+        };
+
+        Temperatures {
+            actual,
+            target: self.block.target,
+        }
+    }
+
+    /// Returns how many degrees the two blocks are apart, None if the telemetry doesn't report the heater
+    pub fn disagreement(&self) -> Option<f32> {
+        self.telemetry
+            .map(|telemetry| (telemetry.value() - self.block.actual.value()).abs())
+    }
+}
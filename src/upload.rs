@@ -0,0 +1,816 @@
+use std::{
+    error::Error,
+    future::Future,
+    path::Path,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    error::PrinterError,
+    path::files_url,
+    raw_job::RawJob,
+    retry,
+    storage::Storage,
+    time::{self, Instant},
+    transport::TransportBody,
+    Printer,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::transport::BodyStream;
+
+/// Size of the chunks the upload body is split into, progress is reported after each chunk
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Magic bytes every binary g-code file starts with
+const BGCODE_MAGIC: &[u8; 4] = b"GCDE";
+
+/// Size of the binary g-code file header: the magic bytes, the version and the checksum type
+const BGCODE_HEADER_SIZE: usize = 10;
+
+/// How long `upload_and_print()` waits for the job to show up after the upload
+const JOB_START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the content of an upload comes from
+pub enum UploadSource {
+    /// The whole file in memory
+    Bytes(Vec<u8>),
+
+    /// A file on the local filesystem, which is streamed from disk. Not available on wasm
+    #[cfg(not(target_arch = "wasm32"))]
+    File(std::path::PathBuf),
+
+    /// A reader returning exactly `length` bytes
+    Reader {
+        reader: Box<dyn AsyncRead + Unpin + Send>,
+        length: u64,
+    },
+}
+
+impl UploadSource {
+    /// Turns the source into a reader and the number of bytes it will return
+    async fn open(self) -> std::io::Result<(Box<dyn AsyncRead + Unpin + Send>, u64)> {
+        Ok(match self {
+            UploadSource::Bytes(data) => {
+                let length = data.len() as u64;
+                (Box::new(std::io::Cursor::new(data)), length)
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            UploadSource::File(path) => {
+                let file = tokio::fs::File::open(path).await?;
+                let length = file.metadata().await?.len();
+                (Box::new(file), length)
+            }
+            UploadSource::Reader { reader, length } => (reader, length),
+        })
+    }
+}
+
+impl From<Vec<u8>> for UploadSource {
+    fn from(data: Vec<u8>) -> Self {
+        UploadSource::Bytes(data)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<std::path::PathBuf> for UploadSource {
+    fn from(path: std::path::PathBuf) -> Self {
+        UploadSource::File(path)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<&Path> for UploadSource {
+    fn from(path: &Path) -> Self {
+        UploadSource::File(path.to_path_buf())
+    }
+}
+
+impl Printer {
+    /// Uploads a file to the given storage of the printer, for example `usb` or `local`.
+    ///
+    /// `path` is the path of the file on the storage, directories that don't exist yet are created.
+    ///
+    /// # Errors
+    ///
+    /// If the printer doesn't accept the file, for example because it already exists,
+    /// the function will return an Err with the status and the message of the printer.
+    ///
+    /// Binary g-code (`.bgcode`) files are checked before they are sent, and a
+    /// `PrinterError::InvalidFile` is returned if they don't have a valid header.
+    ///
+    /// If the file doesn't fit on the storage, a `PrinterError::InsufficientStorage` is returned
+    /// without sending the file. The check is best-effort, it is skipped if the printer doesn't have
+    /// the storage endpoint or doesn't report the free space, other errors reading the storages are returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let gcode = std::fs::read("benchy.gcode").unwrap();
+    /// printer.upload_file("usb", "prints/benchy.gcode", gcode).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn upload_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.upload_file_with_progress(storage, path, data, |_, _| {})
+            .await
+    }
+
+    /// Same as `upload_file()`, but calls `progress` with the number of bytes sent
+    /// and the total number of bytes every time a chunk of the file has been handed to the connection.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let gcode = std::fs::read("benchy.gcode").unwrap();
+    /// printer
+    ///     .upload_file_with_progress("usb", "benchy.gcode", gcode, |sent, total| {
+    ///         println!("{}%", sent * 100 / total);
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn upload_file_with_progress<F>(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        data: Vec<u8>,
+        progress: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(u64, u64) + Send + 'static,
+    {
+        self.upload_bytes(&storage.into(), path, data, false, progress)
+            .await
+    }
+
+    /// Uploads a file to the printer, streaming its content from `reader` instead of
+    /// holding the whole file in memory.
+    ///
+    /// The printer needs to know the size of the file up front, so `length` has to be
+    /// the exact number of bytes the reader will return.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if reading fails or the printer doesn't accept the file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let file = tokio::fs::File::open("benchy.bgcode").await.unwrap();
+    /// let length = file.metadata().await.unwrap().len();
+    ///
+    /// printer.upload_reader("usb", "benchy.bgcode", file, length).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn upload_reader<R>(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        reader: R,
+        length: u64,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        self.upload_reader_with_progress(storage, path, reader, length, |_, _| {})
+            .await
+    }
+
+    /// Same as `upload_reader()`, with a progress callback like `upload_file_with_progress()`
+    pub async fn upload_reader_with_progress<R, F>(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        reader: R,
+        length: u64,
+        progress: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        F: FnMut(u64, u64) + Send + 'static,
+    {
+        self.upload_checked_reader(&storage.into(), path, reader, length, false, progress)
+            .await
+    }
+
+    /// Uploads a file from the local filesystem, streaming it from disk. Not available on wasm
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the local file can't be read or the printer doesn't accept the file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn upload_local_file(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        local_path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn Error>> {
+        let source = UploadSource::from(local_path.as_ref());
+
+        self.upload_source(&storage.into(), path, source, false)
+            .await
+    }
+
+    /// Uploads a file and starts printing it as soon as the transfer is complete,
+    /// returning the job of the new print.
+    ///
+    /// The printer keeps reporting the previous job until the new one starts, so only a job
+    /// for the uploaded file or with another id than the job before the upload is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the upload fails, or a `PrinterError::Timeout` if the printer
+    /// doesn't report the new job shortly after the upload, for example because it was busy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use prusa_link_rs::PrinterBuilder;
+    /// # use std::path::Path;
+    /// # use tokio_test::block_on;
+    /// # block_on(async {
+    /// let printer = PrinterBuilder::new("address".to_string(), "api_key".to_string())
+    ///     .build();
+    ///
+    /// let job = printer
+    ///     .upload_and_print("usb", "benchy.bgcode", Path::new("benchy.bgcode"))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// println!("Started job {}", job.get_id());
+    /// # })
+    /// ```
+    pub async fn upload_and_print(
+        &self,
+        storage: impl Into<Storage>,
+        path: &str,
+        source: impl Into<UploadSource>,
+    ) -> Result<RawJob, Box<dyn Error>> {
+        let storage = storage.into();
+        let _lock = self.lock_commands(&format!("print {}", path)).await?;
+
+        let previous = self.get_job_info().await?.map(|job| job.get_id());
+        self.upload_source(&storage, path, source.into(), true)
+            .await?;
+        self.invalidate_cache();
+
+        let started = Instant::now();
+        loop {
+            if let Some(job) = self.get_job_info().await? {
+                // A reprint of the same file can keep the id of the finished job
+                let is_new = Some(job.get_id()) != previous
+                    || (job.is_active()
+                        && job
+                            .get_file()
+                            .is_some_and(|file| file.is_at(&storage, path)));
+
+                if is_new {
+                    return Ok(job);
+                }
+            }
+
+            if started.elapsed() > JOB_START_TIMEOUT {
+                return Err(PrinterError::Timeout(JOB_START_TIMEOUT).into());
+            }
+
+            time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Uploads a file held in memory, retrying following `PrinterBuilder::retry_uploads()`
+    async fn upload_bytes<F>(
+        &self,
+        storage: &Storage,
+        path: &str,
+        data: Vec<u8>,
+        print_after_upload: bool,
+        progress: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(u64, u64) + Send + 'static,
+    {
+        if is_bgcode(path) {
+            validate_bgcode_header(&data)?;
+        }
+
+        let data = Bytes::from(data);
+        let total = data.len() as u64;
+        let progress = Arc::new(Mutex::new(progress));
+
+        self.retry_upload(storage, path, |started| {
+            let data = data.clone();
+            let chunks = (0..data.len())
+                .step_by(UPLOAD_CHUNK_SIZE)
+                .map(move |start| {
+                    Ok(data.slice(start..(start + UPLOAD_CHUNK_SIZE).min(data.len())))
+                });
+            let progress = progress.clone();
+
+            async move {
+                self.check_upload(storage, total).await?;
+                started.store(true, Ordering::Relaxed);
+
+                self.upload_stream(
+                    storage,
+                    path,
+                    stream::iter(chunks),
+                    total,
+                    print_after_upload,
+                    move |sent, total| (progress.lock().unwrap())(sent, total),
+                )
+                .await
+            }
+        })
+        .await
+    }
+
+    /// Uploads a file from any source, retrying following `PrinterBuilder::retry_uploads()`
+    /// unless the source is a reader, which can only be read once
+    pub(crate) async fn upload_source(
+        &self,
+        storage: &Storage,
+        path: &str,
+        source: UploadSource,
+        print_after_upload: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        match source {
+            UploadSource::Bytes(data) => {
+                self.upload_bytes(storage, path, data, print_after_upload, |_, _| {})
+                    .await
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            UploadSource::File(local_path) => {
+                self.retry_upload(storage, path, |started| {
+                    let local_path = local_path.clone();
+
+                    async move {
+                        let (reader, length) = UploadSource::File(local_path).open().await?;
+                        let reader = checked_reader(path, reader).await?;
+
+                        self.check_upload(storage, length).await?;
+                        started.store(true, Ordering::Relaxed);
+
+                        self.upload_stream(
+                            storage,
+                            path,
+                            reader_body(reader),
+                            length,
+                            print_after_upload,
+                            |_, _| {},
+                        )
+                        .await
+                    }
+                })
+                .await
+            }
+            UploadSource::Reader { reader, length } => {
+                self.upload_checked_reader(
+                    storage,
+                    path,
+                    reader,
+                    length,
+                    print_after_upload,
+                    |_, _| {},
+                )
+                .await
+            }
+        }
+    }
+
+    /// Runs `upload` until it succeeds, as often as `PrinterBuilder::retry_uploads()` allows.
+    ///
+    /// After an attempt that failed during the transfer, the partial file is deleted from the printer
+    /// so it doesn't stay behind and the next attempt isn't refused because the file already exists.
+    /// `upload` sets the flag it is given right before the upload request is sent, an attempt that
+    /// failed before that can't have created a file and nothing is deleted.
+    ///
+    /// A file that already existed before the first attempt is never deleted, the printer refuses to
+    /// overwrite it so the failed attempt can't have changed it. If it can't be told whether the file
+    /// existed, nothing is deleted either.
+    async fn retry_upload<U, Fut>(
+        &self,
+        storage: &Storage,
+        path: &str,
+        mut upload: U,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        U: FnMut(Arc<AtomicBool>) -> Fut,
+        Fut: Future<Output = Result<(), Box<dyn Error>>>,
+    {
+        let mut attempt = 1;
+
+        let existed = match self.upload_retry {
+            Some(_) => self
+                .find_file(storage, path)
+                .await
+                .map_or(true, |file| file.is_some()),
+            None => true,
+        };
+
+        loop {
+            let started = Arc::new(AtomicBool::new(false));
+            let err = match upload(started.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+
+            let Some(policy) = self.upload_retry else {
+                return Err(err);
+            };
+
+            if !retry::is_retryable(err.as_ref()) {
+                return Err(err);
+            }
+
+            // The file may not have been created at all, so a failed delete is expected
+            if started.load(Ordering::Relaxed) && !existed {
+                let _ = self.delete_file(storage, path).await;
+            }
+
+            if attempt >= policy.max_attempts {
+                return Err(err);
+            }
+
+            time::sleep(policy.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Validates the file if it is a binary g-code file and uploads it from `reader`
+    async fn upload_checked_reader<R, F>(
+        &self,
+        storage: &Storage,
+        path: &str,
+        reader: R,
+        length: u64,
+        print_after_upload: bool,
+        progress: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        F: FnMut(u64, u64) + Send + 'static,
+    {
+        let reader = checked_reader(path, reader).await?;
+        self.check_upload(storage, length).await?;
+
+        self.upload_stream(
+            storage,
+            path,
+            reader_body(reader),
+            length,
+            print_after_upload,
+            progress,
+        )
+        .await
+    }
+
+    /// Checks that the printer accepts uploads and that `total` bytes fit on the storage,
+    /// before the upload request is sent
+    async fn check_upload(&self, storage: &Storage, total: u64) -> Result<(), Box<dyn Error>> {
+        self.require(|capabilities| capabilities.upload_by_put, "uploads")
+            .await?;
+        self.ensure_free_space(storage, total).await
+    }
+
+    /// Uploads the chunks of `body`, calling `progress` after every chunk.
+    /// The upload has to be checked with `check_upload()` first.
+    async fn upload_stream<S, F>(
+        &self,
+        storage: &Storage,
+        path: &str,
+        body: S,
+        total: u64,
+        print_after_upload: bool,
+        progress: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        S: Stream<Item = std::io::Result<Bytes>> + Send + Sync + 'static,
+        F: FnMut(u64, u64) + Send + 'static,
+    {
+        let url = files_url(storage, path)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let body: BodyStream = match self.upload_throttle {
+            Some(bytes_per_sec) => Box::pin(throttle(body, bytes_per_sec)),
+            None => Box::pin(body),
+        };
+
+        // The body stream has to be Sync, which a FnMut isn't on its own
+        let progress = Mutex::new(progress);
+        let mut sent = 0;
+
+        let body = body.map(move |chunk| {
+            if let Ok(chunk) = &chunk {
+                sent += chunk.len() as u64;
+                (progress.lock().unwrap())(sent, total);
+            }
+
+            chunk
+        });
+
+        // Fetch can't stream request bodies, so in the browser the file is collected first
+        // and the progress only tells how much of it has been read
+        #[cfg(target_arch = "wasm32")]
+        let body = {
+            let chunks = body.collect::<Vec<_>>().await;
+            let chunks = chunks.into_iter().collect::<std::io::Result<Vec<_>>>()?;
+
+            TransportBody::Bytes(chunks.concat().into())
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let body = TransportBody::Stream(Box::pin(body));
+
+        self.upload_body(&url, path, body, total, print_after_upload)
+            .await?;
+
+        if self.verify_uploads {
+            self.verify_upload(storage, path, total).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the file on the printer has the size of the uploaded file,
+    /// see `PrinterBuilder::verify_uploads()`
+    async fn verify_upload(
+        &self,
+        storage: &Storage,
+        path: &str,
+        expected: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let found = match self.find_file(storage, path).await? {
+            // Without a size there is nothing to compare
+            Some(file) if file.size.is_none() => return Ok(()),
+            Some(file) => file.size,
+            None => None,
+        };
+
+        if found == Some(expected) {
+            return Ok(());
+        }
+
+        Err(PrinterError::UploadVerificationFailed {
+            path: path.to_string(),
+            expected,
+            found,
+        }
+        .into())
+    }
+
+    /// Checks that `needed` bytes fit on the storage before starting a long transfer.
+    ///
+    /// The check is skipped if the printer doesn't have the storage endpoint or doesn't report
+    /// the free space of the storage, the printer will still refuse the file in that case.
+    /// Any other error reading the storages is returned.
+    async fn ensure_free_space(
+        &self,
+        storage: &Storage,
+        needed: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let storages = match self.get_storage_info().await {
+            Ok(storages) => storages,
+            Err(err) if matches!(err.downcast_ref(), Some(PrinterError::Unsupported(_))) => {
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+
+        let available = storages
+            .iter()
+            .find(|info| info.matches(storage))
+            .and_then(|info| info.free_space);
+
+        match available {
+            Some(available) if needed > available => {
+                Err(PrinterError::InsufficientStorage { needed, available }.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sends the upload request with the given body to `url`, as returned by `files_url()`
+    async fn upload_body(
+        &self,
+        url: &str,
+        path: &str,
+        body: TransportBody,
+        length: u64,
+        print_after_upload: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let res = self
+            .send_body(
+                self.request(http::Method::PUT, url)
+                    .header(http::header::CONTENT_LENGTH, length)
+                    .header(http::header::CONTENT_TYPE, content_type(path))
+                    .header(
+                        "Print-After-Upload",
+                        if print_after_upload { "?1" } else { "?0" },
+                    ),
+                body,
+            )
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let message = String::from_utf8_lossy(res.body()).into_owned();
+
+            return Err(match upload_error(status, path, message) {
+                Ok(err) => err.into(),
+                Err(message) => format!(
+                    "Failed to upload the file, server responded with {}: {}",
+                    status, message
+                )
+                .into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps the statuses the printer refuses uploads with to a `PrinterError`,
+/// returns the message back for any other status
+fn upload_error(
+    status: http::StatusCode,
+    path: &str,
+    message: String,
+) -> Result<PrinterError, String> {
+    let path = path.to_string();
+
+    match status {
+        // The printer answers both with a conflict, only the message tells them apart
+        http::StatusCode::CONFLICT
+            if ["print", "busy"]
+                .iter()
+                .any(|word| message.to_lowercase().contains(word)) =>
+        {
+            Ok(PrinterError::FileInUse { path, message })
+        }
+        http::StatusCode::CONFLICT => Ok(PrinterError::FileExists { path, message }),
+        http::StatusCode::PAYLOAD_TOO_LARGE => Ok(PrinterError::FileTooLarge { path, message }),
+        _ => Err(message),
+    }
+}
+
+/// Returns true if the file at `path` is a binary g-code file, judging by its extension
+pub fn is_bgcode(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("bgcode"))
+}
+
+/// Returns the content type the printer expects for the file at `path`
+pub fn content_type(path: &str) -> &'static str {
+    if is_bgcode(path) || crate::firmware::is_firmware(path) {
+        "application/octet-stream"
+    } else {
+        "text/x.gcode"
+    }
+}
+
+/// Checks that `header` is the start of a valid binary g-code file,
+/// so obviously corrupted files are rejected before they are sent to the printer.
+///
+/// # Errors
+///
+/// Returns a `PrinterError::InvalidFile` if the magic bytes are missing or
+/// the version or checksum type is unknown.
+pub fn validate_bgcode_header(header: &[u8]) -> Result<(), PrinterError> {
+    if header.len() < BGCODE_HEADER_SIZE || &header[..4] != BGCODE_MAGIC {
+        return Err(PrinterError::InvalidFile(
+            "the file doesn't start with the bgcode magic bytes".to_string(),
+        ));
+    }
+
+    let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if version != 1 {
+        return Err(PrinterError::InvalidFile(format!(
+            "unsupported bgcode version {}",
+            version
+        )));
+    }
+
+    // 0 is no checksum and 1 is CRC32
+    let checksum_type = u16::from_le_bytes([header[8], header[9]]);
+    if checksum_type > 1 {
+        return Err(PrinterError::InvalidFile(format!(
+            "unknown bgcode checksum type {}",
+            checksum_type
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validates the header of `reader` if `path` is a binary g-code file,
+/// returning a reader that still starts at the beginning of the file
+async fn checked_reader<R>(
+    path: &str,
+    mut reader: R,
+) -> Result<Box<dyn AsyncRead + Unpin + Send>, Box<dyn Error>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    if !is_bgcode(path) {
+        return Ok(Box::new(reader));
+    }
+
+    let mut header = [0; BGCODE_HEADER_SIZE];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => validate_bgcode_header(&header)?,
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+            return Err(PrinterError::InvalidFile("the file is too short".to_string()).into())
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    Ok(Box::new(std::io::Cursor::new(header).chain(reader)))
+}
+
+/// Returns the chunks of `reader` as an upload body
+fn reader_body(
+    reader: Box<dyn AsyncRead + Unpin + Send>,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send + Sync {
+    ReaderStream::with_capacity(SyncReader(Mutex::new(reader)), UPLOAD_CHUNK_SIZE)
+}
+
+/// Makes any `Send` reader `Sync`, which reqwest requires for streamed bodies.
+///
+/// The reader is only ever accessed through `&mut`, so the mutex is never actually locked.
+struct SyncReader<R>(Mutex<R>);
+
+impl<R: AsyncRead + Unpin> AsyncRead for SyncReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let reader = self.get_mut().0.get_mut().unwrap();
+
+        Pin::new(reader).poll_read(cx, buf)
+    }
+}
+
+/// Holds back the chunks of `body` so that on average no more than `bytes_per_sec` bytes
+/// are handed to the connection per second, see `PrinterBuilder::throttle_uploads()`
+#[cfg(not(target_arch = "wasm32"))]
+fn throttle<S>(
+    body: S,
+    bytes_per_sec: u64,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send + Sync
+where
+    S: Stream<Item = std::io::Result<Bytes>> + Send + Sync,
+{
+    let started = Instant::now();
+    let mut sent = 0;
+
+    body.then(move |chunk| {
+        if let Ok(chunk) = &chunk {
+            sent += chunk.len() as u64;
+        }
+
+        let due = Duration::from_secs_f64(sent as f64 / bytes_per_sec.max(1) as f64);
+        let wait = due.saturating_sub(started.elapsed());
+
+        async move {
+            time::sleep(wait).await;
+            chunk
+        }
+    })
+}
@@ -0,0 +1,179 @@
+//! Serving the state of printers from an axum application, enabled with the `axum` feature.
+//!
+//! `router()` and `farm_router()` return routers with two json endpoints, which can be nested
+//! into a dashboard or served on their own:
+//!
+//! - `GET /status` with the state, temperatures and progress, see `PrinterStatus`
+//! - `GET /snapshot` with the printer and job information as PrusaLink sends them, see `PrinterSnapshot`
+//!
+//! A `PrinterHandle` can also be taken directly as an argument by handlers of an application
+//! whose state contains one, with `FromRef`.
+//!
+//! ```no_run
+//! use prusa_link_rs::{web, PrinterBuilder};
+//! # use tokio_test::block_on;
+//! # block_on(async {
+//!
+//! let printer = PrinterBuilder::new("192.168.1.50", "api_key").build_handle();
+//! let app = axum::Router::new().nest("/printer", web::router(printer));
+//!
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+//! axum::serve(listener, app).await.unwrap();
+//! # })
+//! ```
+
+#[cfg(target_arch = "wasm32")]
+compile_error!("the axum feature is not available on wasm");
+
+use std::{collections::BTreeMap, convert::Infallible, error::Error, sync::Arc};
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, State},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::{
+    error::PrinterError,
+    farm::{Farm, PrinterSnapshot},
+    handle::PrinterHandle,
+    units::{TemperatureSource, Temperatures},
+    Printer,
+};
+
+/// A farm shared between the routes of `farm_router()` and the task refreshing it
+pub type SharedFarm = Arc<RwLock<Farm>>;
+
+/// The body of `GET /status`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PrinterStatus {
+    /// The link state, like `PRINTING`
+    pub state: String,
+    pub printing: bool,
+    pub nozzle: Temperatures,
+    pub bed: Temperatures,
+
+    /// The progress of the current job in percent, None without a job
+    pub progress: Option<f32>,
+}
+
+impl From<&PrinterSnapshot> for PrinterStatus {
+    fn from(snapshot: &PrinterSnapshot) -> Self {
+        let printer = &snapshot.printer;
+        let temperatures = printer.temperatures();
+
+        Self {
+            state: printer.link_state().to_string(),
+            printing: printer.get_printing(),
+            nozzle: temperatures.nozzle.resolve(TemperatureSource::Telemetry),
+            bed: temperatures.bed.resolve(TemperatureSource::Telemetry),
+            progress: snapshot.job.as_ref().map(|job| job.get_progress()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PrinterHandle
+where
+    PrinterHandle: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(PrinterHandle::from_ref(state))
+    }
+}
+
+/// Returns a router serving the state of one printer.
+///
+/// The printer information is refreshed following the auto refresh rules of the builder,
+/// so frequent requests don't reach the printer more often than `PrinterBuilder::auto_refresh()` allows.
+/// If the printer can't be refreshed, the endpoints answer with `502 Bad Gateway` and the error.
+pub fn router(printer: PrinterHandle) -> Router {
+    Router::new()
+        .route("/status", get(printer_status))
+        .route("/snapshot", get(printer_snapshot))
+        .with_state(printer)
+}
+
+/// Returns a router serving the state of every printer of a farm, keyed by name.
+///
+/// The farm isn't refreshed by the routes, refresh it with `Farm::refresh_all()` in a background task.
+/// Printers that haven't been refreshed successfully yet are left out.
+pub fn farm_router(farm: SharedFarm) -> Router {
+    Router::new()
+        .route("/status", get(farm_status))
+        .route("/snapshot", get(farm_snapshot))
+        .with_state(farm)
+}
+
+async fn printer_status(printer: PrinterHandle) -> Result<Json<PrinterStatus>, ErrorResponse> {
+    let snapshot = snapshot(&mut *printer.write().await).await?;
+
+    Ok(Json(PrinterStatus::from(&snapshot)))
+}
+
+async fn printer_snapshot(printer: PrinterHandle) -> Result<Json<PrinterSnapshot>, ErrorResponse> {
+    Ok(Json(snapshot(&mut *printer.write().await).await?))
+}
+
+async fn farm_status(State(farm): State<SharedFarm>) -> Json<BTreeMap<String, PrinterStatus>> {
+    let farm = farm.read().await;
+
+    Json(
+        farm.snapshots()
+            .iter()
+            .map(|(name, snapshot)| (name.to_string(), PrinterStatus::from(snapshot)))
+            .collect(),
+    )
+}
+
+async fn farm_snapshot(State(farm): State<SharedFarm>) -> Json<BTreeMap<String, PrinterSnapshot>> {
+    let farm = farm.read().await;
+
+    Json(
+        farm.snapshots()
+            .into_iter()
+            .map(|(name, snapshot)| (name.to_string(), snapshot))
+            .collect(),
+    )
+}
+
+/// Returns the printer and job information, refreshed if it is outdated.
+/// Printers without the job endpoint are returned without a job
+async fn snapshot(printer: &mut Printer) -> Result<PrinterSnapshot, Box<dyn Error>> {
+    printer.refresh_if_necessary().await?;
+
+    match printer.refresh_job_if_necessary().await {
+        Err(err) if matches!(err.downcast_ref(), Some(PrinterError::Unsupported(_))) => {}
+        result => result?,
+    }
+
+    Ok(PrinterSnapshot {
+        printer: printer.printer.clone().unwrap(),
+        job: printer.job.clone(),
+    })
+}
+
+/// A refresh that failed, answered with `502 Bad Gateway`
+struct ErrorResponse(String);
+
+impl From<Box<dyn Error>> for ErrorResponse {
+    fn from(err: Box<dyn Error>) -> Self {
+        Self(err.to_string())
+    }
+}
+
+impl IntoResponse for ErrorResponse {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "error": self.0 });
+
+        (StatusCode::BAD_GATEWAY, Json(body)).into_response()
+    }
+}
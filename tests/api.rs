@@ -0,0 +1,225 @@
+use std::error::Error;
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    api::PrinterApi,
+    error::ConnectionError,
+    farm::PrinterSnapshot,
+    raw_info::RawInfo,
+    raw_job::{FileRefs, JobId, RawJob, ThumbnailSize},
+    raw_printer::{LinkState, PrinterFlags, PrinterTelemetry, RawPrinter, Temp},
+    raw_status::RawStatus,
+    raw_storage::StorageInfo,
+    storage::Storage,
+    units::{Celsius, TemperatureSource, Temperatures},
+};
+use tokio::test;
+
+/// A hand-written fake implementing only the endpoints, the getters come from the trait
+struct Idle;
+
+impl PrinterApi for Idle {
+    async fn get_version(&self) -> Result<String, Box<dyn Error>> {
+        Ok("2.1.2".to_string())
+    }
+
+    async fn check_connection(&self) -> Result<(), ConnectionError> {
+        Ok(())
+    }
+
+    async fn get_printer_info(&mut self) -> Result<RawPrinter, Box<dyn Error>> {
+        Ok(RawPrinter::builder()
+            .nozzle_temp(215.0, 215.0)
+            .bed_temp(60.0, 60.0)
+            .build())
+    }
+
+    async fn get_job_info(&self) -> Result<Option<RawJob>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    async fn get_job(&self, _id: JobId) -> Result<Option<RawJob>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    async fn pause_job(&self, _id: JobId) -> Result<(), Box<dyn Error>> {
+        Err("Not printing".into())
+    }
+
+    async fn resume_job(&self, _id: JobId) -> Result<(), Box<dyn Error>> {
+        Err("Not printing".into())
+    }
+
+    async fn continue_job(&self, _id: JobId) -> Result<(), Box<dyn Error>> {
+        Err("Not printing".into())
+    }
+
+    async fn stop_job(&self, _id: JobId) -> Result<(), Box<dyn Error>> {
+        Err("Not printing".into())
+    }
+
+    async fn get_status(&self) -> Result<RawStatus, Box<dyn Error>> {
+        Err("Not implemented".into())
+    }
+
+    async fn get_info(&self) -> Result<RawInfo, Box<dyn Error>> {
+        Err("Not implemented".into())
+    }
+
+    async fn get_storage_info(&self) -> Result<Vec<StorageInfo>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_thumbnail(
+        &self,
+        _refs: &FileRefs,
+        _size: ThumbnailSize,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    async fn upload_file(
+        &self,
+        _storage: &Storage,
+        _path: &str,
+        _data: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+/// Code written against the trait, as an application would
+async fn summary(printer: &mut impl PrinterApi) -> Result<String, Box<dyn Error>> {
+    if printer.is_printing().await? {
+        let progress = printer.progress_percent().await?.unwrap_or_default();
+
+        return Ok(format!("Printing, {:.0}% done", progress));
+    }
+
+    Ok(format!(
+        "Idle: {}, nozzle at {:.0}",
+        printer.is_idle().await?,
+        printer.get_nozzle_temp().await?
+    ))
+}
+
+#[test]
+async fn getters_use_the_endpoints() {
+    let mut printer = Idle;
+
+    assert_eq!(
+        summary(&mut printer).await.unwrap(),
+        "Idle: true, nozzle at 215°C"
+    );
+    assert_eq!(printer.get_bed_temp().await.unwrap(), Celsius(60.0));
+    assert!(!printer.has_error().await.unwrap());
+    assert_eq!(printer.time_remaining().await.unwrap(), None);
+    assert!(printer.fan_speeds().await.is_err());
+}
+
+#[test]
+async fn raw_printer_builder() {
+    let printer = RawPrinter::builder()
+        .link_state(LinkState::Paused)
+        .tools(vec![
+            Temp {
+                actual: 215.0,
+                target: 215.0,
+            },
+            Temp {
+                actual: 23.0,
+                target: 0.0,
+            },
+        ])
+        .bed_temp(60.0, 60.0)
+        .chamber_temp(38.0, 40.0)
+        .build();
+
+    assert_eq!(printer.link_state(), LinkState::Paused);
+    assert!(printer.get_paused() && !printer.is_idle());
+    assert_eq!(printer.tool_count(), 2);
+    assert_eq!(printer.get_tool_temp(1), Some(Celsius(23.0)));
+    assert_eq!(printer.get_target_bed_temp(), Celsius(60.0));
+    assert_eq!(printer.get_chamber_temp(), Some(Celsius(38.0)));
+    assert_eq!(printer.get_target_chamber_temp(), Some(Celsius(40.0)));
+    assert_eq!(
+        printer.flags(),
+        &PrinterFlags::for_state(&LinkState::Paused)
+    );
+
+    // A built printer reads back the same as the printer would send it
+    let json = serde_json::to_string(&printer).unwrap();
+    assert_eq!(serde_json::from_str::<RawPrinter>(&json).unwrap(), printer);
+}
+
+#[test]
+async fn temperature_view_exposes_both_readings() {
+    let printer = RawPrinter::builder()
+        .nozzle_temp(180.0, 215.0)
+        .bed_temp(60.0, 60.0)
+        .telemetry(PrinterTelemetry {
+            nozzle_temp: Some(183.5),
+            chamber_temp: Some(31.0),
+            ..Default::default()
+        })
+        .build();
+
+    let view = printer.temperatures();
+
+    assert_eq!(
+        view.nozzle.resolve(TemperatureSource::Telemetry),
+        Temperatures {
+            actual: Celsius(183.5),
+            target: Celsius(215.0),
+        }
+    );
+    assert_eq!(
+        view.nozzle.resolve(TemperatureSource::Block).actual,
+        Celsius(180.0)
+    );
+    assert_eq!(view.nozzle.disagreement(), Some(3.5));
+
+    // The getters resolve with the telemetry
+    assert_eq!(
+        view.nozzle.resolve(TemperatureSource::default()).actual,
+        printer.get_nozzle_temp()
+    );
+
+    // Without a telemetry reading both sources agree
+    assert_eq!(view.bed.disagreement(), None);
+    assert_eq!(
+        view.bed.resolve(TemperatureSource::Telemetry),
+        view.bed.resolve(TemperatureSource::Block)
+    );
+
+    // A chamber only in the telemetry has no target
+    let chamber = view.chamber.unwrap();
+    assert_eq!(chamber.block.actual, Celsius(31.0));
+    assert_eq!(chamber.block.target, Celsius(0.0));
+
+    assert_eq!(view.tools.len(), 1);
+    assert_eq!(view.max_disagreement(), Some(3.5));
+}
+
+#[test]
+async fn snapshot_to_json() {
+    let snapshot = PrinterSnapshot {
+        printer: RawPrinter::builder()
+            .link_state(LinkState::Printing)
+            .build(),
+        job: None,
+    };
+
+    let json = snapshot.to_json().unwrap();
+    assert!(!json.contains('\n'));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["printer"]["state"]["flags"]["link_state"], "PRINTING");
+    assert_eq!(value["job"], serde_json::Value::Null);
+
+    let pretty = snapshot.to_pretty_json().unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+        value
+    );
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "blocking")]
+
+use prusa_link_rs::PrinterBuilder;
+
+#[test]
+fn blocking_status() {
+    let mut server = mockito::Server::new();
+    let status_mock = server
+        .mock("GET", "/api/v1/status")
+        .match_header("X-Api-Key", "1234567890")
+        .with_status(200)
+        .with_body(r#"{"printer": {"state": "IDLE", "temp_nozzle": 24.5, "temp_bed": 23.9}}"#)
+        .create();
+
+    let printer = PrinterBuilder::new(server.url(), "1234567890")
+        .build_blocking()
+        .unwrap();
+
+    let status = printer.get_status().unwrap();
+    assert_eq!(status.printer().temp_nozzle, Some(24.5));
+
+    status_mock.assert();
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "toml")]
+
+use prusa_link_rs::{
+    compat::Compatibility,
+    config::{ConfigError, FarmConfig},
+    error::BuildError,
+};
+
+#[test]
+fn farm_from_toml() {
+    let config = FarmConfig::from_toml(
+        r#"
+parallelism = 2
+
+[printers.mk4-a]
+address = "192.168.1.50"
+api_key = "key"
+refresh = 5
+
+[printers.mini]
+address = "http://192.168.1.51:8080"
+api_key = "other_key"
+compatibility = "octoprint"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.parallelism, Some(2));
+    assert_eq!(
+        config.printers["mini"].compatibility,
+        Compatibility::OctoPrint
+    );
+
+    let farm = config.build().unwrap();
+    assert_eq!(farm.names().collect::<Vec<_>>(), vec!["mini", "mk4-a"]);
+    assert_eq!(farm.get("mini").unwrap().to_config().port, Some(8080));
+}
+
+#[test]
+fn invalid_printer_is_named() {
+    let config = FarmConfig::from_toml(
+        r#"
+[printers.mk4]
+address = "192.168.1.50"
+api_key = ""
+"#,
+    )
+    .unwrap();
+
+    match config.build() {
+        Err(ConfigError::Printer { name, err }) => {
+            assert_eq!(name, "mk4");
+            assert_eq!(err, BuildError::EmptyApiKey);
+        }
+        other => panic!("expected a printer error, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn missing_api_key_is_a_parse_error() {
+    let err = FarmConfig::from_toml("[printers.mk4]\naddress = \"192.168.1.50\"\n").unwrap_err();
+
+    assert!(matches!(err, ConfigError::Parse(message) if message.contains("api_key")));
+}
@@ -0,0 +1,82 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    diff::{PrinterDiff, StorageChange},
+    events::Heater,
+    raw_printer::{LinkState, PrinterStorage, PrinterStorageInfo, RawPrinter},
+    units::Celsius,
+};
+
+fn storage(free_space: u64) -> PrinterStorage {
+    PrinterStorage {
+        local: Some(PrinterStorageInfo {
+            free_space,
+            total_space: 1_000_000,
+        }),
+        sd_card: None,
+    }
+}
+
+#[test]
+fn identical_snapshots_have_no_changes() {
+    let printer = RawPrinter::builder().nozzle_temp(215.0, 215.0).build();
+
+    let diff = printer.diff(&printer.clone());
+
+    assert!(diff.is_empty());
+    assert_eq!(diff, PrinterDiff::default());
+    assert_eq!(diff.to_string(), "no changes");
+}
+
+#[test]
+fn diff_lists_state_temperature_and_storage_changes() {
+    let before = RawPrinter::builder()
+        .nozzle_temp(25.0, 0.0)
+        .bed_temp(60.0, 60.0)
+        .storage(storage(500_000))
+        .build();
+    let after = RawPrinter::builder()
+        .link_state(LinkState::Printing)
+        .nozzle_temp(180.0, 215.0)
+        .bed_temp(60.0, 60.0)
+        .storage(storage(400_000))
+        .build();
+
+    let diff = before.diff(&after);
+
+    assert_eq!(diff.state, Some((LinkState::Idle, LinkState::Printing)));
+    assert_eq!(diff.flags, vec![("printing", true), ("ready", false)]);
+
+    assert_eq!(diff.temperatures.len(), 1);
+    let nozzle = diff.temperatures[0];
+    assert_eq!(nozzle.heater, Heater::Nozzle);
+    assert_eq!(nozzle.to.target, Celsius(215.0));
+    assert_eq!(nozzle.actual_delta(), 155.0);
+    assert!(nozzle.target_changed());
+
+    assert_eq!(diff.storage.len(), 1);
+    assert_eq!(diff.storage[0].storage, "local");
+    assert_eq!(diff.storage[0].free_space_delta(), Some(-100_000));
+
+    assert_eq!(
+        diff.to_string(),
+        "state IDLE -> PRINTING, printing true, ready false, \
+         nozzle 25.0°C -> 180.0°C (target 215.0°C), local free 500000 -> 400000 bytes"
+    );
+}
+
+#[test]
+fn removed_storage_has_no_delta() {
+    let before = RawPrinter::builder().storage(storage(500_000)).build();
+    let after = RawPrinter::builder().build();
+
+    let diff = before.diff(&after);
+
+    let removed = StorageChange {
+        storage: "local",
+        from: storage(500_000).local,
+        to: None,
+    };
+    assert_eq!(diff.storage, vec![removed]);
+    assert_eq!(diff.storage[0].free_space_delta(), None);
+    assert_eq!(diff.to_string(), "local removed");
+}
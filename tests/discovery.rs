@@ -0,0 +1,74 @@
+#![cfg(feature = "discovery")]
+
+use mdns_sd::ServiceInfo;
+use pretty_assertions::assert_eq;
+use prusa_link_rs::discovery::*;
+
+#[test]
+fn octoprint_service_is_a_printer() {
+    let info = ServiceInfo::new(
+        "_octoprint._tcp.local.",
+        "PrusaLink MK4",
+        "prusa-mk4.local.",
+        "192.168.1.50",
+        80,
+        &[("path", "/")][..],
+    )
+    .unwrap();
+
+    let found = DiscoveredPrinter::from_service(&info).unwrap();
+
+    assert_eq!(found.name, "PrusaLink MK4");
+    assert_eq!(found.hostname, "prusa-mk4.local.");
+    assert_eq!(found.address.to_string(), "http://192.168.1.50:80");
+    assert_eq!(found.model.as_deref(), Some("MK4"));
+}
+
+#[test]
+fn model_is_read_from_txt_record() {
+    let info = ServiceInfo::new(
+        "_octoprint._tcp.local.",
+        "Farm shelf 2",
+        "printer-7.local.",
+        "192.168.1.57",
+        8080,
+        &[("model", "MK3S")][..],
+    )
+    .unwrap();
+
+    let found = DiscoveredPrinter::from_service(&info).unwrap();
+
+    assert_eq!(found.model.as_deref(), Some("MK3S"));
+    assert!(found.builder("api_key").try_build().is_ok());
+}
+
+#[test]
+fn unrelated_http_services_are_skipped() {
+    let router = ServiceInfo::new(
+        "_http._tcp.local.",
+        "Router admin",
+        "router.local.",
+        "192.168.1.1",
+        80,
+        None,
+    )
+    .unwrap();
+    let mini = ServiceInfo::new(
+        "_http._tcp.local.",
+        "Prusa MINI",
+        "prusa-mini.local.",
+        "192.168.1.52",
+        80,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(DiscoveredPrinter::from_service(&router), None);
+    assert_eq!(
+        DiscoveredPrinter::from_service(&mini)
+            .unwrap()
+            .model
+            .as_deref(),
+        Some("MINI")
+    );
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "test-utils")]
+
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    api::PrinterApi, error::PrinterError, raw_printer::LinkState, storage::Storage, test_utils::*,
+    units::Celsius,
+};
+use tokio::test;
+
+#[test]
+async fn print_lifecycle() {
+    let mut printer = FakePrinter::new();
+
+    assert!(printer.is_idle().await.unwrap());
+    assert_eq!(printer.get_job_info().await.unwrap(), None);
+
+    let id = printer.start_print("benchy.gcode");
+    printer.advance(25.0);
+
+    assert!(printer.is_printing().await.unwrap());
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(215.0));
+    assert_eq!(printer.progress_percent().await.unwrap(), Some(25.0));
+
+    let job = printer.get_job(id).await.unwrap().unwrap();
+    assert_eq!(job.get_file().unwrap().name, "benchy.gcode");
+    assert_eq!(
+        job.get_time_remaining(),
+        Some(Duration::from_secs(FAKE_PRINT_DURATION * 3 / 4))
+    );
+
+    printer.pause_job(id).await.unwrap();
+    assert_eq!(
+        printer.get_status().await.unwrap().state(),
+        LinkState::Paused
+    );
+    assert!(printer.pause_job(id).await.is_err());
+
+    printer.resume_job(id).await.unwrap();
+    printer.advance(80.0);
+
+    let info = printer.get_printer_info().await.unwrap();
+    assert_eq!(info.link_state(), LinkState::Finished);
+    assert_eq!(printer.progress_percent().await.unwrap(), Some(100.0));
+    assert!(printer.is_idle().await.unwrap());
+}
+
+#[test]
+async fn injected_errors() {
+    let mut printer = FakePrinter::new();
+
+    printer.fail_next(PrinterError::Unsupported("the status endpoint".to_string()));
+    let err = printer.get_status().await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::Unsupported(_))
+    ));
+    assert!(printer.get_status().await.is_ok());
+
+    printer.disconnect();
+    assert!(printer.check_connection().await.is_err());
+    assert!(printer.get_info().await.is_err());
+
+    printer.reconnect();
+    printer.set_state(LinkState::Error);
+    assert!(printer.has_error().await.unwrap());
+}
+
+#[test]
+async fn records_uploads() {
+    let printer = FakePrinter::new();
+
+    printer
+        .upload_file(&Storage::Usb, "benchy.gcode", b"G28".to_vec())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        printer.uploads(),
+        vec![Upload {
+            storage: Storage::Usb,
+            path: "benchy.gcode".to_string(),
+            data: b"G28".to_vec(),
+        }]
+    );
+}
@@ -0,0 +1,97 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    files::{glob_match, FileTree},
+    raw_storage::FileEntry,
+};
+
+fn tree() -> FileTree {
+    let root: FileEntry = serde_json::from_str(
+        r#"{
+            "type": "FOLDER",
+            "name": "usb",
+            "children": [
+                { "type": "FOLDER", "name": "projects", "children": [
+                    { "type": "PRINT_FILE", "name": "benchy.bgcode", "size": 4237152 },
+                    { "type": "FOLDER", "name": "old", "children": [
+                        { "type": "PRINT_FILE", "name": "cube.gcode", "size": 1200 }
+                    ] }
+                ] },
+                { "type": "PRINT_FILE", "name": "Calibration.GCODE", "size": 81234 }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    FileTree::new("", root)
+}
+
+#[test]
+fn iterates_depth_first() {
+    let tree = tree();
+
+    let paths = tree
+        .iter()
+        .map(|tree| tree.path.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        paths,
+        [
+            "",
+            "projects",
+            "projects/benchy.bgcode",
+            "projects/old",
+            "projects/old/cube.gcode",
+            "Calibration.GCODE",
+        ]
+    );
+    assert_eq!((&tree).into_iter().count(), 6);
+}
+
+#[test]
+fn files_leave_out_folders() {
+    let tree = tree();
+
+    assert!(tree.files().all(|file| !file.is_folder()));
+    assert_eq!(tree.files().count(), 3);
+}
+
+#[test]
+fn find_ignores_case_and_slashes() {
+    let tree = tree();
+
+    let found = tree.find("/PROJECTS/old/Cube.gcode").unwrap();
+
+    assert_eq!(found.path, "projects/old/cube.gcode");
+    assert_eq!(found.entry.size, Some(1200));
+    assert!(tree.find("projects/missing.gcode").is_none());
+}
+
+#[test]
+fn filter_matches_names_in_every_folder() {
+    let tree = tree();
+
+    let gcode = tree
+        .filter("*.gcode")
+        .map(|tree| tree.path.as_str())
+        .collect::<Vec<_>>();
+    let projects = tree
+        .filter("projects/*")
+        .map(|tree| tree.path.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(gcode, ["projects/old/cube.gcode", "Calibration.GCODE"]);
+    assert_eq!(projects, ["projects/benchy.bgcode"]);
+}
+
+#[test]
+fn glob_patterns() {
+    assert!(glob_match("*", "benchy.gcode"));
+    assert!(glob_match("bench?.gcode", "projects/benchy.gcode"));
+    assert!(!glob_match("bench?.gcode", "bench.gcode"));
+    assert!(glob_match("**/cube.gcode", "cube.gcode"));
+    assert!(glob_match("**/cube.gcode", "a/b/c/cube.gcode"));
+    assert!(glob_match("a/**/b/*.gcode", "a/x/y/b/cube.gcode"));
+    assert!(!glob_match("a/**/b/*.gcode", "a/x/y/c/cube.gcode"));
+    assert!(!glob_match("projects/*.gcode", "cube.gcode"));
+}
@@ -0,0 +1,168 @@
+use std::{io::Write, time::Duration};
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{error::PrinterError, gcode_meta::*};
+
+const GCODE: &str = "; generated by PrusaSlicer 2.7.1+win64 on 2024-01-23 at 15:57:46 UTC
+G28 ; home all axes
+G1 X10 Y10 F3000
+; filament used [mm] = 1234.56
+; filament used [g] = 3.70
+; estimated printing time (normal mode) = 1h 2m 3s
+; prusaslicer_config = begin
+; filament_type = PLA;PETG
+; layer_height = 0.2
+; nozzle_diameter = 0.4,0.4
+; printer_model = MK4
+; temperature = 215
+; prusaslicer_config = end
+";
+
+#[test]
+fn parse_plain_gcode() {
+    let metadata = parse_gcode(GCODE.as_bytes()).unwrap();
+
+    assert_eq!(metadata.estimated_time, Some(Duration::from_secs(3723)));
+    assert_eq!(metadata.filament_used_mm, Some(1234.56));
+    assert_eq!(metadata.filament_used_g, Some(3.7));
+    assert_eq!(metadata.layer_height, Some(0.2));
+    assert_eq!(metadata.nozzle_temp, Some(215.0));
+    assert_eq!(metadata.nozzle_diameter, Some(0.4));
+    assert_eq!(metadata.filament_type.as_deref(), Some("PLA"));
+    assert_eq!(metadata.printer_model.as_deref(), Some("MK4"));
+    assert_eq!(
+        metadata
+            .entries
+            .get("prusaslicer_config")
+            .map(String::as_str),
+        Some("begin")
+    );
+}
+
+#[test]
+fn parse_overflowing_print_time() {
+    for time in ["999999999999999d", "213503982334601d 213503982334601d"] {
+        let gcode = format!("; estimated printing time (normal mode) = {}\n", time);
+        let metadata = parse_gcode(gcode.as_bytes()).unwrap();
+
+        assert_eq!(metadata.estimated_time, None);
+    }
+}
+
+/// Builds a metadata block of a bgcode file with a CRC32 checksum (which isn't verified, so it's zeroed)
+fn bgcode_block(block_type: u16, content: &str, deflate: bool) -> Vec<u8> {
+    let mut block = Vec::new();
+    block.extend(block_type.to_le_bytes());
+    block.extend((deflate as u16).to_le_bytes());
+    block.extend((content.len() as u32).to_le_bytes());
+
+    let data = if deflate {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        let data = encoder.finish().unwrap();
+        block.extend((data.len() as u32).to_le_bytes());
+        data
+    } else {
+        content.as_bytes().to_vec()
+    };
+
+    // Encoding parameter, 0 is INI
+    block.extend(0u16.to_le_bytes());
+    block.extend(data);
+    block.extend([0; 4]);
+    block
+}
+
+#[test]
+fn parse_bgcode_metadata_blocks() {
+    let file = [
+        b"GCDE\x01\x00\x00\x00\x01\x00".to_vec(),
+        bgcode_block(0, "Producer=PrusaSlicer 2.7.1\n", false),
+        bgcode_block(
+            3,
+            "printer_model=MINI\nfilament_type=PETG\nnozzle_diameter=0.6\n",
+            false,
+        ),
+        bgcode_block(
+            4,
+            "filament used [g]=12.5\nestimated printing time (normal mode)=1d 0h 1m 0s\n",
+            false,
+        ),
+        bgcode_block(2, "layer_height = 0.15\ntemperature = 240\n", true),
+        bgcode_block(1, "G28\n", false),
+    ]
+    .concat();
+
+    let metadata = parse_bgcode(file.as_slice()).unwrap();
+
+    assert_eq!(metadata.printer_model.as_deref(), Some("MINI"));
+    assert_eq!(metadata.filament_type.as_deref(), Some("PETG"));
+    assert_eq!(metadata.nozzle_diameter, Some(0.6));
+    assert_eq!(metadata.filament_used_g, Some(12.5));
+    assert_eq!(
+        metadata.estimated_time,
+        Some(Duration::from_secs(24 * 60 * 60 + 60))
+    );
+    assert_eq!(metadata.layer_height, Some(0.15));
+    assert_eq!(metadata.nozzle_temp, Some(240.0));
+    assert_eq!(
+        metadata.entries.get("Producer").map(String::as_str),
+        Some("PrusaSlicer 2.7.1")
+    );
+}
+
+#[test]
+fn parse_truncated_bgcode() {
+    let err = parse_bgcode(b"GCDE".as_slice()).unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::InvalidFile(_))
+    ));
+}
+
+#[test]
+fn parse_bgcode_with_oversized_block() {
+    // A metadata block claiming 4 GiB of data, without the data
+    let mut block = Vec::new();
+    block.extend(0u16.to_le_bytes());
+    block.extend(0u16.to_le_bytes());
+    block.extend(u32::MAX.to_le_bytes());
+    block.extend(0u16.to_le_bytes());
+
+    let file = [b"GCDE\x01\x00\x00\x00\x01\x00".to_vec(), block].concat();
+    let err = parse_bgcode(file.as_slice()).unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::InvalidFile(_))
+    ));
+
+    // Thumbnails aren't read, a truncated one is still an error
+    let mut block = Vec::new();
+    block.extend(5u16.to_le_bytes());
+    block.extend(0u16.to_le_bytes());
+    block.extend(u32::MAX.to_le_bytes());
+    block.extend([0; 6]);
+    block.extend([0; 16]);
+
+    let file = [b"GCDE\x01\x00\x00\x00\x01\x00".to_vec(), block].concat();
+    let err = parse_bgcode(file.as_slice()).unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::InvalidFile(_))
+    ));
+}
+
+#[tokio::test]
+async fn read_metadata_from_disk() {
+    let path = std::env::temp_dir().join("prusa_link_rs_read_metadata.gcode");
+    std::fs::write(&path, GCODE).unwrap();
+
+    let metadata = read_metadata(&path).await.unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(metadata.printer_model.as_deref(), Some("MK4"));
+}
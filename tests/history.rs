@@ -0,0 +1,86 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::history::*;
+use std::time::{Duration, SystemTime};
+
+fn sample(seconds: u64, nozzle_temp: f32, z_height: Option<f32>) -> TelemetrySample {
+    TelemetrySample {
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+        nozzle_temp,
+        bed_temp: 60.0,
+        z_height,
+        print_speed: Some(100.0),
+    }
+}
+
+#[test]
+fn drops_oldest_sample_when_full() {
+    let mut history = TelemetryHistory::new(3);
+
+    for (seconds, temp) in [(0, 200.0), (1, 205.0), (2, 210.0), (3, 215.0)] {
+        history.push(sample(seconds, temp, None));
+    }
+
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history
+            .nozzle_temps()
+            .into_iter()
+            .map(|(_, temp)| temp)
+            .collect::<Vec<_>>(),
+        vec![205.0, 210.0, 215.0]
+    );
+}
+
+#[test]
+fn stats_over_window() {
+    let mut history = TelemetryHistory::new(10);
+
+    history.push(sample(0, 100.0, None));
+    history.push(sample(10, 200.0, Some(0.2)));
+    history.push(sample(20, 210.0, None));
+    history.push(sample(30, 220.0, Some(0.4)));
+
+    assert_eq!(
+        history.stats(TelemetryField::NozzleTemp, Duration::from_secs(20)),
+        Some(SeriesStats {
+            min: 200.0,
+            max: 220.0,
+            avg: 210.0,
+        })
+    );
+
+    // Samples without a value are skipped
+    assert_eq!(
+        history.stats(TelemetryField::ZHeight, Duration::from_secs(60)),
+        Some(SeriesStats {
+            min: 0.2,
+            max: 0.4,
+            avg: 0.3,
+        })
+    );
+
+    assert_eq!(
+        TelemetryHistory::new(1).stats(TelemetryField::BedTemp, Duration::from_secs(60)),
+        None
+    );
+}
+
+#[test]
+fn csv_leaves_missing_fields_empty() {
+    let mut history = TelemetryHistory::new(10);
+
+    history.push(sample(0, 200.0, None));
+    history.push(sample(10, 215.5, Some(0.2)));
+
+    assert_eq!(
+        history.to_csv(),
+        format!(
+            "{}\n0.000,200,60,,100\n10.000,215.5,60,0.2,100\n",
+            CSV_HEADER
+        )
+    );
+    assert_eq!(
+        TelemetryHistory::new(1).to_csv(),
+        format!("{}\n", CSV_HEADER)
+    );
+}
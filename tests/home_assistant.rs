@@ -0,0 +1,102 @@
+#![cfg(feature = "home-assistant")]
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{home_assistant::*, raw_info::RawInfo};
+use serde_json::json;
+
+fn discovery() -> Discovery {
+    Discovery::new("mk4", EntityTopics::under("prusalink/mk4"))
+}
+
+#[test]
+fn sensors_read_the_telemetry() {
+    let entities = discovery().entities();
+    let bed = entities
+        .iter()
+        .find(|entity| entity.key == "bed_temp")
+        .unwrap();
+
+    assert_eq!(bed.component, "sensor");
+    assert_eq!(bed.topic, "homeassistant/sensor/mk4_bed_temp/config");
+    assert_eq!(
+        bed.config,
+        json!({
+            "name": "Bed temperature",
+            "unique_id": "prusalink_mk4_bed_temp",
+            "availability_topic": "prusalink/mk4/availability",
+            "state_topic": "prusalink/mk4/telemetry",
+            "value_template": "{{ value_json.bed_temp }}",
+            "unit_of_measurement": "°C",
+            "device_class": "temperature",
+            "device": {
+                "identifiers": ["prusalink_mk4"],
+                "name": "mk4",
+                "manufacturer": "Prusa Research",
+            },
+        })
+    );
+
+    let state = entities
+        .iter()
+        .find(|entity| entity.key == "state")
+        .unwrap();
+    assert_eq!(state.config["state_topic"], "prusalink/mk4/state");
+    assert!(state.config.get("value_template").is_none());
+}
+
+#[test]
+fn buttons_publish_commands() {
+    let entities = discovery().discovery_prefix("ha").entities();
+    let buttons = entities
+        .iter()
+        .filter(|entity| entity.component == "button")
+        .map(|entity| {
+            (
+                entity.topic.as_str(),
+                entity.config["command_topic"].as_str().unwrap(),
+                entity.config["payload_press"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        buttons,
+        vec![
+            (
+                "ha/button/mk4_pause/config",
+                "prusalink/mk4/command",
+                "pause"
+            ),
+            (
+                "ha/button/mk4_resume/config",
+                "prusalink/mk4/command",
+                "resume"
+            ),
+            ("ha/button/mk4_stop/config", "prusalink/mk4/command", "stop"),
+        ]
+    );
+}
+
+#[test]
+fn device_is_named_after_info() {
+    let info: RawInfo =
+        serde_json::from_str(r#"{"name": "Prusa MK4", "serial": "10589-3742441631123456"}"#)
+            .unwrap();
+
+    let entities =
+        Discovery::from_info("MK4 left", &info, EntityTopics::under("prusalink/mk4")).entities();
+
+    assert_eq!(
+        entities[0].topic,
+        "homeassistant/sensor/MK4_left_state/config"
+    );
+    assert_eq!(
+        entities[0].config["device"],
+        json!({
+            "identifiers": ["prusalink_MK4_left"],
+            "name": "Prusa MK4",
+            "manufacturer": "Prusa Research",
+            "serial_number": "10589-3742441631123456",
+        })
+    );
+}
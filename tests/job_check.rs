@@ -0,0 +1,140 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    gcode_meta::GcodeMetadata,
+    job_check::{JobExpectations, JobMismatch},
+    material::Material,
+    model::PrinterModel,
+    raw_info::RawInfo,
+    raw_printer::{PrinterTelemetry, RawPrinter, Temp},
+};
+
+fn info(json: &str) -> RawInfo {
+    serde_json::from_str(json).unwrap()
+}
+
+fn file(entries: &[(&str, &str)]) -> GcodeMetadata {
+    let mut file = GcodeMetadata {
+        entries: entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+    file.filament_type = file.entries.get("filament_type").cloned();
+    file.nozzle_diameter = file
+        .entries
+        .get("nozzle_diameter")
+        .and_then(|value| value.parse().ok());
+    file.printer_model = file.entries.get("printer_model").cloned();
+    file
+}
+
+fn printer_with_material(material: &str) -> RawPrinter {
+    RawPrinter::builder()
+        .telemetry(PrinterTelemetry {
+            material: Some(material.to_string()),
+            ..Default::default()
+        })
+        .build()
+}
+
+#[test]
+fn matching_file_passes() {
+    let file = file(&[
+        ("filament_type", "PLA"),
+        ("nozzle_diameter", "0.4"),
+        ("printer_model", "MK4"),
+    ]);
+
+    assert_eq!(
+        JobExpectations::new().check(
+            &file,
+            &info(r#"{"nozzle_diameter": 0.4}"#),
+            &printer_with_material("PLA"),
+            PrinterModel::Mk4
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn nothing_loaded_is_a_mismatch() {
+    let file = file(&[("filament_type", "PETG")]);
+
+    assert_eq!(
+        JobExpectations::new().check(
+            &file,
+            &info("{}"),
+            &printer_with_material(" - "),
+            PrinterModel::Mk4
+        ),
+        Err(vec![JobMismatch::Material {
+            file: Material::Petg,
+            loaded: None,
+        }])
+    );
+}
+
+#[test]
+fn unreported_values_are_not_checked() {
+    let file = file(&[("filament_type", "PETG"), ("nozzle_diameter", "0.6")]);
+
+    // Like a MINI, which doesn't report the material
+    assert_eq!(
+        JobExpectations::new().check(
+            &file,
+            &info("{}"),
+            &RawPrinter::builder().build(),
+            PrinterModel::Unknown
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn skipped_checks() {
+    let file = file(&[("filament_type", "PETG"), ("printer_model", "MK4S")]);
+
+    assert_eq!(
+        JobExpectations::new().skip_material().check(
+            &file,
+            &info("{}"),
+            &printer_with_material("PLA"),
+            PrinterModel::Mk4
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn multi_material_files_need_a_unit_or_tools() {
+    let file = file(&[("filament_type", "PLA;PETG;PLA")]);
+    let printer = printer_with_material("PLA");
+
+    assert_eq!(
+        JobExpectations::new().check(
+            &file,
+            &info(r#"{"mmu": false}"#),
+            &printer,
+            PrinterModel::Mk4
+        ),
+        Err(vec![JobMismatch::MultiMaterial { filaments: 3 }])
+    );
+    assert_eq!(
+        JobExpectations::new().check(
+            &file,
+            &info(r#"{"mmu": true}"#),
+            &printer,
+            PrinterModel::Mk4
+        ),
+        Ok(())
+    );
+
+    let xl = RawPrinter::builder()
+        .tools(vec![Temp::default(); 5])
+        .build();
+    assert_eq!(
+        JobExpectations::new().check(&file, &info("{}"), &xl, PrinterModel::Xl),
+        Ok(())
+    );
+}
@@ -0,0 +1,125 @@
+use std::time::{Duration, SystemTime};
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    events::PrinterEvent,
+    job_history::{JobHistory, JobOutcome, JobRecord},
+    raw_printer::{LinkState, RawPrinter},
+};
+
+fn printer(link_state: LinkState) -> RawPrinter {
+    RawPrinter::builder().link_state(link_state).build()
+}
+
+#[test]
+fn records_start_and_outcome() {
+    let mut history = JobHistory::new();
+
+    history.record(
+        &PrinterEvent::PrintStarted,
+        &printer(LinkState::Printing),
+        None,
+        None,
+    );
+    history.record(
+        &PrinterEvent::StateChanged {
+            from: LinkState::Printing,
+            to: LinkState::Error,
+        },
+        &printer(LinkState::Error),
+        None,
+        None,
+    );
+    assert!(history.running().is_some());
+
+    history.record(
+        &PrinterEvent::PrintFailed,
+        &printer(LinkState::Error),
+        None,
+        None,
+    );
+
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.running(), None);
+    assert_eq!(history.latest().unwrap().outcome, Some(JobOutcome::Failed));
+    assert!(history.latest().unwrap().started.is_some());
+}
+
+#[test]
+fn end_without_start_has_no_known_start() {
+    let mut history = JobHistory::new();
+
+    history.record(
+        &PrinterEvent::PrintFinished,
+        &printer(LinkState::Finished),
+        None,
+        None,
+    );
+
+    let record = history.latest().unwrap();
+    assert_eq!(record.outcome, Some(JobOutcome::Finished));
+    assert_eq!(record.started, None);
+    assert_eq!(record.duration(), None);
+}
+
+#[test]
+fn missed_end_is_unknown() {
+    let mut history = JobHistory::new();
+    let started = printer(LinkState::Printing);
+
+    history.record(&PrinterEvent::PrintStarted, &started, None, None);
+    history.record(&PrinterEvent::PrintStarted, &started, None, None);
+
+    let outcomes = history
+        .records()
+        .map(|record| record.outcome)
+        .collect::<Vec<_>>();
+    assert_eq!(outcomes, vec![Some(JobOutcome::Unknown), None]);
+}
+
+#[test]
+fn queries() {
+    let epoch = SystemTime::UNIX_EPOCH;
+    let mut history = JobHistory::new();
+
+    for (start, outcome, filament) in [
+        (1_000, JobOutcome::Finished, Some(10.0)),
+        (2_000, JobOutcome::Stopped, Some(3.0)),
+        (3_000, JobOutcome::Finished, None),
+        (4_000, JobOutcome::Finished, Some(2.5)),
+    ] {
+        let mut record = JobRecord::new(Some(epoch + Duration::from_secs(start)), None, None);
+        record.ended = Some(epoch + Duration::from_secs(start + 600));
+        record.outcome = Some(outcome);
+        record.filament_used_g = filament;
+        history.push(record);
+    }
+
+    assert_eq!(history.with_outcome(JobOutcome::Finished).count(), 3);
+    assert_eq!(history.since(epoch + Duration::from_secs(2_000)).count(), 3);
+    assert_eq!(history.filament_used_g(), 12.5);
+    assert_eq!(
+        history.latest().unwrap().duration(),
+        Some(Duration::from_secs(600))
+    );
+}
+
+#[test]
+fn history_round_trips_through_json() {
+    let mut history = JobHistory::new();
+    let mut record = JobRecord::new(
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        None,
+        None,
+    );
+    record.file_name = Some("benchy.bgcode".to_string());
+    record.outcome = Some(JobOutcome::Stopped);
+    history.push(record);
+
+    let json = serde_json::to_string(&history).unwrap();
+    assert_eq!(
+        json,
+        r#"[{"id":null,"file_name":"benchy.bgcode","started":1700000000,"ended":null,"outcome":"stopped","filament_used_g":null,"filament_used_mm":null}]"#
+    );
+    assert_eq!(serde_json::from_str::<JobHistory>(&json).unwrap(), history);
+}
@@ -0,0 +1,34 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::material::Material;
+
+#[test]
+fn known_materials_are_parsed() {
+    assert_eq!(Material::parse("PLA"), Some(Material::Pla));
+    assert_eq!(Material::parse("petg"), Some(Material::Petg));
+    assert_eq!(Material::parse(" ASA "), Some(Material::Asa));
+    assert_eq!(Material::parse("TPU"), Some(Material::Flex));
+}
+
+#[test]
+fn nothing_loaded() {
+    assert_eq!(Material::parse(" - "), None);
+    assert_eq!(Material::parse("---"), None);
+    assert_eq!(Material::parse(""), None);
+}
+
+#[test]
+fn unknown_materials_keep_their_name() {
+    let material = Material::parse("PA11CF").unwrap();
+
+    assert_eq!(material, Material::Other("PA11CF".to_string()));
+    assert_eq!(material.to_string(), "PA11CF");
+}
+
+#[test]
+fn materials_serialize_as_names() {
+    assert_eq!(serde_json::to_string(&Material::Petg).unwrap(), r#""PETG""#);
+    assert_eq!(
+        serde_json::from_str::<Material>(r#""pla""#).unwrap(),
+        Material::Pla
+    );
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "metrics")]
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    farm::{Farm, PrinterSnapshot},
+    metrics::*,
+    raw_printer::{LinkState, RawPrinter},
+    PrinterBuilder,
+};
+
+#[test]
+fn snapshots_in_exposition_format() {
+    let printing = PrinterSnapshot {
+        printer: RawPrinter::builder()
+            .link_state(LinkState::Printing)
+            .nozzle_temp(215.5, 215.0)
+            .bed_temp(60.0, 60.0)
+            .build(),
+        job: Some(
+            serde_json::from_str(
+                r#"{"id": 297, "state": "PRINTING", "progress": 42.0, "time_remaining": 600, "time_printing": 300}"#,
+            )
+            .unwrap(),
+        ),
+    };
+    let idle = PrinterSnapshot {
+        printer: RawPrinter::builder().build(),
+        job: None,
+    };
+
+    let metrics = encode_snapshots([("mk4", &printing), ("mini \"2\"", &idle)]);
+
+    assert_eq!(
+        metrics,
+        r#"# HELP prusalink_printer_state The state of the printer, 1 for the current state
+# TYPE prusalink_printer_state gauge
+prusalink_printer_state{printer="mk4",state="PRINTING"} 1
+prusalink_printer_state{printer="mini \"2\"",state="IDLE"} 1
+# HELP prusalink_tool_temperature_celsius The temperature of the nozzle of the tool
+# TYPE prusalink_tool_temperature_celsius gauge
+prusalink_tool_temperature_celsius{printer="mk4",tool="0"} 215.5
+prusalink_tool_temperature_celsius{printer="mini \"2\"",tool="0"} 23
+# HELP prusalink_tool_target_temperature_celsius The target temperature of the nozzle of the tool
+# TYPE prusalink_tool_target_temperature_celsius gauge
+prusalink_tool_target_temperature_celsius{printer="mk4",tool="0"} 215
+prusalink_tool_target_temperature_celsius{printer="mini \"2\"",tool="0"} 0
+# HELP prusalink_bed_temperature_celsius The temperature of the bed
+# TYPE prusalink_bed_temperature_celsius gauge
+prusalink_bed_temperature_celsius{printer="mk4"} 60
+prusalink_bed_temperature_celsius{printer="mini \"2\""} 23
+# HELP prusalink_bed_target_temperature_celsius The target temperature of the bed
+# TYPE prusalink_bed_target_temperature_celsius gauge
+prusalink_bed_target_temperature_celsius{printer="mk4"} 60
+prusalink_bed_target_temperature_celsius{printer="mini \"2\""} 0
+# HELP prusalink_job_progress_percent The progress of the current job
+# TYPE prusalink_job_progress_percent gauge
+prusalink_job_progress_percent{printer="mk4"} 42
+# HELP prusalink_job_time_remaining_seconds The estimated time until the current job ends
+# TYPE prusalink_job_time_remaining_seconds gauge
+prusalink_job_time_remaining_seconds{printer="mk4"} 600
+# HELP prusalink_job_time_printing_seconds The time the current job has been printing
+# TYPE prusalink_job_time_printing_seconds gauge
+prusalink_job_time_printing_seconds{printer="mk4"} 300
+"#
+    );
+}
+
+#[test]
+fn farm_response_reports_unreachable_printers() {
+    let mut farm = Farm::new();
+    farm.add(
+        "offline",
+        PrinterBuilder::new("127.0.0.1", "1234567890")
+            .port(1)
+            .build(),
+    );
+
+    let response = farm_response(&farm);
+
+    assert_eq!(response.headers()[http::header::CONTENT_TYPE], CONTENT_TYPE);
+    assert_eq!(
+        response.body(),
+        "# HELP prusalink_up Whether the printer answered its last request\n\
+         # TYPE prusalink_up gauge\n\
+         prusalink_up{printer=\"offline\"} 0\n"
+    );
+}
@@ -1,5 +1,5 @@
 use pretty_assertions::assert_eq;
-use prusa_link_rs::raw_printer::*;
+use prusa_link_rs::{material::Material, raw_job::JobId, raw_printer::*, units::Celsius};
 use tokio::test;
 
 /// Creates the base for a mock server, parsing the given url and returning the server, address, port and api key
@@ -28,15 +28,9 @@ fn mock_base() -> (mockito::ServerGuard, String, u16, String) {
     (server, address, port, api_key.to_string())
 }
 
-fn mock_api_printer() -> (mockito::ServerGuard, mockito::Mock, String, u16, String) {
-    let (mut server, address, port, api_key) = mock_base();
-
-    let mock = server
-        .mock("GET", "/api/printer")
-        .match_header("X-Api-Key", api_key.as_str())
-        .with_status(200)
-        .with_body(
-            r#"{
+/// Body of `/api/printer` with the telemetry block left as a `{telemetry}` placeholder,
+/// since the telemetry is what differs the most between printer models
+const API_PRINTER_BODY: &str = r#"{
     "temperature": {
         "tool0": {
             "actual": 220.2,
@@ -67,7 +61,18 @@ fn mock_api_printer() -> (mockito::ServerGuard, mockito::Mock, String, u16, Stri
             "link_state": "PRINTING"
         }
     },
-    "telemetry": {
+    "telemetry": {telemetry},
+    "storage": {
+        "local": {
+            "free_space": 56813572096,
+            "total_space": 61273088000
+        },
+        "sd_card": null
+    }
+}"#;
+
+/// Telemetry as reported by a MK3 running PrusaLink on a Raspberry Pi
+const MK3_TELEMETRY: &str = r#"{
         "temp-bed": 69.7,
         "temp-nozzle": 220.2,
         "material": " - ",
@@ -76,19 +81,260 @@ fn mock_api_printer() -> (mockito::ServerGuard, mockito::Mock, String, u16, Stri
         "axis_x": null,
         "axis_y": null,
         "axis_z": 16.8
+    }"#;
+
+/// Telemetry as reported by a MK4, which doesn't report axis positions
+const MK4_TELEMETRY: &str = r#"{
+        "temp-bed": 60.1,
+        "temp-nozzle": 215.3,
+        "material": "PLA",
+        "z-height": 0.2,
+        "print-speed": 100
+    }"#;
+
+/// Telemetry as reported by a MINI, which doesn't report the material or axis positions
+const MINI_TELEMETRY: &str = r#"{
+        "temp-bed": 59.9,
+        "temp-nozzle": 214.8,
+        "z-height": 1.4,
+        "print-speed": 95
+    }"#;
+
+/// Telemetry as reported by a CORE One, which has a chamber sensor
+const CORE_ONE_TELEMETRY: &str = r#"{
+        "temp-bed": 100.2,
+        "temp-nozzle": 260.1,
+        "temp-chamber": 44.5,
+        "material": "ASA",
+        "z-height": 3.2,
+        "print-speed": 100
+    }"#;
+
+fn mock_api_printer() -> (mockito::ServerGuard, mockito::Mock, String, u16, String) {
+    mock_api_printer_with_telemetry(MK3_TELEMETRY)
+}
+
+fn mock_api_printer_with_telemetry(
+    telemetry: &str,
+) -> (mockito::ServerGuard, mockito::Mock, String, u16, String) {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let mock = server
+        .mock("GET", "/api/printer")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_PRINTER_BODY.replace("{telemetry}", telemetry))
+        .create();
+
+    (server, mock, address, port, api_key.to_string())
+}
+
+/// Body of `/api/version` as reported by a MK4
+const API_VERSION_BODY: &str = r#"{
+    "api": "2.0.0",
+    "server": "2.1.2",
+    "nozzle_diameter": 0.4,
+    "text": "PrusaLink",
+    "hostname": "prusa-mk4",
+    "capabilities": {
+        "upload-by-put": true
+    }
+}"#;
+
+/// Adds a mock of `/api/version` to an existing server,
+/// only answering with the version if the api key matches
+fn mock_api_version(server: &mut mockito::ServerGuard, api_key: &str) -> mockito::Mock {
+    server
+        .mock("GET", "/api/version")
+        .match_header("X-Api-Key", api_key)
+        .with_status(200)
+        .with_body(API_VERSION_BODY)
+        .create()
+}
+
+/// Body of `/api/v1/job` as reported by a MK4 while printing
+const API_V1_JOB_BODY: &str = r#"{
+    "id": 297,
+    "state": "PRINTING",
+    "progress": 91.0,
+    "time_remaining": 600,
+    "time_printing": 6547,
+    "file": {
+        "refs": {
+            "download": "/usb/BENCHY~1.GCO",
+            "icon": "/thumb/s/usb/BENCHY~1.GCO",
+            "thumbnail": "/thumb/l/usb/BENCHY~1.GCO"
+        },
+        "name": "BENCHY~1.GCO",
+        "display_name": "benchy_0.4n_0.2mm_PLA_MK4_1h2m.gcode",
+        "path": "/usb",
+        "size": 4237152,
+        "m_timestamp": 1706025466
+    }
+}"#;
+
+/// Adds a mock of `/api/v1/job` to an existing server,
+/// so it can be used together with the other mocks
+fn mock_api_v1_job(server: &mut mockito::ServerGuard, api_key: &str) -> mockito::Mock {
+    server
+        .mock("GET", "/api/v1/job")
+        .match_header("X-Api-Key", api_key)
+        .with_status(200)
+        .with_body(API_V1_JOB_BODY)
+        .create()
+}
+
+/// Adds a mock of `/api/v1/storage` to an existing server, with `free_space` bytes free on the usb stick
+fn mock_api_v1_storage(
+    server: &mut mockito::ServerGuard,
+    api_key: &str,
+    free_space: u64,
+) -> mockito::Mock {
+    server
+        .mock("GET", "/api/v1/storage")
+        .match_header("X-Api-Key", api_key)
+        .with_status(200)
+        .with_body(format!(
+            r#"{{
+    "storage_list": [
+        {{
+            "name": "usb",
+            "type": "USB",
+            "path": "/usb",
+            "read_only": false,
+            "free_space": {},
+            "total_space": 15989542912,
+            "available": true
+        }},
+        {{
+            "name": "local",
+            "type": "LOCAL",
+            "path": "/local",
+            "read_only": false,
+            "available": false
+        }}
+    ]
+}}"#,
+            free_space
+        ))
+        .create()
+}
+
+/// Body of `/api/v1/status` as reported by a MK4 with a MMU3 while printing
+const API_V1_STATUS_BODY: &str = r#"{
+    "job": {
+        "id": 297,
+        "progress": 91.0,
+        "time_remaining": 600,
+        "time_printing": 6547
     },
     "storage": {
-        "local": {
-            "free_space": 56813572096,
-            "total_space": 61273088000
-        },
-        "sd_card": null
+        "path": "/usb/",
+        "name": "usb",
+        "read_only": false
+    },
+    "printer": {
+        "state": "PRINTING",
+        "temp_bed": 60.1,
+        "target_bed": 60.0,
+        "temp_nozzle": 215.3,
+        "target_nozzle": 215.0,
+        "axis_z": 2.4,
+        "flow": 100,
+        "speed": 100,
+        "fan_hotend": 4523,
+        "fan_print": 3012,
+        "filament_sensor": true,
+        "slot": {
+            "active": 2,
+            "slots": {
+                "1": { "material": "PLA", "temp": 215.3 },
+                "2": { "material": "PETG", "temp": 215.3 }
+            }
+        }
     }
-}"#,
-        )
-        .create();
+}"#;
 
-    (server, mock, address, port, api_key.to_string())
+/// Adds a mock of `/api/v1/status` to an existing server
+fn mock_api_v1_status(server: &mut mockito::ServerGuard, api_key: &str) -> mockito::Mock {
+    server
+        .mock("GET", "/api/v1/status")
+        .match_header("X-Api-Key", api_key)
+        .with_status(200)
+        .with_body(API_V1_STATUS_BODY)
+        .create()
+}
+
+/// Body of `/api/v1/info` as reported by a MK4 with a MMU3
+const API_V1_INFO_BODY: &str = r#"{
+    "mmu": true,
+    "name": "Prusa MK4",
+    "location": "Farm shelf 2",
+    "farm_mode": false,
+    "nozzle_diameter": 0.4,
+    "min_extrusion_temp": 170,
+    "serial": "10589-3742441633115045",
+    "sd_ready": false,
+    "active_camera": false,
+    "hostname": "prusa-mk4",
+    "port": "80",
+    "network_error_chime": false
+}"#;
+
+/// Adds a mock of `/api/v1/info` to an existing server
+fn mock_api_v1_info(server: &mut mockito::ServerGuard, api_key: &str) -> mockito::Mock {
+    server
+        .mock("GET", "/api/v1/info")
+        .match_header("X-Api-Key", api_key)
+        .with_status(200)
+        .with_body(API_V1_INFO_BODY)
+        .create()
+}
+
+#[test]
+async fn model_from_version_and_info() {
+    use prusa_link_rs::model::{PrinterModel, Version};
+
+    let (mut server, address, port, api_key) = mock_base();
+    // Requested for the capabilities and for the identity
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let info_mock = mock_api_v1_info(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(printer.model().await.unwrap(), PrinterModel::Mk4);
+    assert_eq!(
+        printer.identity().await.unwrap().prusa_link,
+        Some(Version::new(2, 1, 2))
+    );
+
+    info_mock.assert();
+}
+
+#[test]
+async fn require_min_version() {
+    use prusa_link_rs::{error::PrinterError, model::Version};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let _info_mock = mock_api_v1_info(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    printer.require_min_version("2.1").await.unwrap();
+
+    let err = printer.require_min_version("2.7.0").await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::FirmwareTooOld {
+            required: Version::new(2, 7, 0),
+            found: Some(Version::new(2, 1, 2)),
+        })
+    );
+
+    assert!(printer.require_min_version("latest").await.is_err());
 }
 
 #[test]
@@ -102,8 +348,8 @@ async fn get_printer_and_verify() {
 
     let raw_printer = printer.get_printer_info().await.unwrap();
 
-    assert_eq!(raw_printer.get_target_nozzle_temp(), 220.0);
-    assert_eq!(raw_printer.get_target_bed_temp(), 70.0);
+    assert_eq!(raw_printer.get_target_nozzle_temp(), Celsius(220.0));
+    assert_eq!(raw_printer.get_target_bed_temp(), Celsius(70.0));
     assert_eq!(raw_printer.get_sd_ready(), false);
     assert_eq!(raw_printer.get_state_text(), "Printing");
     assert_eq!(raw_printer.get_operational(), false);
@@ -117,13 +363,20 @@ async fn get_printer_and_verify() {
     assert_eq!(raw_printer.get_finished(), false);
     assert_eq!(raw_printer.get_prepared(), false);
     assert_eq!(raw_printer.get_link_state(), "PRINTING");
-    assert_eq!(raw_printer.get_bed_temp(), 69.7);
-    assert_eq!(raw_printer.get_nozzle_temp(), 220.2);
-    assert_eq!(raw_printer.get_material_telemetry(), " - ");
-    assert_eq!(raw_printer.get_print_speed_telemetry(), 100.0);
-    assert_eq!(raw_printer.get_axis_x_telemetry(), None);
-    assert_eq!(raw_printer.get_axis_x_telemetry(), None);
-    assert_eq!(raw_printer.get_z_height_telemetry(), 16.8);
+    assert_eq!(raw_printer.get_bed_temp(), Celsius(69.7));
+    assert_eq!(raw_printer.get_nozzle_temp(), Celsius(220.2));
+    assert_eq!(raw_printer.get_material_telemetry(), Some(" - "));
+    assert_eq!(raw_printer.get_material(), None);
+    assert_eq!(raw_printer.get_print_speed_telemetry(), Some(100.0));
+    assert_eq!(
+        raw_printer.position(),
+        AxisPosition {
+            x: None,
+            y: None,
+            z: Some(16.8),
+        }
+    );
+    assert_eq!(raw_printer.get_z_height_telemetry(), Some(16.8));
     assert_eq!(
         raw_printer.get_local_storage_space(),
         Some(&PrinterStorageInfo {
@@ -144,6 +397,3954 @@ async fn autorefresh_and_get_temp() {
     let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
     let mut printer = printer_builder.port(port.into()).build();
 
-    assert_eq!(printer.get_nozzle_temp().await.unwrap(), 220.2);
-    assert_eq!(printer.get_bed_temp().await.unwrap(), 69.7);
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+    assert_eq!(printer.get_bed_temp().await.unwrap(), Celsius(69.7));
+}
+
+#[cfg(feature = "test-utils")]
+#[test]
+async fn auto_refresh_follows_clock() {
+    use prusa_link_rs::test_utils::MockClock;
+    use std::time::Duration;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/printer")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_PRINTER_BODY.replace("{telemetry}", MK3_TELEMETRY))
+        .expect(2)
+        .create();
+
+    let clock = MockClock::new();
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder
+        .port(port.into())
+        .auto_refresh(Duration::from_secs(5))
+        .clock(clock.clone())
+        .build();
+
+    printer.get_nozzle_temp().await.unwrap();
+
+    clock.advance(Duration::from_secs(5));
+    printer.get_nozzle_temp().await.unwrap();
+
+    clock.advance(Duration::from_secs(1));
+    printer.get_nozzle_temp().await.unwrap();
+
+    mock.assert();
+}
+
+#[test]
+async fn stale_while_revalidate_answers_from_cache() {
+    use prusa_link_rs::cache::CachePolicy;
+    use std::time::Duration;
+
+    let (mut server, address, port, api_key) = mock_base();
+
+    let mocks = [
+        api_printer_body_in_state(false, "IDLE"),
+        api_printer_body_in_state(true, "PRINTING"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .match_header("X-Api-Key", api_key.as_str())
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder
+        .port(port.into())
+        .auto_refresh(Duration::from_millis(10))
+        .cache_policy(CachePolicy::StaleWhileRevalidate {
+            max_stale: Duration::from_secs(60),
+        })
+        .build();
+
+    assert_eq!(printer.state().await.unwrap(), LinkState::Idle);
+
+    // Outdated, so the cached state is returned while the refresh runs in the background
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(printer.state().await.unwrap(), LinkState::Idle);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(printer.state().await.unwrap(), LinkState::Printing);
+
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[test]
+async fn no_cache_always_refreshes() {
+    use prusa_link_rs::cache::CachePolicy;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/printer")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_PRINTER_BODY.replace("{telemetry}", MK3_TELEMETRY))
+        .expect(2)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder
+        .port(port.into())
+        .cache_policy(CachePolicy::NoCache)
+        .build();
+
+    printer.get_nozzle_temp().await.unwrap();
+    printer.get_bed_temp().await.unwrap();
+
+    mock.assert();
+}
+
+#[test]
+async fn cached_printer_info() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert!(printer.cached().is_none());
+
+    let z_height = printer
+        .cached_or_refresh()
+        .await
+        .unwrap()
+        .get_z_height_telemetry();
+    assert_eq!(z_height, Some(16.8));
+    assert_eq!(printer.cached().unwrap().get_state_text(), "Printing");
+
+    printer.cached_or_refresh().await.unwrap();
+
+    mock.assert();
+}
+
+#[test]
+async fn state_flags_from_cache() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(printer.state().await.unwrap(), LinkState::Printing);
+    assert_eq!(printer.state_text().await.unwrap(), "Printing");
+    assert!(printer.is_printing().await.unwrap());
+    assert!(!printer.is_paused().await.unwrap());
+    assert!(!printer.is_pausing().await.unwrap());
+    assert!(!printer.is_cancelling().await.unwrap());
+    assert!(!printer.is_operational().await.unwrap());
+    assert!(printer.flags().await.unwrap().printing);
+
+    mock.assert();
+}
+
+#[test]
+async fn nozzle_and_bed_read_actual_and_target() {
+    use prusa_link_rs::units::Temperatures;
+
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let nozzle = printer.nozzle().await.unwrap();
+    assert_eq!(
+        nozzle,
+        Temperatures {
+            actual: Celsius(220.2),
+            target: Celsius(220.0),
+        }
+    );
+    assert!(!nozzle.is_heating(1.0));
+
+    let bed = printer.bed().await.unwrap();
+    assert_eq!(bed.target, Celsius(70.0));
+    assert!(bed.is_heating(0.1));
+
+    mock.assert();
+}
+
+#[test]
+async fn get_printer_mk4_telemetry() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer_with_telemetry(MK4_TELEMETRY);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    assert_eq!(raw_printer.get_bed_temp(), Celsius(60.1));
+    assert_eq!(raw_printer.get_nozzle_temp(), Celsius(215.3));
+    assert_eq!(raw_printer.get_material_telemetry(), Some("PLA"));
+    assert_eq!(raw_printer.get_material(), Some(Material::Pla));
+    assert_eq!(raw_printer.get_z_height_telemetry(), Some(0.2));
+    assert_eq!(raw_printer.get_print_speed_telemetry(), Some(100.0));
+    assert_eq!(raw_printer.position(), AxisPosition::default());
+
+    mock.assert();
+}
+
+#[test]
+async fn get_printer_mini_telemetry() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer_with_telemetry(MINI_TELEMETRY);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    assert_eq!(raw_printer.get_bed_temp(), Celsius(59.9));
+    assert_eq!(raw_printer.get_nozzle_temp(), Celsius(214.8));
+    assert_eq!(raw_printer.get_material_telemetry(), None);
+    assert_eq!(raw_printer.get_z_height_telemetry(), Some(1.4));
+    assert_eq!(raw_printer.get_print_speed_telemetry(), Some(95.0));
+
+    mock.assert();
+}
+
+#[test]
+async fn telemetry_without_temperatures_falls_back() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) =
+        mock_api_printer_with_telemetry(r#"{ "material": "PETG" }"#);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    assert_eq!(raw_printer.get_bed_temp(), Celsius(69.7));
+    assert_eq!(raw_printer.get_nozzle_temp(), Celsius(220.2));
+    assert_eq!(raw_printer.get_z_height_telemetry(), None);
+    assert_eq!(raw_printer.get_print_speed_telemetry(), None);
+
+    mock.assert();
+}
+
+#[test]
+async fn loaded_material_from_telemetry() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) =
+        mock_api_printer_with_telemetry(r#"{ "material": "PETG" }"#);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(
+        printer.loaded_material().await.unwrap(),
+        Some(Material::Petg)
+    );
+
+    mock.assert();
+}
+
+#[test]
+async fn raw_printer_blocks_are_public() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    let flags = raw_printer.flags().clone();
+    assert!(matches!(
+        flags,
+        PrinterFlags {
+            printing: true,
+            error: false,
+            ..
+        }
+    ));
+    assert_eq!(flags.link_state, "PRINTING");
+    assert_eq!(
+        raw_printer.temperature().nozzle(),
+        Some(&Temp {
+            actual: 220.2,
+            target: 220.0,
+        })
+    );
+    assert_eq!(raw_printer.telemetry().axis_z, Some(16.8));
+    assert_eq!(raw_printer.storage().sd_card, None);
+    assert_eq!(raw_printer.clone(), raw_printer);
+
+    mock.assert();
+}
+
+#[test]
+async fn printing_predicates() {
+    #[allow(unused)]
+    let (mut server, mock, address, port, api_key) = mock_api_printer();
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert!(printer.is_printing().await.unwrap());
+    assert!(!printer.is_idle().await.unwrap());
+    assert!(!printer.has_error().await.unwrap());
+    assert_eq!(printer.progress_percent().await.unwrap(), Some(91.0));
+    assert_eq!(
+        printer.time_remaining().await.unwrap(),
+        Some(std::time::Duration::from_secs(600))
+    );
+
+    // Everything should have been served from the cache after the first request
+    mock.assert();
+    job_mock.expect(1).assert();
+}
+
+#[test]
+async fn octoprint_compatibility_mode_cached_getters() {
+    use prusa_link_rs::compat::Compatibility;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(OCTOPRINT_PRINTER_BODY)
+        .create();
+
+    let mut printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .compatibility(Compatibility::OctoPrint)
+        .build();
+
+    // Refreshes, the second getter uses the cached information
+    assert!(printer.is_printing().await.unwrap());
+    assert!(printer.get_nozzle_temp().await.is_ok());
+
+    mock.assert();
+}
+
+#[test]
+async fn no_job_has_no_progress() {
+    let (mut server, address, port, api_key) = mock_base();
+    let job_mock = server
+        .mock("GET", "/api/v1/job")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(printer.progress_percent().await.unwrap(), None);
+    assert_eq!(printer.time_remaining().await.unwrap(), None);
+
+    job_mock.assert();
+}
+
+#[test]
+async fn progress_combines_job_and_status() {
+    #[allow(unused)]
+    let (mut server, mock, address, port, api_key) = mock_api_printer();
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+    let status_mock = mock_api_v1_status(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let progress = printer.progress().await.unwrap().unwrap();
+    assert_eq!(progress.percent, 91.0);
+    assert_eq!(progress.current_layer_height, Some(2.4));
+    assert_eq!(progress.time_elapsed, std::time::Duration::from_secs(6547));
+    assert_eq!(
+        progress.time_remaining,
+        Some(std::time::Duration::from_secs(600))
+    );
+    assert!(progress.eta.is_some());
+
+    job_mock.assert();
+    status_mock.assert();
+}
+
+/// Returns the `/api/printer` body with the printing flag and link state replaced
+fn api_printer_body_in_state(printing: bool, link_state: &str) -> String {
+    API_PRINTER_BODY
+        .replace("{telemetry}", MK3_TELEMETRY)
+        .replace(
+            r#""printing": true"#,
+            &format!(r#""printing": {}"#, printing),
+        )
+        .replace(r#""PRINTING""#, &format!(r#""{}""#, link_state))
+}
+
+#[test]
+async fn watch_yields_print_events() {
+    use futures::StreamExt;
+    use prusa_link_rs::events::PrinterEvent;
+
+    let (mut server, address, port, api_key) = mock_base();
+
+    // Mockito serves the first mock that still expects hits, so these are returned in order
+    let mocks = [
+        api_printer_body_in_state(false, "IDLE"),
+        api_printer_body_in_state(true, "PRINTING"),
+        api_printer_body_in_state(false, "FINISHED"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .match_header("X-Api-Key", api_key.as_str())
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    // A zero interval polls at the shortest interval instead of panicking
+    let events = printer
+        .watch(std::time::Duration::ZERO)
+        .take(4)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(
+        events,
+        vec![
+            PrinterEvent::StateChanged {
+                from: LinkState::Idle,
+                to: LinkState::Printing,
+            },
+            PrinterEvent::PrintStarted,
+            PrinterEvent::StateChanged {
+                from: LinkState::Printing,
+                to: LinkState::Finished,
+            },
+            PrinterEvent::PrintFinished,
+        ]
+    );
+
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[test]
+async fn watch_records_job_history() {
+    use futures::StreamExt;
+    use prusa_link_rs::job_history::JobOutcome;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+
+    let file_mock = server
+        .mock("GET", "/api/v1/files/usb/BENCHY~1.GCO")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "PRINT_FILE",
+                "name": "BENCHY~1.GCO",
+                "meta": { "filament used [g]": 12.5, "filament used [mm]": 4180.2 }
+            }"#,
+        )
+        .create();
+
+    let _printer_mocks = [
+        api_printer_body_in_state(false, "IDLE"),
+        api_printer_body_in_state(true, "PRINTING"),
+        api_printer_body_in_state(false, "FINISHED"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .match_header("X-Api-Key", api_key.as_str())
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).job_history().build();
+
+    printer
+        .watch(std::time::Duration::from_millis(10))
+        .take(4)
+        .for_each(|_| async {})
+        .await;
+
+    let history = printer.job_history().unwrap();
+    assert_eq!(history.len(), 1);
+
+    let record = history.latest().unwrap();
+    assert_eq!(record.id, Some(JobId(297)));
+    assert_eq!(
+        record.file_name.as_deref(),
+        Some("benchy_0.4n_0.2mm_PLA_MK4_1h2m.gcode")
+    );
+    assert_eq!(record.outcome, Some(JobOutcome::Finished));
+    assert_eq!(record.filament_used_g, Some(12.5));
+    assert!(record.duration().is_some());
+
+    // Only read for the start, the end closes the running record
+    job_mock.assert();
+    file_mock.assert();
+}
+
+#[test]
+async fn run_calls_event_handlers() {
+    use prusa_link_rs::events::{EventKind, PrinterEvent};
+
+    let (mut server, address, port, api_key) = mock_base();
+
+    let _mocks = [
+        api_printer_body_in_state(true, "PRINTING"),
+        api_printer_body_in_state(false, "FINISHED"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .match_header("X-Api-Key", api_key.as_str())
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    printer.on(EventKind::PrintFinished, move |event| {
+        let tx = tx.clone();
+        async move {
+            tx.send(event).unwrap();
+        }
+    });
+
+    let runner = tokio::spawn(async move {
+        printer.run(std::time::Duration::from_millis(10)).await;
+    });
+
+    assert_eq!(rx.recv().await, Some(PrinterEvent::PrintFinished));
+
+    runner.abort();
+}
+
+#[test]
+async fn system_commands_need_confirmation() {
+    use prusa_link_rs::error::PrinterError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let reboot_mock = server
+        .mock("POST", "/api/system/commands/core/reboot")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .expect(1)
+        .create();
+    let shutdown_mock = server
+        .mock("POST", "/api/system/commands/core/shutdown")
+        .with_status(404)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    assert!(printer.reboot(false).await.is_err());
+    printer.reboot(true).await.unwrap();
+
+    let err = printer.shutdown(true).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::Unsupported(_))
+    ));
+
+    reboot_mock.assert();
+    shutdown_mock.assert();
+}
+
+#[test]
+async fn delete_job_refuses_active_jobs() {
+    use prusa_link_rs::{error::PrinterError, raw_job::JobId};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _job_mock = mock_api_v1_job(&mut server, &api_key);
+    let active_mock = server
+        .mock("DELETE", "/api/v1/job/297")
+        .with_status(204)
+        .expect(0)
+        .create();
+    let finished_mock = server
+        .mock("DELETE", "/api/v1/job/12")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .expect(1)
+        .create();
+    let conflict_mock = server
+        .mock("DELETE", "/api/v1/job/13")
+        .with_status(409)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let err = printer.delete_job(JobId(297)).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::JobActive(JobId(297)))
+    ));
+
+    printer.delete_job(JobId(12)).await.unwrap();
+
+    let err = printer.delete_job(JobId(13)).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::JobActive(JobId(13)))
+    ));
+
+    active_mock.assert();
+    finished_mock.assert();
+    conflict_mock.assert();
+}
+
+#[test]
+async fn wait_until_finished_returns_final_state() {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let mocks = [
+        api_printer_body_in_state(true, "PRINTING"),
+        api_printer_body_in_state(false, "STOPPED"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    // A zero interval polls at the shortest interval instead of panicking
+    let state = printer
+        .wait_until_finished(std::time::Duration::ZERO, std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(state, LinkState::Stopped);
+
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[test]
+async fn commands_invalidate_the_cache() {
+    use prusa_link_rs::raw_job::JobId;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let pause_mock = server
+        .mock("PUT", "/api/v1/job/297/pause")
+        .with_status(204)
+        .create();
+
+    let mocks = [
+        api_printer_body_in_state(true, "PRINTING"),
+        api_printer_body_in_state(false, "PAUSED"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder
+        .port(port.into())
+        .auto_refresh(std::time::Duration::from_secs(60))
+        .build();
+
+    assert!(printer.is_printing().await.unwrap());
+
+    printer.pause_job(JobId(297)).await.unwrap();
+
+    assert!(!printer.is_printing().await.unwrap());
+    assert_eq!(printer.state().await.unwrap(), LinkState::Paused);
+
+    pause_mock.assert();
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[test]
+async fn job_commands_without_id() {
+    use prusa_link_rs::{error::PrinterError, raw_job::JobId};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+    let pause_mock = server
+        .mock("PUT", "/api/v1/job/297/pause")
+        .with_status(204)
+        .expect(2)
+        .create();
+
+    let mocks = [
+        api_printer_body_in_state(true, "PRINTING"),
+        api_printer_body_in_state(false, "PAUSED"),
+    ]
+    .map(|body| {
+        server
+            .mock("GET", "/api/printer")
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create()
+    });
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key.clone());
+    let mut printer = printer_builder
+        .port(port.into())
+        .auto_refresh(std::time::Duration::from_millis(10))
+        .build();
+
+    assert_eq!(printer.try_pause().await.unwrap(), JobId(297));
+
+    let state = printer
+        .pause_and_confirm(std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(state, LinkState::Paused);
+
+    job_mock.remove();
+    let _no_job = server.mock("GET", "/api/v1/job").with_status(204).create();
+
+    let err = printer.try_stop().await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::NoActiveJob)
+    );
+
+    pause_mock.assert();
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[test]
+async fn ensure_ready_refuses_a_printing_printer() {
+    use prusa_link_rs::{
+        error::PrinterError,
+        ready::{NotReadyReason, ReadyCheck},
+    };
+
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let err = printer.ensure_ready(ReadyCheck::new()).await.unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::NotReady(NotReadyReason::Busy(
+            LinkState::Printing
+        )))
+    );
+
+    mock.assert();
+}
+
+#[test]
+async fn wait_for_state_times_out() {
+    use prusa_link_rs::error::PrinterError;
+
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let timeout = std::time::Duration::from_millis(50);
+    let err = printer
+        .wait_for_state(
+            LinkState::Idle,
+            std::time::Duration::from_millis(10),
+            timeout,
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::Timeout(timeout))
+    );
+}
+
+#[test]
+async fn wait_for_temps() {
+    use prusa_link_rs::error::PrinterError;
+
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder
+        .port(port.into())
+        .auto_refresh(std::time::Duration::from_millis(10))
+        .build();
+
+    let timeout = std::time::Duration::from_millis(50);
+
+    printer
+        .wait_for_nozzle_temp(220.0, 1.0, timeout)
+        .await
+        .unwrap();
+
+    let err = printer
+        .wait_for_bed_temp(100.0, 1.0, timeout)
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::Timeout(timeout))
+    );
+}
+
+#[test]
+async fn refresh_records_telemetry_history() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder
+        .port(port.into())
+        .telemetry_history(10)
+        .build();
+
+    assert!(printer.telemetry_history().unwrap().is_empty());
+
+    printer.refresh().await.unwrap();
+
+    let history = printer.telemetry_history().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.latest().unwrap().nozzle_temp, 220.2);
+    assert_eq!(history.latest().unwrap().z_height, Some(16.8));
+}
+
+#[test]
+async fn upload_file_reports_progress() {
+    use std::sync::{Arc, Mutex};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let data = vec![b'G'; 150 * 1024];
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/usb/prints/benchy.gcode")
+        .match_header("X-Api-Key", api_key.as_str())
+        .match_body(data.clone())
+        .with_status(201)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let progress = reports.clone();
+
+    printer
+        .upload_file_with_progress("usb", "/prints/benchy.gcode", data, move |sent, total| {
+            progress.lock().unwrap().push((sent, total))
+        })
+        .await
+        .unwrap();
+
+    let total = 150 * 1024;
+    assert_eq!(
+        *reports.lock().unwrap(),
+        vec![(64 * 1024, total), (128 * 1024, total), (total, total)]
+    );
+
+    mock.assert();
+}
+
+#[test]
+async fn upload_file_rejected() {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .with_status(409)
+        .with_body("File already exists")
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let err = printer
+        .upload_file("usb", "benchy.gcode", b"G28".to_vec())
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("File already exists"));
+
+    mock.assert();
+}
+
+#[test]
+async fn upload_errors_are_typed() {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let exists = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .with_status(409)
+        .with_body("File already exists")
+        .create();
+    let printing = server
+        .mock("PUT", "/api/v1/files/usb/printing.gcode")
+        .with_status(409)
+        .with_body("File is currently being printed")
+        .create();
+    let large = server
+        .mock("PUT", "/api/v1/files/usb/large.gcode")
+        .with_status(413)
+        .with_body("Not enough space")
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let upload = |path: &'static str| printer.upload_file("usb", path, b"G28".to_vec());
+    let printer_error = |err: Box<dyn std::error::Error>| {
+        err.downcast::<prusa_link_rs::error::PrinterError>()
+            .map(|err| *err)
+            .unwrap()
+    };
+
+    assert_eq!(
+        printer_error(upload("benchy.gcode").await.unwrap_err()),
+        prusa_link_rs::error::PrinterError::FileExists {
+            path: "benchy.gcode".to_string(),
+            message: "File already exists".to_string(),
+        }
+    );
+    assert_eq!(
+        printer_error(upload("printing.gcode").await.unwrap_err()),
+        prusa_link_rs::error::PrinterError::FileInUse {
+            path: "printing.gcode".to_string(),
+            message: "File is currently being printed".to_string(),
+        }
+    );
+    assert_eq!(
+        printer_error(upload("large.gcode").await.unwrap_err()),
+        prusa_link_rs::error::PrinterError::FileTooLarge {
+            path: "large.gcode".to_string(),
+            message: "Not enough space".to_string(),
+        }
+    );
+
+    exists.assert();
+    printing.assert();
+    large.assert();
+}
+
+#[test]
+async fn upload_reader_streams_body() {
+    let (mut server, address, port, api_key) = mock_base();
+    let data = b"G28\nG1 X10 Y10\n".repeat(10_000);
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/local/benchy.gcode")
+        .match_header("Content-Length", data.len().to_string().as_str())
+        .match_body(data.clone())
+        .with_status(201)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let length = data.len() as u64;
+    printer
+        .upload_reader("local", "benchy.gcode", std::io::Cursor::new(data), length)
+        .await
+        .unwrap();
+
+    mock.assert();
+}
+
+#[test]
+async fn upload_local_file_from_disk() {
+    let (mut server, address, port, api_key) = mock_base();
+    let data = b"G28\n".repeat(1000);
+
+    let local_path = std::env::temp_dir().join("prusa_link_rs_upload_local_file.gcode");
+    std::fs::write(&local_path, &data).unwrap();
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .match_body(data)
+        .with_status(201)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    printer
+        .upload_local_file("usb", "benchy.gcode", &local_path)
+        .await
+        .unwrap();
+
+    std::fs::remove_file(local_path).unwrap();
+    mock.assert();
+}
+
+#[test]
+async fn upload_and_print_returns_job() {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .match_header("Print-After-Upload", "?1")
+        .match_body("G28\n")
+        .with_status(201)
+        .create();
+    // Mockito serves the first mock that still expects hits, there is no job before the upload
+    let no_job_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(204)
+        .expect(1)
+        .create();
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let job = printer
+        .upload_and_print("usb", "benchy.gcode", b"G28\n".to_vec())
+        .await
+        .unwrap();
+
+    assert_eq!(job.get_id(), JobId(297));
+
+    upload_mock.assert();
+    no_job_mock.assert();
+    job_mock.assert();
+}
+
+/// The finished job of an earlier print of `benchy.gcode`
+fn stale_job_body() -> String {
+    API_V1_JOB_BODY
+        .replace(r#""PRINTING""#, r#""FINISHED""#)
+        .replace("benchy_0.4n_0.2mm_PLA_MK4_1h2m.gcode", "benchy.gcode")
+}
+
+#[test]
+async fn upload_and_print_skips_the_previous_job() {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let _upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .with_status(201)
+        .create();
+    // Before the upload and on the first poll, the printer still reports the finished job
+    let stale_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(200)
+        .with_body(stale_job_body())
+        .expect(2)
+        .create();
+    let new_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(200)
+        .with_body(API_V1_JOB_BODY.replace(r#""id": 297"#, r#""id": 298"#))
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let job = printer
+        .upload_and_print("usb", "benchy.gcode", b"G28\n".to_vec())
+        .await
+        .unwrap();
+
+    assert_eq!(job.get_id(), JobId(298));
+
+    stale_mock.assert();
+    new_mock.assert();
+}
+
+#[test]
+async fn upload_and_print_times_out_on_a_stale_job() {
+    use prusa_link_rs::error::PrinterError;
+
+    let (mut server, address, port, api_key) = mock_base();
+
+    let _upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .with_status(201)
+        .create();
+    let _stale_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(200)
+        .with_body(stale_job_body())
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let err = printer
+        .upload_and_print("usb", "benchy.gcode", b"G28\n".to_vec())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::Timeout(_))
+    ));
+}
+
+/// Start of a binary g-code file: magic bytes, version 1 and CRC32 checksums
+const BGCODE_HEADER: &[u8] = b"GCDE\x01\x00\x00\x00\x01\x00";
+
+#[test]
+async fn upload_bgcode_as_octet_stream() {
+    let (mut server, address, port, api_key) = mock_base();
+    let data = [BGCODE_HEADER, &[0; 100]].concat();
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.bgcode")
+        .match_header("Content-Type", "application/octet-stream")
+        .match_body(data.clone())
+        .with_status(201)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let length = data.len() as u64;
+    printer
+        .upload_reader("usb", "benchy.bgcode", std::io::Cursor::new(data), length)
+        .await
+        .unwrap();
+
+    mock.assert();
+}
+
+#[test]
+async fn corrupted_bgcode_is_rejected_locally() {
+    use prusa_link_rs::error::PrinterError;
+
+    let (mut server, address, port, api_key) = mock_base();
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.bgcode")
+        .with_status(201)
+        .expect(0)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let err = printer
+        .upload_file("usb", "benchy.bgcode", b"G28\nG1 X10\n".to_vec())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::InvalidFile(_))
+    ));
+
+    mock.assert();
+}
+
+#[test]
+async fn bgcode_header_validation() {
+    use prusa_link_rs::upload::*;
+
+    assert_eq!(content_type("benchy.BGCODE"), "application/octet-stream");
+    assert_eq!(content_type("benchy.gcode"), "text/x.gcode");
+
+    assert!(validate_bgcode_header(BGCODE_HEADER).is_ok());
+    assert!(validate_bgcode_header(b"GCDE").is_err());
+    assert!(validate_bgcode_header(b"GCDE\x02\x00\x00\x00\x01\x00").is_err());
+    assert!(validate_bgcode_header(b"GCDE\x01\x00\x00\x00\x07\x00").is_err());
+}
+
+#[test]
+async fn get_storage_info() {
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = mock_api_v1_storage(&mut server, &api_key, 1000);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let storages = printer.get_storage_info().await.unwrap();
+
+    assert_eq!(storages.len(), 2);
+    assert!(storages[0].matches("/usb"));
+    assert_eq!(storages[0].free_space, Some(1000));
+    assert_eq!(storages[1].free_space, None);
+    assert!(!storages[1].available);
+
+    mock.assert();
+}
+
+#[test]
+async fn storage_space_from_cache_then_storage_list() {
+    #[allow(unused)]
+    let (mut server, mock, address, port, api_key) = mock_api_printer();
+    let _storage_mock = mock_api_v1_storage(&mut server, &api_key, 1000);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    // The local storage is in the storage block of `/api/printer`
+    assert_eq!(
+        printer.free_space("local").await.unwrap(),
+        Some(56813572096)
+    );
+    assert_eq!(
+        printer.total_space("/local").await.unwrap(),
+        Some(61273088000)
+    );
+
+    // The usb stick is only in `/api/v1/storage`
+    assert_eq!(printer.free_space("usb").await.unwrap(), Some(1000));
+    assert_eq!(printer.free_space("sdcard").await.unwrap(), None);
+
+    mock.assert();
+}
+
+#[test]
+async fn upload_larger_than_free_space_is_rejected() {
+    use prusa_link_rs::error::PrinterError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let storage_mock = mock_api_v1_storage(&mut server, &api_key, 1000);
+    let upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .expect(0)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let err = printer
+        .upload_file("usb", "benchy.gcode", vec![b'G'; 1500])
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::InsufficientStorage {
+            needed: 1500,
+            available: 1000,
+        })
+    );
+
+    storage_mock.assert();
+    upload_mock.assert();
+}
+
+#[test]
+async fn upload_free_space_check_errors_are_returned() {
+    let (mut server, address, port, api_key) = mock_base();
+    let storage_mock = server
+        .mock("GET", "/api/v1/storage")
+        .with_status(401)
+        .create();
+    let upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .expect(0)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    assert!(printer
+        .upload_file("usb", "benchy.gcode", b"G28".to_vec())
+        .await
+        .is_err());
+
+    storage_mock.assert();
+    upload_mock.assert();
+}
+
+#[test]
+async fn upload_skips_free_space_check_without_storage_endpoint() {
+    let (mut server, address, port, api_key) = mock_base();
+    let storage_mock = server
+        .mock("GET", "/api/v1/storage")
+        .with_status(404)
+        .create();
+    let upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .with_status(201)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    printer
+        .upload_file("usb", "benchy.gcode", b"G28".to_vec())
+        .await
+        .unwrap();
+
+    storage_mock.assert();
+    upload_mock.assert();
+}
+
+#[test]
+async fn download_thumbnails() {
+    use prusa_link_rs::raw_job::ThumbnailSize;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+    let png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let small_mock = server
+        .mock("GET", "/thumb/s/usb/BENCHY~1.GCO")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_header("Content-Type", "image/png")
+        .with_body(&png)
+        .create();
+    let large_mock = server
+        .mock("GET", "/thumb/l/usb/BENCHY~1.GCO")
+        .with_status(404)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let job = printer.get_job_info().await.unwrap().unwrap();
+    let refs = job.get_file().unwrap().refs.as_ref().unwrap();
+
+    assert_eq!(
+        printer
+            .get_thumbnail(refs, ThumbnailSize::Small)
+            .await
+            .unwrap(),
+        Some(png)
+    );
+    assert_eq!(
+        printer
+            .get_thumbnail(refs, ThumbnailSize::Large)
+            .await
+            .unwrap(),
+        None
+    );
+
+    job_mock.assert();
+    small_mock.assert();
+    large_mock.assert();
+}
+
+#[test]
+async fn xl_reports_every_tool() {
+    let (mut server, address, port, api_key) = mock_base();
+
+    let tools = (0..5)
+        .map(|index| {
+            format!(
+                r#""tool{}": {{ "actual": {}.0, "target": 0.0 }}"#,
+                index,
+                30 + index
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let body = API_PRINTER_BODY
+        .replace("{telemetry}", MK4_TELEMETRY)
+        .replace(
+            r#""tool0": {
+            "actual": 220.2,
+            "target": 220.0
+        },"#,
+            &format!("{},", tools),
+        );
+
+    let mock = server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(body)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    assert_eq!(raw_printer.tool_count(), 5);
+    assert_eq!(raw_printer.get_tool_temp(4), Some(Celsius(34.0)));
+    assert_eq!(raw_printer.get_target_tool_temp(4), Some(Celsius(0.0)));
+    assert_eq!(raw_printer.get_tool_temp(5), None);
+    assert_eq!(raw_printer.get_target_nozzle_temp(), Celsius(0.0));
+
+    // The temperature block is serialized back into separate tool keys
+    let json = serde_json::to_value(raw_printer.temperature()).unwrap();
+    assert_eq!(json["tool3"]["actual"], 33.0);
+
+    mock.assert();
+}
+
+#[test]
+async fn mmu_and_filament_sensor() {
+    let (mut server, address, port, api_key) = mock_base();
+    let status_mock = mock_api_v1_status(&mut server, &api_key);
+    let info_mock = mock_api_v1_info(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert!(printer.has_mmu().await.unwrap());
+    assert_eq!(printer.active_slot().await.unwrap(), Some(2));
+    assert_eq!(printer.filament_detected().await.unwrap(), Some(true));
+
+    let status = printer.get_status().await.unwrap();
+    assert_eq!(status.state(), LinkState::Printing);
+    assert_eq!(
+        status.printer().slot.as_ref().unwrap().slots["2"]
+            .material
+            .as_deref(),
+        Some("PETG")
+    );
+
+    status_mock.expect(2).assert();
+    info_mock.assert();
+}
+
+#[test]
+async fn fans_and_overrides() {
+    let (mut server, address, port, api_key) = mock_base();
+    let status_mock = mock_api_v1_status(&mut server, &api_key);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(
+        printer.fan_speeds().await.unwrap(),
+        (Some(4523), Some(3012))
+    );
+    assert_eq!(
+        printer.speed_and_flow().await.unwrap(),
+        (Some(100), Some(100))
+    );
+    assert!(!printer.hotend_fan_stalled().await.unwrap());
+    assert_eq!(printer.current_error().await.unwrap(), None);
+
+    status_mock.assert();
+}
+
+#[test]
+async fn stalled_hotend_fan() {
+    let (mut server, address, port, api_key) = mock_base();
+    let status_mock = server
+        .mock("GET", "/api/v1/status")
+        .with_status(200)
+        .with_body(API_V1_STATUS_BODY.replace(r#""fan_hotend": 4523"#, r#""fan_hotend": 0"#))
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert!(printer.hotend_fan_stalled().await.unwrap());
+
+    status_mock.assert();
+}
+
+#[test]
+async fn current_error_from_status() {
+    use prusa_link_rs::raw_status::PrinterErrorInfo;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let status_mock = server
+        .mock("GET", "/api/v1/status")
+        .with_status(200)
+        .with_body(
+            API_V1_STATUS_BODY
+                .replace(r#""state": "PRINTING""#, r#""state": "ATTENTION""#)
+                .replace(
+                    r#""filament_sensor": true,"#,
+                    r#""filament_sensor": false,
+        "status_printer": { "ok": false, "message": "Filament runout", "code": 17505 },
+        "status_connect": { "ok": true },"#,
+                ),
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(
+        printer.current_error().await.unwrap(),
+        Some(PrinterErrorInfo {
+            state: LinkState::Attention,
+            code: Some(17505),
+            text: "Filament runout".to_string(),
+        })
+    );
+
+    status_mock.assert();
+}
+
+#[test]
+async fn prusa_connect_registration() {
+    use prusa_link_rs::connect::{ConnectRegistration, ConnectServer, RegistrationCode};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let status_mock = server
+        .mock("GET", "/api/connection")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+    "connect": {
+        "hostname": "connect.prusa3d.com",
+        "port": 443,
+        "tls": true,
+        "registration": "NO_REGISTRATION",
+        "custom_cert": false
+    },
+    "printer": { "port": "/dev/ttyAMA0", "baudrate": 115200 }
+}"#,
+        )
+        .create();
+    let register_mock = server
+        .mock("POST", "/api/connection")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "connect": { "hostname": "connect.prusa3d.com", "port": 443, "tls": true }
+        })))
+        .with_status(200)
+        .with_body(r#"{ "url": "https://connect.prusa3d.com/add-printer/connect/1.3.1/b4c2e3a1" }"#)
+        .create();
+    let unregister_mock = server
+        .mock("DELETE", "/api/connection")
+        .with_status(204)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let status = printer.connect_status().await.unwrap();
+    assert_eq!(status.registration(), ConnectRegistration::NotRegistered);
+    assert_eq!(status.server(), ConnectServer::default());
+
+    assert_eq!(
+        printer
+            .register_connect(&ConnectServer::default())
+            .await
+            .unwrap(),
+        RegistrationCode {
+            code: "b4c2e3a1".to_string(),
+            url: "https://connect.prusa3d.com/add-printer/connect/1.3.1/b4c2e3a1".to_string(),
+        }
+    );
+
+    printer.unregister_connect().await.unwrap();
+
+    status_mock.assert();
+    register_mock.assert();
+    unregister_mock.assert();
+}
+
+#[test]
+async fn get_network_info_with_wifi() {
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let network_mock = server
+        .mock("GET", "/api/v1/network")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+    "hostname": "prusa-mk4",
+    "interfaces": [
+        { "name": "eth0", "type": "ethernet", "mac": "10:9c:70:2a:00:01" },
+        {
+            "name": "wlan0",
+            "type": "wifi",
+            "mac": "10:9c:70:2a:00:02",
+            "ipv4": "192.168.1.52",
+            "netmask": "255.255.255.0",
+            "gateway": "192.168.1.1",
+            "dns": ["192.168.1.1"],
+            "dhcp": true,
+            "ssid": "farm",
+            "rssi": -71
+        }
+    ]
+}"#,
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let network = printer.get_network_info().await.unwrap();
+    let wifi = network.wifi().unwrap();
+
+    assert_eq!(network.hostname.as_deref(), Some("prusa-mk4"));
+    assert_eq!(wifi.ssid.as_deref(), Some("farm"));
+    assert_eq!(wifi.signal_quality(), Some(58));
+    assert_eq!(network.active(), Some(wifi));
+
+    network_mock.assert();
+}
+
+#[test]
+async fn address_with_scheme_and_port() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder =
+        prusa_link_rs::PrinterBuilder::new(format!("http://{}:{}/", address, port), api_key);
+    let mut printer = printer_builder.try_build().unwrap();
+
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+
+    mock.assert();
+}
+
+#[test]
+async fn requests_go_through_proxy() {
+    use prusa_link_rs::{error::BuildError, PrinterBuilder};
+
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    // The mock server answers as the proxy, the printer itself can't be resolved
+    let mut printer = PrinterBuilder::new("prusa-mk4.invalid", api_key)
+        .proxy(format!("http://{}:{}", address, port))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+    mock.assert();
+
+    assert!(matches!(
+        PrinterBuilder::new("192.168.1.50", "key")
+            .proxy("not a url")
+            .try_build()
+            .unwrap_err(),
+        BuildError::InvalidProxy(_)
+    ));
+}
+
+#[test]
+async fn custom_headers_are_sent_with_every_request() {
+    use prusa_link_rs::{error::BuildError, PrinterBuilder};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/version")
+        .match_header("X-Api-Key", api_key.as_str())
+        .match_header("Authorization", "Bearer 0123456789")
+        .match_header("User-Agent", "farm-dashboard/1.0")
+        .with_status(200)
+        .with_body(API_VERSION_BODY)
+        .create();
+
+    let printer = PrinterBuilder::new(address, api_key)
+        .port(port.into())
+        .header("Authorization", "Bearer 0123456789")
+        .user_agent("farm-dashboard/1.0")
+        .try_build()
+        .unwrap();
+
+    printer.get_version().await.unwrap();
+    assert!(!format!("{:?}", printer).contains("0123456789"));
+
+    mock.assert();
+
+    assert_eq!(
+        PrinterBuilder::new("192.168.1.50", "key")
+            .header("Bad Header", "value")
+            .try_build()
+            .unwrap_err(),
+        BuildError::InvalidHeader("Bad Header".to_string())
+    );
+}
+
+#[test]
+async fn rotate_api_key_keeps_old_key_on_failure() {
+    use prusa_link_rs::error::ConnectionError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _rejected_mock = server
+        .mock("GET", "/api/version")
+        .match_header("X-Api-Key", "wrong_key")
+        .with_status(401)
+        .create();
+    let _version_mock = mock_api_version(&mut server, "new_key");
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key.clone());
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(
+        printer.rotate_api_key("wrong_key", true).await,
+        Err(ConnectionError::InvalidApiKey)
+    );
+    assert_eq!(printer.expose_api_key(), api_key);
+
+    printer.rotate_api_key("new_key", true).await.unwrap();
+    assert_eq!(printer.expose_api_key(), "new_key");
+
+    printer
+        .rotate_api_key("unchecked_key", false)
+        .await
+        .unwrap();
+    assert_eq!(printer.expose_api_key(), "unchecked_key");
+}
+
+#[test]
+async fn handle_shares_the_printer_between_tasks() {
+    use prusa_link_rs::handle::PrinterHandle;
+
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<PrinterHandle>();
+
+    let (_server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer = prusa_link_rs::PrinterBuilder::new(address, api_key)
+        .port(port.into())
+        .auto_refresh(std::time::Duration::from_secs(60))
+        .build_handle();
+
+    let poller = printer.clone();
+    tokio::spawn(async move { poller.write().await.refresh().await.unwrap() })
+        .await
+        .unwrap();
+
+    // The other clone sees the refresh of the task, so nothing is requested again
+    let info = printer.printer_info().await.unwrap();
+    assert_eq!(info.get_nozzle_temp(), Celsius(220.2));
+    assert_eq!(printer.stats().await.total().requests, 1);
+    assert!(printer.health().await.is_healthy());
+
+    mock.assert();
+}
+
+#[test]
+async fn address_from_socket_addr() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let socket = std::net::SocketAddr::new(address.parse().unwrap(), port);
+    let mut printer = prusa_link_rs::PrinterBuilder::new(socket, api_key).build();
+
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+
+    mock.assert();
+}
+
+#[test]
+async fn parse_printer_addresses() {
+    use prusa_link_rs::address::*;
+
+    let address: PrinterAddress = "prusa-mk4.local".parse().unwrap();
+    assert_eq!(address.scheme, Scheme::Http);
+    assert_eq!(address.host, "prusa-mk4.local");
+    assert_eq!(address.port_or_default(), 80);
+
+    let address: PrinterAddress = "192.168.1.50:8080".parse().unwrap();
+    assert_eq!(address.host, "192.168.1.50");
+    assert_eq!(address.port, Some(8080));
+
+    let address: PrinterAddress = "https://[fe80::1]".parse().unwrap();
+    assert_eq!(address.host, "[fe80::1]");
+    assert_eq!(address.port_or_default(), 443);
+
+    let address: PrinterAddress = "fe80::1".parse().unwrap();
+    assert_eq!(address.to_string(), "http://[fe80::1]");
+
+    assert_eq!(
+        "ftp://printer".parse::<PrinterAddress>(),
+        Err(AddressError::UnsupportedScheme("ftp".to_string()))
+    );
+    assert_eq!(
+        "printer:99999".parse::<PrinterAddress>(),
+        Err(AddressError::InvalidPort("99999".to_string()))
+    );
+    assert_eq!(
+        "http://".parse::<PrinterAddress>(),
+        Err(AddressError::Empty)
+    );
+    assert!(prusa_link_rs::PrinterBuilder::new("my printer", "key")
+        .try_build()
+        .is_err());
+}
+
+#[test]
+async fn try_build_rejects_invalid_configuration() {
+    use prusa_link_rs::{address::AddressError, error::BuildError, PrinterBuilder};
+
+    assert_eq!(
+        PrinterBuilder::new("192.168.1.50", "")
+            .try_build()
+            .unwrap_err(),
+        BuildError::EmptyApiKey
+    );
+    assert_eq!(
+        PrinterBuilder::new("192.168.1.50", "key")
+            .port(65536)
+            .try_build()
+            .unwrap_err(),
+        BuildError::InvalidPort(65536)
+    );
+    assert_eq!(
+        PrinterBuilder::new("", "key").try_build().unwrap_err(),
+        BuildError::InvalidAddress(AddressError::Empty)
+    );
+    assert_eq!(
+        PrinterBuilder::new("192.168.1.50:0", "key")
+            .try_build()
+            .unwrap_err(),
+        BuildError::InvalidAddress(AddressError::InvalidPort("0".to_string()))
+    );
+    assert!(PrinterBuilder::new("192.168.1.50", "key")
+        .port(8080)
+        .try_build()
+        .is_ok());
+}
+
+#[test]
+async fn builder_from_env() {
+    use prusa_link_rs::{error::BuildError, PrinterBuilder};
+
+    std::env::remove_var("PRUSALINK_HOST");
+    std::env::set_var("PRUSALINK_API_KEY", "key");
+    assert_eq!(
+        PrinterBuilder::from_env().unwrap_err(),
+        BuildError::MissingEnvVar("PRUSALINK_HOST")
+    );
+
+    std::env::set_var("PRUSALINK_HOST", "prusa-mk4.local");
+    std::env::set_var("PRUSALINK_PORT", "eighty");
+    assert_eq!(
+        PrinterBuilder::from_env().unwrap_err(),
+        BuildError::InvalidEnvVar("PRUSALINK_PORT", "eighty".to_string())
+    );
+
+    std::env::set_var("PRUSALINK_PORT", "8080");
+    let printer = PrinterBuilder::from_env().unwrap().try_build().unwrap();
+    assert_eq!(printer.address(), "prusa-mk4.local");
+    assert_eq!(printer.to_config().port, Some(8080));
+}
+
+#[test]
+async fn printer_config_round_trip() {
+    use prusa_link_rs::{compat::Compatibility, config::PrinterConfig, Printer};
+    use std::time::Duration;
+
+    let config: PrinterConfig = serde_json::from_str(
+        r#"{"address": "https://farm.local:8443", "api_key": "key", "refresh": 0.5, "base_path": "/printers/mk4a", "compatibility": "octoprint"}"#,
+    )
+    .unwrap();
+
+    let printer = Printer::from_config(&config).unwrap();
+    assert_eq!(printer.address(), "farm.local");
+
+    let config = printer.to_config();
+    assert_eq!(config.address, "https://farm.local");
+    assert_eq!(config.port, Some(8443));
+    assert_eq!(config.refresh, Duration::from_millis(500));
+    assert_eq!(config.base_path.as_deref(), Some("/printers/mk4a"));
+    assert_eq!(config.compatibility, Compatibility::OctoPrint);
+    assert!(format!("{:?}", config).contains("REDACTED"));
+
+    let defaults: PrinterConfig =
+        serde_json::from_str(r#"{"address": "192.168.1.50", "api_key": "key"}"#).unwrap();
+    assert_eq!(
+        serde_json::to_value(&defaults).unwrap(),
+        serde_json::json!({
+            "address": "192.168.1.50",
+            "api_key": "key",
+            "refresh": 2.0,
+            "compatibility": "prusalink",
+        })
+    );
+}
+
+#[test]
+async fn requests_go_below_base_path() {
+    let (mut server, address, port, api_key) = mock_base();
+    let printer_mock = server
+        .mock("GET", "/printers/mk4a/api/printer")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_PRINTER_BODY.replace("{telemetry}", MK3_TELEMETRY))
+        .create();
+    let status_mock = server
+        .mock("GET", "/printers/mk4a/api/v1/status")
+        .with_status(200)
+        .with_body(API_V1_STATUS_BODY)
+        .create();
+
+    let mut printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .base_path("printers/mk4a/")
+        .build();
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+
+    let printer = prusa_link_rs::PrinterBuilder::new(
+        format!("http://{}:{}/printers/mk4a", address, port),
+        &api_key,
+    )
+    .try_build()
+    .unwrap();
+    assert!(printer.get_status().await.is_ok());
+
+    printer_mock.assert();
+    status_mock.assert();
+}
+
+#[test]
+async fn change_address_moves_requests_and_drops_cache() {
+    let (_old_server, old_mock, address, port, api_key) = mock_api_printer();
+    let (mut server, new_address, new_port, _) = mock_base();
+    let new_mock = server
+        .mock("GET", "/printers/mk4a/api/printer")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_PRINTER_BODY.replace("{telemetry}", MK3_TELEMETRY))
+        .create();
+
+    let mut printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+    assert!(printer.get_nozzle_temp().await.is_ok());
+
+    assert!(printer.change_address("ftp://printer.local").is_err());
+    printer
+        .change_address(&format!(
+            "http://{}:{}/printers/mk4a/",
+            new_address, new_port
+        ))
+        .unwrap();
+
+    // The printer information of the old address isn't served from the cache
+    assert!(printer.get_nozzle_temp().await.is_ok());
+
+    old_mock.assert();
+    new_mock.assert();
+}
+
+#[test]
+async fn check_connection_results() {
+    use prusa_link_rs::error::ConnectionError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let version_mock = mock_api_version(&mut server, &api_key);
+    let unauthorized_mock = server
+        .mock("GET", "/api/version")
+        .match_header("X-Api-Key", "wrong")
+        .with_status(401)
+        .create();
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+    assert_eq!(printer.check_connection().await, Ok(()));
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, "wrong")
+        .port(port.into())
+        .build();
+    assert_eq!(
+        printer.check_connection().await,
+        Err(ConnectionError::InvalidApiKey)
+    );
+
+    version_mock.assert();
+    unauthorized_mock.assert();
+
+    // A closed port, nothing is listening after the listener is dropped
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let printer = prusa_link_rs::PrinterBuilder::new("127.0.0.1", &api_key)
+        .port(closed_port.into())
+        .build();
+    assert!(matches!(
+        printer.check_connection().await,
+        Err(ConnectionError::Unreachable(_))
+    ));
+}
+
+#[test]
+async fn check_connection_to_something_else() {
+    use prusa_link_rs::error::ConnectionError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/version")
+        .with_status(200)
+        .with_body("<html>Welcome to nginx!</html>")
+        .create();
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+    assert!(matches!(
+        printer.check_connection().await,
+        Err(ConnectionError::WrongProtocol(_))
+    ));
+
+    mock.assert();
+}
+
+#[test]
+async fn legacy_firmware_capabilities() {
+    use prusa_link_rs::error::PrinterError;
+
+    #[allow(unused)]
+    let (mut server, printer_mock, address, port, api_key) = mock_api_printer();
+    let version_mock = server
+        .mock("GET", "/api/version")
+        .with_status(200)
+        .with_body(r#"{"api": "0.1.0", "server": "0.7.0", "text": "PrusaLink MK3"}"#)
+        .expect(1)
+        .create();
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+
+    let capabilities = printer.capabilities().await.unwrap();
+    assert!(!capabilities.v1_api);
+    assert!(!capabilities.upload_by_put);
+    assert_eq!(capabilities.server_version.as_deref(), Some("0.7.0"));
+
+    let err = printer.get_status().await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::Unsupported(
+            "the status endpoint".to_string()
+        ))
+    );
+
+    let err = printer
+        .upload_file("usb", "benchy.gcode", b"G28\n".to_vec())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::Unsupported(_))
+    ));
+
+    // Falls back to the storage block of `/api/printer`
+    let storage = printer.get_storage_info().await.unwrap();
+    assert_eq!(storage.len(), 1);
+    assert_eq!(storage[0].name, "local");
+    assert_eq!(storage[0].free_space, Some(56813572096));
+
+    version_mock.assert();
+    printer_mock.assert();
+}
+
+#[test]
+async fn v1_firmware_capabilities() {
+    let (mut server, address, port, api_key) = mock_base();
+    let version_mock = mock_api_version(&mut server, &api_key);
+    let status_mock = mock_api_v1_status(&mut server, &api_key);
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+
+    let capabilities = printer.capabilities().await.unwrap();
+    assert!(capabilities.v1_api);
+    assert!(capabilities.upload_by_put);
+    assert!(printer.get_status().await.is_ok());
+
+    version_mock.assert();
+    status_mock.assert();
+}
+
+#[test]
+async fn job_by_id_and_commands() {
+    let (mut server, address, port, api_key) = mock_base();
+    let job_mock = server
+        .mock("GET", "/api/v1/job/297")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(r#"{"id": 297, "state": "PAUSED", "progress": 42.0, "time_printing": 1800}"#)
+        .create();
+    let missing_mock = server
+        .mock("GET", "/api/v1/job/12")
+        .with_status(404)
+        .create();
+    let pause_mock = server
+        .mock("PUT", "/api/v1/job/297/pause")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .create();
+    let resume_mock = server
+        .mock("PUT", "/api/v1/job/297/resume")
+        .with_status(409)
+        .create();
+    let stop_mock = server
+        .mock("DELETE", "/api/v1/job/297")
+        .with_status(204)
+        .create();
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+
+    let job = printer.get_job(JobId(297)).await.unwrap().unwrap();
+    assert_eq!(job.get_id(), JobId(297));
+    assert_eq!(job.get_state(), "PAUSED");
+    assert_eq!(
+        job.get_time_printing(),
+        std::time::Duration::from_secs(1800)
+    );
+    assert_eq!(job.get_time_remaining(), None);
+    assert_eq!(job.get_eta(), None);
+    assert_eq!(printer.get_job(12.into()).await.unwrap(), None);
+
+    printer.pause_job(job.get_id()).await.unwrap();
+    assert!(printer.resume_job(job.get_id()).await.is_err());
+    printer.stop_job(job.get_id()).await.unwrap();
+
+    job_mock.assert();
+    missing_mock.assert();
+    pause_mock.assert();
+    resume_mock.assert();
+    stop_mock.assert();
+}
+
+/// `/api/printer` of the MK3 PrusaLink on a Raspberry Pi, without the telemetry, storage and sd blocks
+const OCTOPRINT_PRINTER_BODY: &str = r#"{
+    "temperature": {
+        "tool0": {
+            "actual": 214.9,
+            "target": 215.0,
+            "offset": 0
+        },
+        "bed": {
+            "actual": 59.8,
+            "target": 60.0,
+            "offset": 0
+        }
+    },
+    "state": {
+        "text": "Printing",
+        "flags": {
+            "operational": true,
+            "paused": false,
+            "printing": true,
+            "cancelling": false,
+            "pausing": false,
+            "error": false,
+            "ready": false,
+            "closedOrError": false
+        }
+    }
+}"#;
+
+#[test]
+async fn octoprint_compatibility_mode() {
+    use prusa_link_rs::compat::Compatibility;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(OCTOPRINT_PRINTER_BODY)
+        .expect(2)
+        .create();
+
+    let mut strict = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+    assert!(strict.get_printer_info().await.is_err());
+
+    let mut printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .compatibility(Compatibility::OctoPrint)
+        .build();
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    assert_eq!(raw_printer.link_state(), LinkState::Printing);
+    assert_eq!(raw_printer.get_nozzle_temp(), Celsius(214.9));
+    assert_eq!(raw_printer.get_bed_temp(), Celsius(59.8));
+    assert_eq!(raw_printer.get_sd_ready(), false);
+    assert_eq!(raw_printer.get_finished(), false);
+    assert_eq!(raw_printer.get_local_storage_space(), None);
+    assert_eq!(raw_printer.telemetry().material, None);
+
+    mock.assert();
+}
+
+#[test]
+async fn unknown_fields_and_missing_blocks() {
+    let (mut server, address, port, api_key) = mock_base();
+    let body = API_PRINTER_BODY.replace("{telemetry}", MK4_TELEMETRY);
+    let mut body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let object = body.as_object_mut().unwrap();
+    object.remove("telemetry");
+    object.remove("storage");
+    object.insert("camera".to_string(), serde_json::json!({ "enabled": true }));
+
+    let printer_mock = server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(body.to_string())
+        .create();
+    let job_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(200)
+        .with_body(r#"{"id": 3, "state": "PRINTING", "progress": 5.0, "time_printing": 60, "inaccurate_estimates": true}"#)
+        .create();
+
+    let mut printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+    assert_eq!(raw_printer.get_material_telemetry(), None);
+    assert_eq!(raw_printer.get_local_storage_space(), None);
+    assert_eq!(raw_printer.get_nozzle_temp(), Celsius(220.2));
+    assert_eq!(
+        raw_printer.extra().get("camera"),
+        Some(&serde_json::json!({ "enabled": true }))
+    );
+
+    let job = printer.get_job_info().await.unwrap().unwrap();
+    assert_eq!(
+        job.extra().get("inaccurate_estimates"),
+        Some(&serde_json::Value::Bool(true))
+    );
+
+    printer_mock.assert();
+    job_mock.assert();
+}
+
+#[test]
+async fn api_key_is_redacted_from_debug() {
+    let builder = prusa_link_rs::PrinterBuilder::new("192.168.1.50", "super-secret-key");
+    assert!(!format!("{:?}", builder).contains("super-secret-key"));
+
+    let mut printer = builder.build();
+    assert!(!format!("{:?}", printer).contains("super-secret-key"));
+    assert_eq!(printer.expose_api_key(), "super-secret-key");
+
+    printer.change_api_key("another-key".to_string());
+    assert!(!format!("{:?}", printer).contains("another-key"));
+    assert_eq!(printer.expose_api_key(), "another-key");
+}
+
+#[test]
+async fn request_and_response_hooks() {
+    use std::sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc,
+    };
+
+    let (mut server, address, port, api_key) = mock_base();
+    let status_mock = server
+        .mock("GET", "/api/v1/status")
+        .match_header("X-Api-Key", api_key.as_str())
+        .match_header("X-Forwarded-User", "octofarm")
+        .with_status(200)
+        .with_body(API_V1_STATUS_BODY)
+        .create();
+
+    let last_status = Arc::new(AtomicU16::new(0));
+    let recorded_status = last_status.clone();
+
+    let printer = prusa_link_rs::PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .on_request(|request| {
+            request
+                .headers_mut()
+                .insert("X-Forwarded-User", "octofarm".parse().unwrap());
+        })
+        .on_response(move |response| {
+            recorded_status.store(response.status().as_u16(), Ordering::SeqCst);
+        })
+        .build();
+
+    assert!(printer.get_status().await.is_ok());
+    assert_eq!(last_status.load(Ordering::SeqCst), 200);
+
+    status_mock.assert();
+}
+
+/// A transport answering `/api/v1/status` itself and 404 everywhere else, recording the requested paths
+#[derive(Debug, Default, Clone)]
+struct FakeTransport {
+    paths: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl prusa_link_rs::transport::HttpTransport for FakeTransport {
+    fn send(
+        &self,
+        request: http::Request<prusa_link_rs::transport::TransportBody>,
+    ) -> prusa_link_rs::transport::TransportFuture<'_> {
+        let path = request.uri().path().to_string();
+        self.paths.lock().unwrap().push(path.clone());
+
+        Box::pin(async move {
+            let response = match path.as_str() {
+                "/api/v1/status" => http::Response::builder()
+                    .status(200)
+                    .body(API_V1_STATUS_BODY.into()),
+                _ => http::Response::builder()
+                    .status(404)
+                    .body(bytes::Bytes::new()),
+            };
+
+            Ok(response?)
+        })
+    }
+}
+
+#[test]
+async fn custom_transport() {
+    let transport = FakeTransport::default();
+
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .build();
+
+    let status = printer.get_status().await.unwrap();
+
+    assert_eq!(status.get_fan_hotend(), Some(4523));
+    assert_eq!(
+        *transport.paths.lock().unwrap(),
+        vec!["/api/version".to_string(), "/api/v1/status".to_string()]
+    );
+}
+
+#[test]
+async fn custom_transport_unreachable() {
+    use prusa_link_rs::{error::ConnectionError, transport::*};
+
+    #[derive(Debug)]
+    struct Offline;
+
+    impl HttpTransport for Offline {
+        fn send(&self, _request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            Box::pin(async { Err(TransportError::new(TransportErrorKind::Connect, "offline")) })
+        }
+    }
+
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(Offline)
+        .build();
+
+    assert_eq!(
+        printer.check_connection().await,
+        Err(ConnectionError::Unreachable("offline".to_string()))
+    );
+}
+
+/// Drops nulls and rounds numbers to f32, so a serialized model can be compared with the body it was read from.
+/// Optional fields that were missing are serialized as null, and floats are read into f32.
+fn wire_format(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, wire_format(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(wire_format).collect()),
+        Value::Number(number) => Value::from(number.as_f64().unwrap() as f32 as f64),
+        other => other,
+    }
+}
+
+/// Parses `body`, checks the model serializes back to the same json, and that it reads back equal
+fn assert_round_trip<T>(body: &str)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug + Clone,
+{
+    let parsed = serde_json::from_str::<T>(body).unwrap();
+    let serialized = serde_json::to_value(parsed.clone()).unwrap();
+
+    assert_eq!(
+        wire_format(serialized.clone()),
+        wire_format(serde_json::from_str(body).unwrap())
+    );
+    assert_eq!(serde_json::from_value::<T>(serialized).unwrap(), parsed);
+}
+
+#[test]
+async fn raw_models_round_trip() {
+    use prusa_link_rs::{
+        capabilities::RawVersion, raw_info::RawInfo, raw_job::RawJob, raw_status::RawStatus,
+    };
+
+    for telemetry in [MK3_TELEMETRY, MK4_TELEMETRY, MINI_TELEMETRY] {
+        assert_round_trip::<RawPrinter>(&API_PRINTER_BODY.replace("{telemetry}", telemetry));
+    }
+
+    assert_round_trip::<RawStatus>(API_V1_STATUS_BODY);
+    assert_round_trip::<RawJob>(API_V1_JOB_BODY);
+    assert_round_trip::<RawInfo>(API_V1_INFO_BODY);
+    assert_round_trip::<RawVersion>(API_VERSION_BODY);
+}
+
+#[test]
+async fn farm_refreshes_every_printer() {
+    use prusa_link_rs::{farm::Farm, raw_job::JobId, PrinterBuilder};
+
+    let (mut printing_server, printing_mock, address, port, api_key) = mock_api_printer();
+    let version_mock = mock_api_version(&mut printing_server, &api_key);
+    let job_mock = mock_api_v1_job(&mut printing_server, &api_key);
+    let printing = PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+
+    let (mut idle_server, address, port, api_key) = mock_base();
+    let idle_mock = idle_server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(api_printer_body_in_state(false, "IDLE"))
+        .create();
+    let no_job_mock = idle_server
+        .mock("GET", "/api/v1/job")
+        .with_status(204)
+        .create();
+    let idle = PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .build();
+
+    let offline = PrinterBuilder::new("127.0.0.1", "1234567890")
+        .port(1)
+        .build();
+
+    let mut farm = Farm::new().parallelism(2);
+    farm.add("mk4", printing);
+    farm.add("mini", idle);
+    farm.add("offline", offline);
+
+    let results = farm.refresh_all().await;
+
+    assert_eq!(
+        results.keys().collect::<Vec<_>>(),
+        vec!["mini", "mk4", "offline"]
+    );
+    assert!(results["mini"].is_ok());
+    assert!(results["mk4"].is_ok());
+    assert!(results["offline"].is_err());
+    assert_eq!(farm.idle_printers(), vec!["mini"]);
+    assert_eq!(farm.jobs_running(), 1);
+
+    let snapshots = farm.snapshots();
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots["mini"].job, None);
+    assert_eq!(
+        snapshots["mk4"].job.as_ref().map(|job| job.get_id()),
+        Some(JobId(297))
+    );
+
+    printing_mock.assert();
+    version_mock.assert();
+    job_mock.assert();
+    idle_mock.assert();
+    no_job_mock.assert();
+}
+
+#[cfg(feature = "axum")]
+#[test]
+async fn web_router_serves_status_and_snapshot() {
+    use axum::{body::Body, http::Request};
+    use prusa_link_rs::{farm::Farm, web, PrinterBuilder};
+    use tower::ServiceExt;
+
+    /// Sends a GET request to the router and returns the status and the json body
+    async fn get(router: &axum::Router, path: &str) -> (u16, serde_json::Value) {
+        let res = router
+            .clone()
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = res.status().as_u16();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    let (mut server, printer_mock, address, port, api_key) = mock_api_printer();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let job_mock = mock_api_v1_job(&mut server, &api_key);
+
+    let printer = PrinterBuilder::new(&address, &api_key)
+        .port(port.into())
+        .auto_refresh(std::time::Duration::from_secs(60))
+        .build_handle();
+    let router = web::router(printer);
+
+    let (status, body) = get(&router, "/status").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["state"], "PRINTING");
+    assert_eq!(body["nozzle"]["actual"], 220.2);
+    assert_eq!(body["progress"], 91.0);
+
+    // The second request is answered from the cache
+    let (status, body) = get(&router, "/snapshot").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["job"]["id"], 297);
+
+    printer_mock.assert();
+    job_mock.assert();
+
+    let offline = PrinterBuilder::new("127.0.0.1", "1234567890")
+        .port(1)
+        .build_handle();
+    let (status, body) = get(&web::router(offline), "/status").await;
+    assert_eq!(status, 502);
+    assert!(body["error"].is_string());
+
+    let mut farm = Farm::new();
+    farm.add(
+        "mk4",
+        PrinterBuilder::new(&address, &api_key)
+            .port(port.into())
+            .build(),
+    );
+    farm.refresh_all().await;
+
+    let router = web::farm_router(std::sync::Arc::new(tokio::sync::RwLock::new(farm)));
+    let (status, body) = get(&router, "/status").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["mk4"]["state"], "PRINTING");
+}
+
+#[test]
+async fn farm_spreads_refreshes() {
+    use prusa_link_rs::{farm::Farm, PrinterBuilder};
+    use std::time::{Duration, Instant};
+
+    let mut farm = Farm::new()
+        .spread(Duration::from_millis(200))
+        .jitter(Duration::from_millis(10));
+    let mut servers = Vec::new();
+
+    for name in ["a", "b"] {
+        let (mut server, address, port, api_key) = mock_base();
+        server
+            .mock("GET", "/api/printer")
+            .with_status(200)
+            .with_body(api_printer_body_in_state(false, "IDLE"))
+            .create();
+        server.mock("GET", "/api/v1/job").with_status(204).create();
+
+        farm.add(
+            name,
+            PrinterBuilder::new(&address, &api_key)
+                .port(port.into())
+                .build(),
+        );
+        servers.push(server);
+    }
+
+    let started = Instant::now();
+    let errors = farm.refresh_errors().await;
+
+    // The second printer only starts halfway through the spread
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert!(errors.is_empty());
+    assert_eq!(farm.idle_printers(), vec!["a", "b"]);
+}
+
+#[test]
+async fn print_queue_runs_files_in_order() {
+    use prusa_link_rs::queue::PrintQueue;
+
+    let (mut server, address, port, api_key) = mock_base();
+
+    // A zero interval is raised to the shortest one instead of panicking while printing
+    let mut queue = PrintQueue::new("usb").poll_interval(std::time::Duration::ZERO);
+    let mut upload_mocks = Vec::new();
+
+    for name in ["prusa_link_rs_queue_1.gcode", "prusa_link_rs_queue_2.gcode"] {
+        let local_path = std::env::temp_dir().join(name);
+        std::fs::write(&local_path, "G28\n").unwrap();
+        queue.push(&local_path);
+
+        upload_mocks.push(
+            server
+                .mock("PUT", format!("/api/v1/files/usb/{}", name).as_str())
+                .match_body("G28\n")
+                .with_status(201)
+                .expect(1)
+                .create(),
+        );
+    }
+    queue.push_as(std::env::temp_dir().join("missing.gcode"), "missing.gcode");
+
+    // Mockito serves the first mock that still expects hits: no job before the first upload,
+    // then the job of the first file, which is still reported before the second upload
+    let no_job_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(204)
+        .expect(1)
+        .create();
+    let first_job_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(200)
+        .with_body(API_V1_JOB_BODY)
+        .expect(2)
+        .create();
+    let second_job_mock = server
+        .mock("GET", "/api/v1/job")
+        .with_status(200)
+        .with_body(API_V1_JOB_BODY.replace(r#""id": 297"#, r#""id": 298"#))
+        .expect_at_least(1)
+        .create();
+    let printer_mock = server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(api_printer_body_in_state(false, "FINISHED"))
+        .expect(2)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    queue.run(&mut printer).await;
+
+    assert!(queue.is_empty());
+
+    let outcomes = queue.outcomes();
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0].file.path, "prusa_link_rs_queue_1.gcode");
+    assert_eq!(outcomes[1].file.path, "prusa_link_rs_queue_2.gcode");
+    assert!(outcomes[..2].iter().all(|outcome| outcome.succeeded()));
+    assert_eq!(outcomes[0].job, Some(JobId(297)));
+    assert_eq!(outcomes[1].job, Some(JobId(298)));
+
+    // A file that can't be read is recorded as failed without stopping the queue
+    assert_eq!(outcomes[2].job, None);
+    assert!(outcomes[2].result.is_err());
+
+    for name in ["prusa_link_rs_queue_1.gcode", "prusa_link_rs_queue_2.gcode"] {
+        std::fs::remove_file(std::env::temp_dir().join(name)).unwrap();
+    }
+    for mock in upload_mocks {
+        mock.assert();
+    }
+    no_job_mock.assert();
+    first_job_mock.assert();
+    second_job_mock.assert();
+    printer_mock.assert();
+}
+
+#[test]
+async fn rate_limit_spaces_out_requests() {
+    use prusa_link_rs::rate_limit::RateLimit;
+    use std::time::{Duration, Instant};
+
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = mock_api_version(&mut server, &api_key).expect(4);
+
+    let printer = prusa_link_rs::PrinterBuilder::new(address, api_key)
+        .port(port.into())
+        .rate_limit(RateLimit::TokenBucket {
+            burst: 2,
+            refill: Duration::from_millis(100),
+        })
+        .build();
+
+    let started = Instant::now();
+
+    // Concurrent requests wait for their turn instead of all being sent at once
+    let results = futures::future::join_all((0..4).map(|_| printer.get_version())).await;
+
+    assert!(results.iter().all(Result::is_ok));
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    mock.assert();
+}
+
+#[test]
+async fn command_lock_serializes_commands() {
+    use prusa_link_rs::{
+        command_lock::CommandLock, error::PrinterError, raw_job::JobId, transport::*,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Takes a while to answer commands, and remembers how many were running at once
+    #[derive(Debug, Clone, Default)]
+    struct Slow {
+        running: Arc<AtomicUsize>,
+        most_running: Arc<AtomicUsize>,
+    }
+
+    impl HttpTransport for Slow {
+        fn send(&self, request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            let is_version = request.uri().path() == "/api/version";
+
+            Box::pin(async move {
+                if is_version {
+                    return Ok(http::Response::builder()
+                        .status(200)
+                        .body(API_VERSION_BODY.into())?);
+                }
+
+                let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+                self.most_running.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                self.running.fetch_sub(1, Ordering::SeqCst);
+
+                Ok(http::Response::builder()
+                    .status(204)
+                    .body(Vec::new().into())?)
+            })
+        }
+    }
+
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(Slow::default())
+        .command_lock(CommandLock::Reject)
+        .build();
+
+    let (stopped, started) = tokio::join!(
+        printer.stop_job(JobId(297)),
+        printer.start_print("usb", "benchy.bgcode")
+    );
+
+    assert!(stopped.is_ok());
+    assert!(matches!(
+        started.unwrap_err().downcast_ref::<PrinterError>(),
+        Some(PrinterError::Busy(_))
+    ));
+
+    let transport = Slow::default();
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .command_lock(CommandLock::Queue)
+        .build();
+
+    let (stopped, started) = tokio::join!(
+        printer.stop_job(JobId(297)),
+        printer.start_print("usb", "benchy.bgcode")
+    );
+
+    assert!(stopped.is_ok());
+    assert!(started.is_ok());
+    assert_eq!(transport.most_running.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+async fn stats_count_requests_per_endpoint() {
+    use prusa_link_rs::raw_job::JobId;
+
+    let (mut server, _mock, address, port, api_key) = mock_api_printer();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let _pause_mock = server
+        .mock("PUT", "/api/v1/job/297/pause")
+        .with_status(409)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    printer.refresh().await.unwrap();
+    printer.refresh().await.unwrap();
+    assert!(printer.pause_job(JobId(297)).await.is_err());
+
+    let stats = printer.stats();
+    let refreshes = stats.endpoint("GET /api/printer").unwrap();
+    assert_eq!(refreshes.requests, 2);
+    assert_eq!(refreshes.failures, 0);
+    assert!(refreshes.average_latency().is_some());
+    assert_eq!(refreshes.latency_histogram.iter().sum::<u64>(), 2);
+
+    let pauses = stats.endpoint("PUT /api/v1/job/{id}/pause").unwrap();
+    assert_eq!(pauses.error_responses, 1);
+
+    assert_eq!(
+        stats.endpoints().map(|(name, _)| name).collect::<Vec<_>>(),
+        vec![
+            "GET /api/printer",
+            "GET /api/version",
+            "PUT /api/v1/job/{id}/pause"
+        ]
+    );
+    assert_eq!(stats.total().requests, 4);
+
+    printer.reset_stats();
+    assert_eq!(printer.stats().total().requests, 0);
+}
+
+#[test]
+async fn stale_data_is_served_during_outage() {
+    use prusa_link_rs::transport::*;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    /// Answers `/api/printer` while online, and fails to connect otherwise
+    #[derive(Debug, Clone)]
+    struct Flaky {
+        online: Arc<AtomicBool>,
+    }
+
+    impl HttpTransport for Flaky {
+        fn send(&self, _request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            let online = self.online.load(Ordering::SeqCst);
+
+            Box::pin(async move {
+                if !online {
+                    return Err(TransportError::new(TransportErrorKind::Connect, "offline"));
+                }
+
+                Ok(http::Response::builder().status(200).body(
+                    API_PRINTER_BODY
+                        .replace("{telemetry}", MK3_TELEMETRY)
+                        .into(),
+                )?)
+            })
+        }
+    }
+
+    let transport = Flaky {
+        online: Arc::new(AtomicBool::new(true)),
+    };
+
+    let mut printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .auto_refresh(std::time::Duration::ZERO)
+        .serve_stale(std::time::Duration::from_secs(60))
+        .build();
+
+    assert!(!printer.health().is_healthy());
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+    assert!(printer.health().is_healthy());
+    assert!(!printer.is_stale());
+
+    transport.online.store(false, Ordering::SeqCst);
+
+    assert_eq!(printer.get_nozzle_temp().await.unwrap(), Celsius(220.2));
+    assert!(printer.is_stale());
+
+    let health = printer.health();
+    assert_eq!(health.consecutive_failures, 1);
+    assert_eq!(health.last_error, Some("offline".to_string()));
+    assert!(health.last_success.is_some());
+
+    transport.online.store(true, Ordering::SeqCst);
+
+    printer.get_nozzle_temp().await.unwrap();
+    assert!(!printer.is_stale());
+    assert_eq!(printer.health().consecutive_failures, 0);
+}
+
+#[test]
+async fn circuit_breaker_stops_requests_to_offline_printer() {
+    use prusa_link_rs::{error::PrinterError, transport::*};
+    use std::{
+        error::Error,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// Fails to connect after a moment, counting the requests it was given
+    #[derive(Debug, Clone, Default)]
+    struct Offline {
+        sent: Arc<AtomicUsize>,
+    }
+
+    impl HttpTransport for Offline {
+        fn send(&self, _request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            self.sent.fetch_add(1, Ordering::SeqCst);
+
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Err(TransportError::new(TransportErrorKind::Connect, "offline"))
+            })
+        }
+    }
+
+    let transport = Offline::default();
+
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .circuit_breaker(2, Duration::from_millis(100))
+        .build();
+
+    for _ in 0..2 {
+        assert!(printer.get_version().await.is_err());
+    }
+
+    let err = printer.get_version().await.unwrap_err();
+    let err = err.downcast_ref::<TransportError>().unwrap();
+
+    assert_eq!(err.kind(), TransportErrorKind::CircuitOpen);
+    assert!(matches!(
+        err.source().and_then(|source| source.downcast_ref()),
+        Some(PrinterError::CircuitOpen { failures: 2, .. })
+    ));
+    assert_eq!(transport.sent.load(Ordering::SeqCst), 2);
+    assert_eq!(printer.health().consecutive_failures, 2);
+
+    // After the cooldown a request goes through again
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    assert!(printer.get_version().await.is_err());
+    assert_eq!(transport.sent.load(Ordering::SeqCst), 3);
+
+    // Only one of several requests at the same time probes the printer
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    let (first, second) = futures::join!(printer.get_version(), printer.get_version());
+    let kinds = [first, second].map(|result| {
+        result
+            .unwrap_err()
+            .downcast_ref::<TransportError>()
+            .unwrap()
+            .kind()
+    });
+
+    assert_eq!(
+        kinds,
+        [TransportErrorKind::Connect, TransportErrorKind::CircuitOpen]
+    );
+    assert_eq!(transport.sent.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+async fn list_files_in_folder() {
+    let (mut server, address, port, api_key) = mock_base();
+    let version_mock = mock_api_version(&mut server, &api_key);
+
+    let mock = server
+        .mock("GET", "/api/v1/files/usb/projects")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "PROJECTS",
+                "display_name": "projects",
+                "ro": false,
+                "m_timestamp": 1706025000,
+                "children": [
+                    {
+                        "type": "PRINT_FILE",
+                        "name": "BENCHY~1.BGC",
+                        "display_name": "benchy.bgcode",
+                        "size": 4237152,
+                        "m_timestamp": 1706025466,
+                        "ro": false,
+                        "refs": {
+                            "download": "/usb/PROJECTS/BENCHY~1.BGC",
+                            "icon": "/thumb/s/usb/PROJECTS/BENCHY~1.BGC",
+                            "thumbnail": "/thumb/l/usb/PROJECTS/BENCHY~1.BGC"
+                        }
+                    },
+                    {
+                        "type": "FOLDER",
+                        "name": "OLD",
+                        "m_timestamp": 1706000000
+                    }
+                ]
+            }"#,
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let files = printer.list_files("usb", "projects").await.unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].display_name(), "benchy.bgcode");
+    assert_eq!(files[0].size, Some(4237152));
+    assert!(!files[0].is_folder());
+    assert_eq!(files[1].display_name(), "OLD");
+    assert!(files[1].is_folder());
+
+    version_mock.assert();
+    mock.assert();
+}
+
+#[test]
+async fn file_tree_lists_every_folder() {
+    let (mut server, address, port, api_key) = mock_base();
+    let version_mock = mock_api_version(&mut server, &api_key);
+
+    let root_mock = server
+        .mock("GET", "/api/v1/files/usb/")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "usb",
+                "children": [
+                    { "type": "FOLDER", "name": "PROJECTS", "display_name": "projects" },
+                    { "type": "PRINT_FILE", "name": "cube.gcode", "size": 81234 }
+                ]
+            }"#,
+        )
+        .create();
+
+    let folder_mock = server
+        .mock("GET", "/api/v1/files/usb/projects")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "PROJECTS",
+                "display_name": "projects",
+                "children": [
+                    { "type": "PRINT_FILE", "name": "BENCHY~1.BGC", "display_name": "benchy.bgcode", "size": 4237152 }
+                ]
+            }"#,
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let tree = printer.file_tree("usb", "").await.unwrap();
+
+    let files = tree
+        .files()
+        .map(|file| file.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(files, ["projects/benchy.bgcode", "cube.gcode"]);
+    assert_eq!(
+        tree.find("projects/benchy.bgcode").unwrap().entry.size,
+        Some(4237152)
+    );
+
+    version_mock.assert();
+    root_mock.assert();
+    folder_mock.assert();
+}
+
+#[test]
+async fn find_files_matches_glob_patterns() {
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+
+    let _root_mock = server
+        .mock("GET", "/api/v1/files/usb/")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "usb",
+                "children": [
+                    { "type": "FOLDER", "name": "projects" },
+                    { "type": "PRINT_FILE", "name": "cube.gcode", "size": 81234 },
+                    { "type": "PRINT_FILE", "name": "benchy.bgcode", "size": 4237152 }
+                ]
+            }"#,
+        )
+        .create();
+
+    let _folder_mock = server
+        .mock("GET", "/api/v1/files/usb/projects")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "projects",
+                "children": [
+                    { "type": "PRINT_FILE", "name": "vase.gcode", "size": 1200 }
+                ]
+            }"#,
+        )
+        .create()
+        .expect(2);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let gcode = printer.find_files("usb", "*.gcode").await.unwrap();
+    let projects = printer
+        .find_files("usb", "projects/**/*.gcode")
+        .await
+        .unwrap();
+
+    let paths = |files: &[prusa_link_rs::files::FileTree]| {
+        files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(paths(&gcode), ["projects/vase.gcode", "cube.gcode"]);
+    assert_eq!(paths(&projects), ["projects/vase.gcode"]);
+}
+
+#[test]
+async fn cleanup_storage_removes_oldest_files() {
+    use prusa_link_rs::cleanup::CleanupPolicy;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let _job_mock = mock_api_v1_job(&mut server, &api_key);
+
+    let _root_mock = server
+        .mock("GET", "/api/v1/files/usb/")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "usb",
+                "children": [
+                    { "type": "PRINT_FILE", "name": "NEW~1.GCO", "display_name": "new.gcode",
+                      "size": 500, "m_timestamp": 4000000000 },
+                    { "type": "PRINT_FILE", "name": "BENCHY~1.GCO",
+                      "display_name": "benchy_0.4n_0.2mm_PLA_MK4_1h2m.gcode",
+                      "size": 4237152, "m_timestamp": 1706025466 },
+                    { "type": "PRINT_FILE", "name": "OLD~1.GCO", "display_name": "old.gcode",
+                      "size": 1000, "m_timestamp": 1600000000 },
+                    { "type": "PRINT_FILE", "name": "LOCKED~1.GCO", "display_name": "locked.gcode",
+                      "size": 10, "m_timestamp": 1600000001, "ro": true },
+                    { "type": "PRINT_FILE", "name": "UNKNOW~1.GCO", "display_name": "unknown.gcode",
+                      "size": 20 }
+                ]
+            }"#,
+        )
+        .create();
+
+    let old_mock = server
+        .mock("DELETE", "/api/v1/files/usb/old.gcode")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .create();
+    let new_mock = server
+        .mock("DELETE", "/api/v1/files/usb/new.gcode")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let policy = CleanupPolicy::new()
+        .max_age(std::time::Duration::from_secs(24 * 60 * 60))
+        .max_total_size(4_000_000);
+    let report = printer.cleanup_storage("usb", policy).await.unwrap();
+
+    let paths = |files: &[prusa_link_rs::files::FileTree]| {
+        files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(paths(&report.removed), ["old.gcode", "new.gcode"]);
+    // Without a modification time the age is unknown, the file is kept and sorted last
+    assert_eq!(
+        paths(&report.kept),
+        [
+            "locked.gcode",
+            "benchy_0.4n_0.2mm_PLA_MK4_1h2m.gcode",
+            "unknown.gcode"
+        ]
+    );
+    assert!(report.failed.is_empty());
+    assert_eq!(report.freed, 1500);
+    assert_eq!(report.remaining_size, 4237182);
+
+    old_mock.assert();
+    new_mock.assert();
+}
+
+#[test]
+async fn get_file_info_with_metadata() {
+    let (mut server, address, port, api_key) = mock_base();
+    let version_mock = mock_api_version(&mut server, &api_key);
+
+    let mock = server
+        .mock("GET", "/api/v1/files/usb/projects/benchy.bgcode")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "PRINT_FILE",
+                "name": "BENCHY~1.BGC",
+                "display_name": "benchy.bgcode",
+                "size": 4237152,
+                "m_timestamp": 1706025466,
+                "ro": false,
+                "refs": {
+                    "download": "/usb/PROJECTS/BENCHY~1.BGC",
+                    "icon": "/thumb/s/usb/PROJECTS/BENCHY~1.BGC",
+                    "thumbnail": "/thumb/l/usb/PROJECTS/BENCHY~1.BGC"
+                },
+                "meta": {
+                    "estimated printing time (normal mode)": "1h 2m 3s",
+                    "filament_type": "PLA",
+                    "layer_height": 0.2,
+                    "filament used [g]": 12.5
+                }
+            }"#,
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let file = printer
+        .get_file_info("usb", "projects/benchy.bgcode")
+        .await
+        .unwrap();
+
+    assert_eq!(file.display_name(), "benchy.bgcode");
+    assert_eq!(file.size, Some(4237152));
+    assert_eq!(file.m_timestamp, Some(1706025466));
+    assert_eq!(
+        file.refs.as_ref().unwrap().thumbnail.as_deref(),
+        Some("/thumb/l/usb/PROJECTS/BENCHY~1.BGC")
+    );
+    assert_eq!(
+        file.estimated_print_time(),
+        Some(std::time::Duration::from_secs(3723))
+    );
+
+    let metadata = file.metadata();
+    assert_eq!(metadata.filament_type.as_deref(), Some("PLA"));
+    assert_eq!(metadata.layer_height, Some(0.2));
+    assert_eq!(metadata.filament_used_g, Some(12.5));
+
+    version_mock.assert();
+    mock.assert();
+}
+
+#[test]
+async fn file_paths_are_encoded_and_validated() {
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+
+    let mock = server
+        .mock(
+            "DELETE",
+            "/api/v1/files/usb/my%20prints/benchy%20%C3%BC.gcode",
+        )
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(204)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    printer
+        .delete_file("usb", r"\my prints\benchy ü.gcode")
+        .await
+        .unwrap();
+
+    let err = printer
+        .delete_file("usb", "my prints/benchy?.gcode")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<prusa_link_rs::error::PrinterError>(),
+        Some(prusa_link_rs::error::PrinterError::InvalidPath(_))
+    ));
+
+    mock.assert();
+}
+
+/// Adds a mock of the file info of `benchy.gcode`, a 10 byte file downloaded from `/usb/BENCHY~1.GCO`
+fn mock_download_info(server: &mut mockito::ServerGuard, api_key: &str) -> mockito::Mock {
+    server
+        .mock("GET", "/api/v1/files/usb/benchy.gcode")
+        .match_header("X-Api-Key", api_key)
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "PRINT_FILE",
+                "name": "BENCHY~1.GCO",
+                "display_name": "benchy.gcode",
+                "size": 10,
+                "refs": { "download": "/usb/BENCHY~1.GCO" }
+            }"#,
+        )
+        .create()
+}
+
+#[test]
+async fn download_file_with_range() {
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let _info_mock = mock_download_info(&mut server, &api_key);
+
+    // This printer ignores the range and sends the whole file
+    let mock = server
+        .mock("GET", "/usb/BENCHY~1.GCO")
+        .match_header("X-Api-Key", api_key.as_str())
+        .match_header("Range", "bytes=0-9")
+        .with_status(200)
+        .with_body("G28\nG1 X10")
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = reports.clone();
+    let data = printer
+        .download_file_with_progress("usb", "benchy.gcode", move |received, total| {
+            recorded.lock().unwrap().push((received, total));
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(data, b"G28\nG1 X10");
+    assert_eq!(*reports.lock().unwrap(), [(10, 10)]);
+    assert!(printer
+        .stats()
+        .endpoint("GET /usb/{path}")
+        .is_some_and(|stats| stats.requests == 1));
+
+    mock.assert();
+}
+
+#[test]
+async fn download_local_file_resumes() {
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    let _info_mock = mock_download_info(&mut server, &api_key);
+
+    let mock = server
+        .mock("GET", "/usb/BENCHY~1.GCO")
+        .match_header("X-Api-Key", api_key.as_str())
+        .match_header("Range", "bytes=4-9")
+        .with_status(206)
+        .with_header("Content-Range", "bytes 4-9/10")
+        .with_body("G1 X10")
+        .create();
+
+    let local_path = std::env::temp_dir().join("prusa_link_rs_download_local_file.gcode");
+    std::fs::write(&local_path, "G28\n").unwrap();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let size = printer
+        .download_local_file("usb", "benchy.gcode", &local_path)
+        .await
+        .unwrap();
+
+    assert_eq!(size, 10);
+    assert_eq!(std::fs::read(&local_path).unwrap(), b"G28\nG1 X10");
+
+    // The file is complete, so nothing is downloaded again
+    printer
+        .download_local_file("usb", "benchy.gcode", &local_path)
+        .await
+        .unwrap();
+
+    std::fs::remove_file(&local_path).unwrap();
+    mock.assert();
+}
+
+#[test]
+async fn verify_uploads_detects_truncated_files() {
+    use prusa_link_rs::error::PrinterError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+
+    let _upload_mock = server
+        .mock(
+            "PUT",
+            mockito::Matcher::Regex("^/api/v1/files/usb/".to_string()),
+        )
+        .with_status(201)
+        .create();
+
+    let _complete_mock = server
+        .mock("GET", "/api/v1/files/usb/complete.gcode")
+        .with_status(200)
+        .with_body(r#"{ "type": "PRINT_FILE", "name": "complete.gcode", "size": 10 }"#)
+        .create();
+    let _truncated_mock = server
+        .mock("GET", "/api/v1/files/usb/truncated.gcode")
+        .with_status(200)
+        .with_body(r#"{ "type": "PRINT_FILE", "name": "truncated.gcode", "size": 4 }"#)
+        .create();
+    let _missing_mock = server
+        .mock("GET", "/api/v1/files/usb/missing.gcode")
+        .with_status(404)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).verify_uploads().build();
+
+    let data = b"G28\nG1 X10".to_vec();
+
+    printer
+        .upload_file("usb", "complete.gcode", data.clone())
+        .await
+        .unwrap();
+
+    let truncated = printer
+        .upload_file("usb", "truncated.gcode", data.clone())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        truncated.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::UploadVerificationFailed {
+            path: "truncated.gcode".to_string(),
+            expected: 10,
+            found: Some(4),
+        })
+    );
+
+    let missing = printer
+        .upload_file("usb", "missing.gcode", data)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        missing.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::UploadVerificationFailed {
+            path: "missing.gcode".to_string(),
+            expected: 10,
+            found: None,
+        })
+    );
+}
+
+#[test]
+async fn failed_uploads_are_cleaned_up_and_retried() {
+    use prusa_link_rs::{retry::RetryPolicy, transport::*};
+    use std::sync::{Arc, Mutex};
+
+    /// Drops the connection during the first upload of `benchy.gcode`, and refuses `exists.gcode`
+    #[derive(Debug, Clone, Default)]
+    struct Flaky {
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for Flaky {
+        fn send(&self, request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            let line = format!("{} {}", request.method(), request.uri().path());
+
+            Box::pin(async move {
+                let mut requests = self.requests.lock().unwrap();
+                let first_upload = !requests.contains(&line);
+                requests.push(line.clone());
+
+                let status = match line.as_str() {
+                    "GET /api/version" => {
+                        return Ok(http::Response::builder()
+                            .status(200)
+                            .body(API_VERSION_BODY.into())?)
+                    }
+                    "PUT /api/v1/files/usb/benchy.gcode" if first_upload => {
+                        return Err(TransportError::new(
+                            TransportErrorKind::Other,
+                            "connection reset",
+                        ))
+                    }
+                    "GET /api/v1/storage" => 404,
+                    "GET /api/v1/files/usb/benchy.gcode" => 404,
+                    "PUT /api/v1/files/usb/exists.gcode" => 409,
+                    "PUT /api/v1/files/usb/benchy.gcode" => 201,
+                    _ => 204,
+                };
+
+                Ok(http::Response::builder()
+                    .status(status)
+                    .body(Vec::new().into())?)
+            })
+        }
+    }
+
+    let transport = Flaky::default();
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .retry_uploads(RetryPolicy::new(3).backoff(
+            std::time::Duration::from_millis(10),
+            std::time::Duration::from_millis(10),
+        ))
+        .build();
+
+    printer
+        .upload_file("usb", "benchy.gcode", b"G28".to_vec())
+        .await
+        .unwrap();
+    assert!(printer
+        .upload_file("usb", "exists.gcode", b"G28".to_vec())
+        .await
+        .is_err());
+
+    let requests = transport.requests.lock().unwrap();
+    let requests = requests
+        .iter()
+        .filter(|line| line.starts_with("PUT") || line.starts_with("DELETE"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        requests,
+        [
+            "PUT /api/v1/files/usb/benchy.gcode",
+            "DELETE /api/v1/files/usb/benchy.gcode",
+            "PUT /api/v1/files/usb/benchy.gcode",
+            "PUT /api/v1/files/usb/exists.gcode",
+        ]
+    );
+}
+
+#[test]
+async fn failed_uploads_keep_existing_files() {
+    use prusa_link_rs::{retry::RetryPolicy, transport::*};
+    use std::sync::{Arc, Mutex};
+
+    /// Has `benchy.gcode` already and drops the connection during every upload
+    #[derive(Debug, Clone, Default)]
+    struct Existing {
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for Existing {
+        fn send(&self, request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            let line = format!("{} {}", request.method(), request.uri().path());
+
+            Box::pin(async move {
+                self.requests.lock().unwrap().push(line.clone());
+
+                let body = match line.as_str() {
+                    "GET /api/version" => API_VERSION_BODY,
+                    "GET /api/v1/files/usb/benchy.gcode" => {
+                        r#"{"type": "PRINT_FILE", "name": "benchy.gcode", "size": 1000}"#
+                    }
+                    "PUT /api/v1/files/usb/benchy.gcode" => {
+                        return Err(TransportError::new(
+                            TransportErrorKind::Other,
+                            "connection reset",
+                        ))
+                    }
+                    _ => {
+                        return Ok(http::Response::builder()
+                            .status(404)
+                            .body(Vec::new().into())?)
+                    }
+                };
+
+                Ok(http::Response::builder().status(200).body(body.into())?)
+            })
+        }
+    }
+
+    let transport = Existing::default();
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .retry_uploads(RetryPolicy::new(2).backoff(
+            std::time::Duration::from_millis(10),
+            std::time::Duration::from_millis(10),
+        ))
+        .build();
+
+    assert!(printer
+        .upload_file("usb", "benchy.gcode", b"G28".to_vec())
+        .await
+        .is_err());
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|line| line.starts_with("PUT"))
+            .count(),
+        2
+    );
+    assert!(!requests.iter().any(|line| line.starts_with("DELETE")));
+}
+
+#[test]
+async fn failed_upload_checks_delete_nothing() {
+    use prusa_link_rs::{retry::RetryPolicy, transport::*};
+    use std::sync::{Arc, Mutex};
+
+    /// Drops the connection when the free space of the storages is read
+    #[derive(Debug, Clone, Default)]
+    struct Unreachable {
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for Unreachable {
+        fn send(&self, request: http::Request<TransportBody>) -> TransportFuture<'_> {
+            let line = format!("{} {}", request.method(), request.uri().path());
+
+            Box::pin(async move {
+                self.requests.lock().unwrap().push(line.clone());
+
+                match line.as_str() {
+                    "GET /api/version" => Ok(http::Response::builder()
+                        .status(200)
+                        .body(API_VERSION_BODY.into())?),
+                    _ => Err(TransportError::new(
+                        TransportErrorKind::Other,
+                        "connection reset",
+                    )),
+                }
+            })
+        }
+    }
+
+    let transport = Unreachable::default();
+    let printer = prusa_link_rs::PrinterBuilder::new("printer.local", "1234567890")
+        .transport(transport.clone())
+        .retry_uploads(RetryPolicy::new(2).backoff(
+            std::time::Duration::from_millis(10),
+            std::time::Duration::from_millis(10),
+        ))
+        .build();
+
+    assert!(printer
+        .upload_file("usb", "benchy.gcode", b"G28".to_vec())
+        .await
+        .is_err());
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(
+        requests
+            .iter()
+            .filter(|line| line.as_str() == "GET /api/v1/storage")
+            .count(),
+        2
+    );
+    assert!(!requests
+        .iter()
+        .any(|line| line.starts_with("PUT") || line.starts_with("DELETE")));
+}
+
+#[test]
+async fn throttled_uploads_are_paced() {
+    let (mut server, address, port, api_key) = mock_base();
+    let data = vec![b'G'; 200_000];
+
+    let mock = server
+        .mock("PUT", "/api/v1/files/usb/benchy.gcode")
+        .match_header("Content-Length", "200000")
+        .match_body(data.clone())
+        .with_status(201)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder
+        .port(port.into())
+        .throttle_uploads(1_000_000)
+        .build();
+
+    let started = std::time::Instant::now();
+    printer
+        .upload_file("usb", "benchy.gcode", data)
+        .await
+        .unwrap();
+
+    // 200 kB at 1 MB/s
+    assert!(started.elapsed() >= std::time::Duration::from_millis(190));
+
+    mock.assert();
+}
+
+#[test]
+async fn firmware_upload_and_status() {
+    use prusa_link_rs::error::PrinterError;
+
+    let (mut server, address, port, api_key) = mock_base();
+    let version_mock = server
+        .mock("GET", "/api/version")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_VERSION_BODY.replace(
+            r#""api": "2.0.0","#,
+            r#""api": "2.0.0", "firmware": "6.0.2+14152","#,
+        ))
+        .expect_at_least(1)
+        .create();
+    let upload_mock = server
+        .mock("PUT", "/api/v1/files/usb/MK4_firmware_6.1.3.bbf")
+        .match_header("Content-Type", "application/octet-stream")
+        .match_body("firmware")
+        .with_status(201)
+        .create();
+    let root_mock = server
+        .mock("GET", "/api/v1/files/usb/")
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "FOLDER",
+                "name": "usb",
+                "children": [
+                    { "type": "FILE", "name": "MK4_FI~1.BBF", "display_name": "MK4_firmware_6.1.3.bbf", "size": 8 },
+                    { "type": "PRINT_FILE", "name": "cube.gcode", "size": 81234 }
+                ]
+            }"#,
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let err = printer
+        .upload_firmware("cube.gcode", b"G28".to_vec())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<PrinterError>(),
+        Some(PrinterError::InvalidFile(_))
+    ));
+
+    printer
+        .upload_firmware("MK4_firmware_6.1.3.bbf", b"firmware".to_vec())
+        .await
+        .unwrap();
+
+    let status = printer.firmware_update_status().await.unwrap();
+    assert_eq!(
+        status.current,
+        Some(prusa_link_rs::model::Version::new(6, 0, 2))
+    );
+    assert_eq!(status.pending.len(), 1);
+    assert_eq!(
+        status.pending_version(),
+        Some(prusa_link_rs::model::Version::new(6, 1, 3))
+    );
+    assert!(status.update_pending());
+
+    version_mock.assert();
+    upload_mock.assert();
+    root_mock.assert();
+}
+
+#[test]
+async fn get_logs_lists_and_reads_files() {
+    let (mut server, address, port, api_key) = mock_base();
+    let list_mock = server
+        .mock("GET", "/api/logs")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "files": [
+                    { "name": "prusalink.log.1", "size": 52311, "date": 1700000000 },
+                    { "name": "prusalink.log", "size": 1024, "date": 1700086400 }
+                ]
+            }"#,
+        )
+        .create();
+    let log_mock = server
+        .mock("GET", "/api/logs/prusalink.log")
+        .with_status(200)
+        .with_body("2023-11-15 22:13:20 ERROR: Thermal runaway\n")
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let printer = printer_builder.port(port.into()).build();
+
+    let logs = printer.get_logs().await.unwrap();
+    assert_eq!(
+        logs.iter().map(|log| log.name.as_str()).collect::<Vec<_>>(),
+        ["prusalink.log", "prusalink.log.1"]
+    );
+
+    let text = printer.get_log(&logs[0].name).await.unwrap();
+    assert!(text.contains("Thermal runaway"));
+
+    list_mock.assert();
+    log_mock.assert();
+}
+
+#[test]
+async fn sla_printer_info() {
+    let (mut server, address, port, api_key) = mock_base();
+    let mock = server
+        .mock("GET", "/api/printer")
+        .with_status(200)
+        .with_body(
+            r#"{
+                "state": {
+                    "text": "Printing",
+                    "flags": { "operational": true, "printing": true, "paused": false, "error": false, "ready": false }
+                },
+                "telemetry": {
+                    "temp-uv-led": 38.2,
+                    "temp-amb": 24.9,
+                    "temp-cpu": 51.0,
+                    "fan-uv-led": 2040,
+                    "fan-blower": 1200,
+                    "fan-rear": 1080,
+                    "cover-closed": true,
+                    "resin-remaining": 142.5
+                }
+            }"#,
+        )
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let raw_printer = printer.get_printer_info().await.unwrap();
+
+    assert!(raw_printer.is_sla());
+    assert_eq!(raw_printer.link_state(), LinkState::Printing);
+    assert_eq!(raw_printer.tool_count(), 0);
+    assert_eq!(raw_printer.telemetry().uv_led_temp, Some(38.2));
+    assert_eq!(raw_printer.telemetry().cover_closed, Some(true));
+    assert_eq!(raw_printer.telemetry().resin_remaining, Some(142.5));
+
+    mock.assert();
+}
+
+#[test]
+async fn chamber_temperature() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) =
+        mock_api_printer_with_telemetry(CORE_ONE_TELEMETRY);
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(
+        printer.get_chamber_temp().await.unwrap(),
+        Some(Celsius(44.5))
+    );
+
+    mock.assert();
+}
+
+#[test]
+async fn no_chamber_temperature_without_sensor() {
+    #[allow(unused)]
+    let (server, mock, address, port, api_key) = mock_api_printer();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    assert_eq!(printer.get_chamber_temp().await.unwrap(), None);
+
+    mock.assert();
+}
+
+#[test]
+async fn start_print_checked_refuses_a_mismatched_file() {
+    use prusa_link_rs::{
+        error::PrinterError,
+        job_check::{JobExpectations, JobMismatch},
+        model::PrinterModel,
+    };
+
+    let (mut server, address, port, api_key) = mock_base();
+    let _version_mock = mock_api_version(&mut server, &api_key);
+    // Requested for every check and once for the identity
+    let info_mock = server
+        .mock("GET", "/api/v1/info")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_V1_INFO_BODY)
+        .expect(3)
+        .create();
+
+    let printer_mock = server
+        .mock("GET", "/api/printer")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(API_PRINTER_BODY.replace("{telemetry}", r#"{ "material": "PLA" }"#))
+        .expect(2)
+        .create();
+
+    let file_mock = server
+        .mock("GET", "/api/v1/files/usb/benchy.bgcode")
+        .match_header("X-Api-Key", api_key.as_str())
+        .with_status(200)
+        .with_body(
+            r#"{
+                "type": "PRINT_FILE",
+                "name": "BENCHY~1.BGC",
+                "display_name": "benchy.bgcode",
+                "meta": {
+                    "filament_type": "PETG",
+                    "nozzle_diameter": 0.6,
+                    "printer_model": "MK3S"
+                }
+            }"#,
+        )
+        .expect(2)
+        .create();
+
+    let start_mock = server
+        .mock("POST", "/api/v1/files/usb/benchy.bgcode")
+        .expect(0)
+        .create();
+
+    let printer_builder = prusa_link_rs::PrinterBuilder::new(address, api_key);
+    let mut printer = printer_builder.port(port.into()).build();
+
+    let err = printer
+        .start_print_checked("usb", "benchy.bgcode", JobExpectations::new())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.downcast_ref::<PrinterError>(),
+        Some(&PrinterError::IncompatibleJob {
+            path: "benchy.bgcode".to_string(),
+            mismatches: vec![
+                JobMismatch::Material {
+                    file: Material::Petg,
+                    loaded: Some(Material::Pla),
+                },
+                JobMismatch::NozzleDiameter {
+                    file: 0.6,
+                    printer: 0.4,
+                },
+                JobMismatch::Model {
+                    file: PrinterModel::Mk3RaspberryPi,
+                    printer: PrinterModel::Mk4,
+                },
+            ],
+        })
+    );
+
+    // The printer information isn't cached, the nozzle might have been swapped in between
+    assert!(printer
+        .start_print_checked("usb", "benchy.bgcode", JobExpectations::new())
+        .await
+        .is_err());
+
+    info_mock.assert();
+    printer_mock.assert();
+    file_mock.assert();
+    start_mock.assert();
 }
@@ -0,0 +1,106 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    capabilities::RawVersion,
+    model::{PrinterIdentity, PrinterModel, Version},
+    raw_info::RawInfo,
+};
+
+fn version(json: &str) -> RawVersion {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn raspberry_pi_from_original() {
+    let version = version(
+        r#"{"api": "2.0.0", "server": "0.7.0", "original": "PrusaLink I3MK3S", "text": "PrusaLink 0.7.0", "firmware": "3.11.0-4955"}"#,
+    );
+
+    let identity = PrinterIdentity::detect(Some(&version), None);
+
+    assert_eq!(identity.model, PrinterModel::Mk3RaspberryPi);
+    assert_eq!(identity.firmware, Some(Version::new(3, 11, 0)));
+    assert_eq!(identity.prusa_link, Some(Version::new(0, 7, 0)));
+}
+
+#[test]
+fn buddy_printers_from_hostname() {
+    for (hostname, model) in [
+        ("prusa-mk4", PrinterModel::Mk4),
+        ("prusa-mk3.9", PrinterModel::Mk39),
+        ("prusa-mk3-5", PrinterModel::Mk35),
+        ("prusa-mini", PrinterModel::Mini),
+        ("prusa-xl", PrinterModel::Xl),
+        ("prusa-coreone", PrinterModel::CoreOne),
+    ] {
+        let version = version(&format!(
+            r#"{{"api": "2.0.0", "server": "2.1.2", "text": "PrusaLink", "hostname": "{}"}}"#,
+            hostname
+        ));
+
+        assert_eq!(PrinterModel::detect(Some(&version), None), model);
+    }
+}
+
+#[test]
+fn falls_back_to_the_name_in_the_info() {
+    let version = version(r#"{"api": "2.0.0", "text": "PrusaLink", "hostname": "shelf-2"}"#);
+    let info: RawInfo = serde_json::from_str(r#"{"name": "Core One #3"}"#).unwrap();
+
+    assert_eq!(
+        PrinterModel::detect(Some(&version), Some(&info)),
+        PrinterModel::CoreOne
+    );
+    assert_eq!(
+        PrinterModel::detect(Some(&version), None),
+        PrinterModel::Unknown
+    );
+}
+
+#[test]
+fn model_features() {
+    assert!(PrinterModel::Xl.is_multi_tool());
+    assert!(!PrinterModel::Mk4.is_multi_tool());
+    assert!(PrinterModel::Mini.supports_bgcode());
+    assert!(!PrinterModel::Mk3RaspberryPi.supports_bgcode());
+    assert_eq!(PrinterModel::CoreOne.to_string(), "CORE One");
+}
+
+#[test]
+fn sla_printers_are_detected() {
+    use prusa_link_rs::{capabilities::Capabilities, model::PrinterKind};
+
+    let version = version(
+        r#"{"api": "2.0.0", "server": "1.1.0", "text": "PrusaLink", "hostname": "prusa-sl1s"}"#,
+    );
+
+    assert_eq!(
+        PrinterModel::detect(Some(&version), None),
+        PrinterModel::Sl1
+    );
+    assert_eq!(PrinterModel::Sl1.kind(), PrinterKind::Sla);
+    assert_eq!(PrinterModel::Mk4.kind(), PrinterKind::Fdm);
+    assert_eq!(Capabilities::from_version(&version).kind, PrinterKind::Sla);
+}
+
+#[test]
+fn sla_job_exposure_times() {
+    use prusa_link_rs::raw_job::RawJob;
+    use std::time::Duration;
+
+    let job = serde_json::from_str::<RawJob>(
+        r#"{
+            "id": 12,
+            "state": "PRINTING",
+            "progress": 40.0,
+            "time_printing": 1800,
+            "exposure_time": 2500,
+            "exposure_time_first": 35000,
+            "layer_height": 0.05
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(job.get_exposure_time(), Some(Duration::from_millis(2500)));
+    assert_eq!(job.get_exposure_time_first(), Some(Duration::from_secs(35)));
+    assert_eq!(job.get_layer_height(), Some(0.05));
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "mqtt")]
+
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    events::{Heater, PrinterEvent},
+    farm::PrinterSnapshot,
+    home_assistant::DEFAULT_DISCOVERY_PREFIX,
+    mqtt::*,
+    raw_printer::{LinkState, RawPrinter},
+    units::Celsius,
+};
+use serde_json::{json, Value};
+
+fn payload(message: &MqttMessage) -> Value {
+    serde_json::from_str(&message.payload).unwrap()
+}
+
+#[test]
+fn topics_are_under_prefix_and_name() {
+    let bridge = MqttBridge::new("MK4 #2/left").topic_prefix("farm");
+
+    assert_eq!(bridge.base_topic(), "farm/MK4__2_left");
+    assert_eq!(bridge.state_topic(), "farm/MK4__2_left/state");
+    assert_eq!(bridge.events_topic(), "farm/MK4__2_left/events");
+}
+
+#[test]
+fn status_messages_are_retained() {
+    let bridge = MqttBridge::new("mk4");
+    let snapshot = PrinterSnapshot {
+        printer: RawPrinter::builder()
+            .link_state(LinkState::Printing)
+            .nozzle_temp(215.0, 215.0)
+            .bed_temp(60.0, 60.0)
+            .build(),
+        job: Some(
+            serde_json::from_str(
+                r#"{"id": 297, "state": "PRINTING", "progress": 42.0, "time_remaining": 600, "time_printing": 300}"#,
+            )
+            .unwrap(),
+        ),
+    };
+
+    let messages = bridge.status_messages(&snapshot);
+
+    assert!(messages.iter().all(|message| message.retain));
+    assert_eq!(messages[0].topic, "prusalink/mk4/availability");
+    assert_eq!(messages[0].payload, "online");
+    assert_eq!(messages[1].topic, "prusalink/mk4/state");
+    assert_eq!(messages[1].payload, "PRINTING");
+    assert_eq!(messages[2].topic, "prusalink/mk4/telemetry");
+    assert_eq!(
+        payload(&messages[2]),
+        json!({
+            "nozzle_temp": 215.0,
+            "target_nozzle_temp": 215.0,
+            "bed_temp": 60.0,
+            "target_bed_temp": 60.0,
+            "progress": 42.0,
+            "time_remaining": 600,
+            "time_printing": 300,
+        })
+    );
+}
+
+#[test]
+fn events_are_not_retained() {
+    let bridge = MqttBridge::new("mk4");
+
+    let message = bridge.event_message(&PrinterEvent::StateChanged {
+        from: LinkState::Idle,
+        to: LinkState::Printing,
+    });
+    assert!(!message.retain);
+    assert_eq!(message.topic, "prusalink/mk4/events");
+    assert_eq!(
+        payload(&message),
+        json!({ "event": "state_changed", "from": "IDLE", "to": "PRINTING" })
+    );
+
+    let message = bridge.event_message(&PrinterEvent::TemperatureReached {
+        heater: Heater::Bed,
+        temperature: Celsius(60.0),
+    });
+    assert_eq!(
+        payload(&message),
+        json!({ "event": "temperature_reached", "heater": "bed", "temperature": 60.0 })
+    );
+}
+
+#[test]
+fn discovery_only_with_home_assistant() {
+    assert!(MqttBridge::new("mk4").discovery_messages(None).is_empty());
+
+    let messages = MqttBridge::new("mk4")
+        .home_assistant(DEFAULT_DISCOVERY_PREFIX)
+        .discovery_messages(None);
+
+    let nozzle = messages
+        .iter()
+        .find(|message| message.topic == "homeassistant/sensor/mk4_nozzle_temp/config")
+        .unwrap();
+    let config = payload(nozzle);
+
+    assert!(nozzle.retain);
+    assert_eq!(config["state_topic"], "prusalink/mk4/telemetry");
+    assert_eq!(config["value_template"], "{{ value_json.nozzle_temp }}");
+    assert_eq!(config["unique_id"], "prusalink_mk4_nozzle_temp");
+    assert_eq!(config["availability_topic"], "prusalink/mk4/availability");
+}
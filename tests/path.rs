@@ -0,0 +1,47 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{error::PrinterError, path::PrinterPath};
+
+#[test]
+fn separators_are_normalized() {
+    let path = PrinterPath::new(r"/projects\\old//cube.gcode/").unwrap();
+
+    assert_eq!(path.as_str(), "projects/old/cube.gcode");
+    assert_eq!(path.file_name(), Some("cube.gcode"));
+    assert_eq!(path, "projects/old/cube.gcode".parse().unwrap());
+}
+
+#[test]
+fn empty_path_is_the_root() {
+    let path = PrinterPath::new("/").unwrap();
+
+    assert!(path.is_root());
+    assert_eq!(path.file_name(), None);
+    assert_eq!(path.url_encoded(), "");
+}
+
+#[test]
+fn segments_are_percent_encoded() {
+    let path = PrinterPath::new("my prints/Benchy #2 (ß).bgcode").unwrap();
+
+    assert_eq!(
+        path.url_encoded(),
+        "my%20prints/Benchy%20%232%20%28%C3%9F%29.bgcode"
+    );
+}
+
+#[test]
+fn forbidden_characters_are_rejected() {
+    for path in ["a:b.gcode", "what?.gcode", "a|b", "tab\there", "\"quoted\""] {
+        assert!(
+            matches!(PrinterPath::new(path), Err(PrinterError::InvalidPath(_))),
+            "{} was accepted",
+            path
+        );
+    }
+}
+
+#[test]
+fn relative_segments_are_rejected() {
+    assert!(PrinterPath::new("../secret.gcode").is_err());
+    assert!(PrinterPath::new("projects/./cube.gcode").is_err());
+}
@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{
+    raw_printer::{LinkState, RawPrinter},
+    ready::{NotReadyReason, ReadyCheck},
+    units::Celsius,
+};
+
+#[test]
+fn idle_printer_is_ready() {
+    let printer = RawPrinter::builder().build();
+
+    assert_eq!(ReadyCheck::new().check(&printer), Ok(()));
+    assert_eq!(
+        ReadyCheck::new()
+            .max_nozzle_temp(50.0)
+            .max_bed_temp(40.0)
+            .check(&printer),
+        Ok(())
+    );
+}
+
+#[test]
+fn busy_and_failed_printers_are_not_ready() {
+    let printing = RawPrinter::builder()
+        .link_state(LinkState::Printing)
+        .build();
+    let paused = RawPrinter::builder().link_state(LinkState::Paused).build();
+    let failed = RawPrinter::builder().link_state(LinkState::Error).build();
+
+    assert_eq!(
+        ReadyCheck::new().check(&printing),
+        Err(NotReadyReason::Busy(LinkState::Printing))
+    );
+    assert_eq!(
+        ReadyCheck::new().check(&paused),
+        Err(NotReadyReason::Busy(LinkState::Paused))
+    );
+    assert_eq!(
+        ReadyCheck::new().check(&failed),
+        Err(NotReadyReason::Error("ERROR".to_string()))
+    );
+}
+
+#[test]
+fn hot_printer_is_not_ready_with_limits() {
+    let printer = RawPrinter::builder()
+        .nozzle_temp(170.0, 0.0)
+        .bed_temp(55.0, 0.0)
+        .build();
+
+    assert_eq!(ReadyCheck::new().check(&printer), Ok(()));
+    assert_eq!(
+        ReadyCheck::new().max_nozzle_temp(50.0).check(&printer),
+        Err(NotReadyReason::NozzleTooHot {
+            actual: Celsius(170.0),
+            limit: Celsius(50.0),
+        })
+    );
+
+    let reason = ReadyCheck::new()
+        .max_bed_temp(40.0)
+        .check(&printer)
+        .unwrap_err();
+    assert_eq!(
+        reason.to_string(),
+        "the bed is at 55.0°C, above the limit of 40.0°C"
+    );
+}
@@ -0,0 +1,51 @@
+use pretty_assertions::assert_eq;
+use prusa_link_rs::{raw_storage::StorageInfo, storage::Storage};
+
+#[test]
+fn names_map_to_storages() {
+    assert_eq!(Storage::from("local"), Storage::Local);
+    assert_eq!(Storage::from("/USB/"), Storage::Usb);
+    assert_eq!(Storage::from("sdcard"), Storage::SdCard);
+    assert_eq!(
+        Storage::from("/flash"),
+        Storage::Custom("flash".to_string())
+    );
+    assert_eq!("usb".parse::<Storage>().unwrap(), Storage::Usb);
+}
+
+#[test]
+fn storages_display_their_wire_names() {
+    assert_eq!(Storage::Local.to_string(), "local");
+    assert_eq!(Storage::Usb.to_string(), "usb");
+    assert_eq!(Storage::SdCard.to_string(), "sdcard");
+    assert_eq!(Storage::Custom("flash".to_string()).to_string(), "flash");
+}
+
+#[test]
+fn storages_serialize_as_wire_names() {
+    let json = serde_json::to_string(&[Storage::Usb, Storage::SdCard]).unwrap();
+
+    assert_eq!(json, r#"["usb","sdcard"]"#);
+    assert_eq!(
+        serde_json::from_str::<Vec<Storage>>(&json).unwrap(),
+        [Storage::Usb, Storage::SdCard]
+    );
+}
+
+#[test]
+fn storage_info_matches_by_name_or_path() {
+    let info = StorageInfo {
+        name: "usb".to_string(),
+        kind: "USB".to_string(),
+        path: "/usb".to_string(),
+        read_only: false,
+        free_space: None,
+        total_space: None,
+        available: true,
+    };
+
+    assert_eq!(info.storage(), Storage::Usb);
+    assert!(info.matches(Storage::Usb));
+    assert!(info.matches("/usb"));
+    assert!(!info.matches(Storage::Local));
+}